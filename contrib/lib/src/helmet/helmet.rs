@@ -201,7 +201,7 @@ impl Fairing for SpaceHelmet {
         self.apply(res);
     }
 
-    fn on_launch(&self, rocket: &Rocket) {
+    async fn on_launch(&self, rocket: &Rocket) -> Result<(), ()> {
         if rocket.config().tls_enabled()
             && rocket.figment().profile() != rocket::Config::DEBUG_PROFILE
             && !self.is_enabled::<Hsts>()
@@ -211,5 +211,7 @@ impl Fairing for SpaceHelmet {
             info_!("To disable this warning, configure an HSTS policy.");
             self.force_hsts.store(true, Ordering::Relaxed);
         }
+
+        Ok(())
     }
 }
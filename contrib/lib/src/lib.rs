@@ -23,6 +23,7 @@
 //! * [uuid](uuid) - UUID (de)serialization
 //! * [${database}_pool](databases) - Database Configuration and Pooling
 //! * [helmet](helmet) - Fairing for Security and Privacy Headers
+//! * [websocket](websocket) - WebSocket Connections
 //!
 //! The recommend way to include features from this crate via Rocket in your
 //! project is by adding a `[dependencies.rocket_contrib]` section to your
@@ -65,6 +66,8 @@ pub mod serve;
 pub mod templates;
 #[cfg(feature = "uuid")]
 pub mod uuid;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 #[cfg(feature = "databases")]
 #[doc(hidden)]
@@ -0,0 +1,180 @@
+//! WebSocket support via a request guard and connection-upgrading responder.
+//!
+//! See the [`WebSocket`] type for further details.
+//!
+//! # Enabling
+//!
+//! This module is only available when the `websocket` feature is enabled.
+//! Enable it in `Cargo.toml` as follows:
+//!
+//! ```toml
+//! [dependencies.rocket_contrib]
+//! version = "0.5.0-dev"
+//! default-features = false
+//! features = ["websocket"]
+//! ```
+
+pub extern crate tokio_tungstenite;
+
+use std::future::Future;
+
+use sha1::{Sha1, Digest};
+use rocket::futures::future::{BoxFuture, FutureExt};
+use rocket::http::Status;
+use rocket::request::{Request, Outcome, FromRequest};
+use rocket::response::{self, Response, Responder, Upgrade, Upgraded};
+
+use self::tokio_tungstenite::tungstenite::protocol::Role;
+
+#[doc(inline)]
+pub use self::tokio_tungstenite::tungstenite::Message;
+
+/// The message stream and sink of an established WebSocket connection.
+///
+/// A `WebSocketStream` is a [`Stream`](rocket::futures::stream::Stream) of
+/// incoming [`Message`]s and a [`Sink`](rocket::futures::sink::Sink) for
+/// outgoing ones.
+pub type WebSocketStream = tokio_tungstenite::WebSocketStream<Upgraded>;
+
+/// The GUID appended to a client's key to compute the `Sec-WebSocket-Accept`
+/// handshake response, as specified by RFC 6455.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The only version of the WebSocket protocol, from RFC 6455, that is supported.
+const WEBSOCKET_VERSION: &str = "13";
+
+/// A request guard for WebSocket handshake requests.
+///
+/// A `WebSocket` guard succeeds for requests that ask to upgrade the
+/// connection to a WebSocket. It forwards all other requests, so a route for
+/// the same path without the guard can serve regular requests. A handshake
+/// request for an unsupported version of the WebSocket protocol succeeds, but
+/// its [`Channel`] responds with `426 Upgrade Required` and, as RFC 6455
+/// requires, a `Sec-WebSocket-Version` header naming the supported version
+/// instead of upgrading the connection. A handshake missing its key fails with
+/// a `400 Bad Request` status.
+///
+/// To accept the connection, respond with the [`Channel`] returned by
+/// [`WebSocket::channel()`]. Once the handshake completes, the channel's
+/// handler is called with the connection's [`WebSocketStream`].
+///
+/// # Usage
+///
+/// To use, add the `websocket` feature to the `rocket_contrib` dependencies
+/// section of your `Cargo.toml`:
+///
+/// ```toml
+/// [dependencies.rocket_contrib]
+/// version = "0.5.0-dev"
+/// default-features = false
+/// features = ["websocket"]
+/// ```
+///
+/// A route that echoes every message it receives:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::futures::{SinkExt, StreamExt};
+/// use rocket_contrib::websocket::{WebSocket, Channel};
+///
+/// #[get("/echo")]
+/// fn echo(ws: WebSocket) -> Channel {
+///     ws.channel(|mut stream| async move {
+///         while let Some(Ok(message)) = stream.next().await {
+///             if message.is_text() || message.is_binary() {
+///                 let _ = stream.send(message).await;
+///             }
+///         }
+///     })
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WebSocket {
+    /// The `Sec-WebSocket-Accept` value, or `None` if the client's version of
+    /// the protocol is unsupported.
+    accept: Option<String>,
+}
+
+impl WebSocket {
+    /// Returns a responder that completes the WebSocket handshake and then
+    /// calls `handler` with the connection's [`WebSocketStream`]. The
+    /// connection is closed when the future returned by `handler` completes.
+    pub fn channel<F, Fut>(self, handler: F) -> Channel
+        where F: FnOnce(WebSocketStream) -> Fut + Send + 'static,
+              Fut: Future<Output = ()> + Send + 'static
+    {
+        Channel {
+            accept: self.accept,
+            handler: Box::new(move |stream| handler(stream).boxed()),
+        }
+    }
+}
+
+/// Returns `true` if the comma-separated header `value` contains `token`,
+/// ignoring case.
+fn has_token(value: &str, token: &str) -> bool {
+    value.split(',').any(|t| t.trim().eq_ignore_ascii_case(token))
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for WebSocket {
+    type Error = ();
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, ()> {
+        let headers = request.headers();
+        let is_upgrade = headers.get("Connection").any(|v| has_token(v, "upgrade"));
+        let is_websocket = headers.get("Upgrade").any(|v| has_token(v, "websocket"));
+        if !is_upgrade || !is_websocket {
+            return Outcome::Forward(());
+        }
+
+        if headers.get_one("Sec-WebSocket-Version") != Some(WEBSOCKET_VERSION) {
+            return Outcome::Success(WebSocket { accept: None });
+        }
+
+        let key = match headers.get_one("Sec-WebSocket-Key") {
+            Some(key) => key,
+            None => return Outcome::Failure((Status::BadRequest, ())),
+        };
+
+        let digest = Sha1::digest(format!("{}{}", key, HANDSHAKE_GUID).as_bytes());
+        Outcome::Success(WebSocket { accept: Some(base64::encode(digest)) })
+    }
+}
+
+/// A responder that accepts a WebSocket connection and runs its handler.
+///
+/// A `Channel` is created with [`WebSocket::channel()`]. It responds with
+/// `101 Switching Protocols` and then runs the handler on the upgraded
+/// connection. If the client's version of the WebSocket protocol is
+/// unsupported, it instead responds with `426 Upgrade Required`.
+pub struct Channel {
+    accept: Option<String>,
+    handler: Box<dyn FnOnce(WebSocketStream) -> BoxFuture<'static, ()> + Send>,
+}
+
+#[rocket::async_trait]
+impl Upgrade for Channel {
+    async fn start(self: Box<Self>, io: Upgraded) {
+        let stream = WebSocketStream::from_raw_socket(io, Role::Server, None).await;
+        (self.handler)(stream).await
+    }
+}
+
+impl<'r> Responder<'r, 'static> for Channel {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let accept = match self.accept {
+            Some(ref accept) => accept.clone(),
+            None => return Response::build()
+                .status(Status::UpgradeRequired)
+                .raw_header("Sec-WebSocket-Version", WEBSOCKET_VERSION)
+                .ok(),
+        };
+
+        Response::build()
+            .raw_header("Sec-WebSocket-Accept", accept)
+            .upgrade("websocket", self)
+            .ok()
+    }
+}
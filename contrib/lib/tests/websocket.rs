@@ -0,0 +1,81 @@
+#[macro_use]
+#[cfg(feature = "websocket")]
+extern crate rocket;
+
+#[cfg(feature = "websocket")]
+mod websocket_tests {
+    use rocket::futures::{SinkExt, StreamExt};
+    use rocket::http::{Header, Status};
+    use rocket::local::asynchronous::Client;
+
+    use rocket_contrib::websocket::{WebSocket, Channel, Message};
+    use rocket_contrib::websocket::tokio_tungstenite::WebSocketStream;
+    use rocket_contrib::websocket::tokio_tungstenite::tungstenite::protocol::Role;
+
+    #[get("/echo")]
+    fn echo(ws: WebSocket) -> Channel {
+        ws.channel(|mut stream| async move {
+            while let Some(Ok(message)) = stream.next().await {
+                if message.is_text() || message.is_binary() {
+                    let _ = stream.send(message).await;
+                }
+            }
+        })
+    }
+
+    #[get("/echo", rank = 2)]
+    fn not_websocket() -> &'static str {
+        "Not a WebSocket."
+    }
+
+    async fn client() -> Client {
+        let rocket = rocket::ignite().mount("/", routes![echo, not_websocket]);
+        Client::tracked(rocket).await.unwrap()
+    }
+
+    #[rocket::async_test]
+    async fn test_handshake_and_echo() {
+        let client = client().await;
+        let mut response = client.get("/echo")
+            .header(Header::new("Connection", "keep-alive, Upgrade"))
+            .header(Header::new("Upgrade", "websocket"))
+            .header(Header::new("Sec-WebSocket-Version", "13"))
+            .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .dispatch().await;
+
+        assert_eq!(response.status(), Status::SwitchingProtocols);
+        assert_eq!(response.headers().get_one("Upgrade"), Some("websocket"));
+        assert_eq!(response.headers().get_one("Sec-WebSocket-Accept"),
+            Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+        let io = response.upgrade().expect("upgrade");
+        let mut ws = WebSocketStream::from_raw_socket(io, Role::Client, None).await;
+        ws.send(Message::text("hello")).await.unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::text("hello"));
+        ws.send(Message::binary(vec![1, 2, 3])).await.unwrap();
+        assert_eq!(ws.next().await.unwrap().unwrap(), Message::binary(vec![1, 2, 3]));
+    }
+
+    #[rocket::async_test]
+    async fn test_non_websocket_request_forwards() {
+        let client = client().await;
+        let response = client.get("/echo").dispatch().await;
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().await.unwrap(), "Not a WebSocket.");
+    }
+
+    #[rocket::async_test]
+    async fn test_unsupported_version_requires_upgrade() {
+        let client = client().await;
+        let response = client.get("/echo")
+            .header(Header::new("Connection", "upgrade"))
+            .header(Header::new("Upgrade", "websocket"))
+            .header(Header::new("Sec-WebSocket-Version", "8"))
+            .header(Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="))
+            .dispatch().await;
+
+        assert_eq!(response.status(), Status::UpgradeRequired);
+        assert_eq!(response.headers().get_one("Sec-WebSocket-Version"), Some("13"));
+        assert!(response.headers().get_one("Upgrade").is_none());
+    }
+}
@@ -9,7 +9,7 @@ use indexmap::IndexSet;
 use crate::proc_macro_ext::{Diagnostics, StringLit};
 use crate::syn_ext::{IdentExt, NameSource};
 use crate::proc_macro2::{TokenStream, Span};
-use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, Optional};
+use crate::http_codegen::{Method, MediaType, RoutePath, DataSegment, Optional, Timeout};
 use crate::attribute::segments::{Source, Kind, Segment};
 use crate::syn::{Attribute, parse::Parser};
 
@@ -24,6 +24,7 @@ struct RouteAttribute {
     data: Option<SpanWrapped<DataSegment>>,
     format: Option<MediaType>,
     rank: Option<isize>,
+    timeout: Option<Timeout>,
 }
 
 /// The raw, parsed `#[method]` (e.g, `get`, `put`, `post`, etc.) attribute.
@@ -34,6 +35,7 @@ struct MethodRouteAttribute {
     data: Option<SpanWrapped<DataSegment>>,
     format: Option<MediaType>,
     rank: Option<isize>,
+    timeout: Option<Timeout>,
 }
 
 /// This structure represents the parsed `route` attribute and associated items.
@@ -144,9 +146,14 @@ fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream
         #Outcome::Forward(#data)
     });
 
+    let raw_segment = match seg.part {
+        Some(j) => quote!(#req.raw_segment_part(#i, #j)),
+        None => quote!(#req.raw_segment_str(#i)),
+    };
+
     let expr = match seg.kind {
         Kind::Single => quote_spanned! { span =>
-            match #req.raw_segment_str(#i) {
+            match #raw_segment {
                 #_Some(__s) => match <#ty as #request::FromParam>::from_param(__s) {
                     #_Ok(__v) => __v,
                     #_Err(#error) => return #parse_error,
@@ -163,7 +170,8 @@ fn param_expr(seg: &Segment, ident: &syn::Ident, ty: &syn::Type) -> TokenStream
                 #_None => return #internal_error
             }
         },
-        Kind::Static => return quote!()
+        Kind::Static => return quote!(),
+        Kind::Mixed => unreachable!("mixed segments are split into parameters"),
     };
 
     quote! {
@@ -228,7 +236,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
                 #[allow(non_snake_case)]
                 let mut #trail = #SmallVec::<[#request::FormItem; 8]>::new();
             },
-            Kind::Static => quote!()
+            Kind::Static => quote!(),
+            Kind::Mixed => unreachable!("only path segments may be mixed"),
         };
 
         let name = segment.name.name();
@@ -252,7 +261,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
             },
             Kind::Multi => quote! {
                 _ => #trail.push(__i),
-            }
+            },
+            Kind::Mixed => unreachable!("only path segments may be mixed"),
         };
 
         let builder = match segment.kind {
@@ -276,7 +286,8 @@ fn query_exprs(route: &Route) -> Option<TokenStream> {
                     }
                 };
             },
-            Kind::Static => quote!()
+            Kind::Static => quote!(),
+            Kind::Mixed => unreachable!("only path segments may be mixed"),
         };
 
         decls.push(decl);
@@ -419,6 +430,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
     let path = route.attribute.path.origin.0.to_string();
     let rank = Optional(route.attribute.rank);
     let format = Optional(route.attribute.format);
+    let timeout = Optional(route.attribute.timeout);
 
     Ok(quote! {
         #user_handler_fn
@@ -451,6 +463,7 @@ fn codegen_route(route: Route) -> Result<TokenStream> {
                     handler: monomorphized_function,
                     format: #format,
                     rank: #rank,
+                    timeout: #timeout,
                 }
             }
         }
@@ -514,6 +527,7 @@ fn incomplete_route(
         data: method_attribute.data,
         format: method_attribute.format,
         rank: method_attribute.rank,
+        timeout: method_attribute.timeout,
     };
 
     codegen_route(parse_route(attribute, function)?)
@@ -4,7 +4,7 @@ use devise::{syn, Diagnostic, ext::SpanDiagnosticExt};
 use crate::proc_macro2::Span;
 
 use crate::http::uri::{self, UriPart};
-use crate::http::route::RouteSegment;
+use crate::http::route::{RouteSegment, Piece};
 use crate::proc_macro_ext::{Diagnostics, StringLit, PResult, DResult};
 use crate::syn_ext::NameSource;
 
@@ -17,6 +17,9 @@ pub struct Segment {
     pub source: Source,
     pub name: NameSource,
     pub index: Option<usize>,
+    /// The index of the parameter among the parameters of its path segment if
+    /// the segment mixes static text and parameters, like `<name>.<ext>`.
+    pub part: Option<usize>,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -36,7 +39,42 @@ impl Segment {
         };
 
         let (kind, index) = (segment.kind, segment.index);
-        Segment { span, kind, source, index, name: NameSource::new(&segment.name, span) }
+        let name = NameSource::new(&segment.name, span);
+        Segment { span, kind, source, index, name, part: None }
+    }
+
+    /// Returns a `Single` segment for each parameter in `segment`, a segment
+    /// of kind `Mixed` like `<name>.<ext>`, found in `string` at `span`.
+    fn from_mixed<P: UriPart>(
+        segment: &RouteSegment<'_, P>,
+        string: &str,
+        span: Span
+    ) -> Vec<Segment> {
+        let mut segments = vec![];
+        let mut rest = &*segment.string;
+        for piece in &segment.pieces {
+            if let Piece::Dynamic(name, constraint) = piece {
+                // The parameter's text is its name and `:constraint`, if any.
+                let start = rest.find('<').expect("parameter in mixed segment") + 1;
+                let end = start + name.len() + constraint.as_ref()
+                    .map_or(0, |c| c.as_str().len() + 1);
+
+                let param_span = subspan(&rest[start..(start + name.len())], string, span);
+                let part = segments.len();
+                segments.push(Segment {
+                    span: param_span,
+                    kind: Kind::Single,
+                    source: Source::Path,
+                    name: NameSource::new(name, param_span),
+                    index: segment.index,
+                    part: Some(part),
+                });
+
+                rest = &rest[(end + 1)..];
+            }
+        }
+
+        segments
     }
 
     pub fn is_wild(&self) -> bool {
@@ -46,7 +84,7 @@ impl Segment {
     pub fn is_dynamic(&self) -> bool {
         match self.kind {
             Kind::Static => false,
-            Kind::Single | Kind::Multi => true,
+            Kind::Single | Kind::Multi | Kind::Mixed => true,
         }
     }
 }
@@ -59,6 +97,7 @@ impl From<&syn::Ident> for Segment {
             span: ident.span(),
             name: ident.clone().into(),
             index: None,
+            part: None,
         }
     }
 }
@@ -123,6 +162,24 @@ fn into_diagnostic(
                 .note("components cannot contain reserved characters")
                 .help("reserved characters include: '%', '+', '&', etc.")
         }
+        Error::Adjacent => {
+            seg_span.error(error.to_string())
+                .help("parameters in a segment must be separated, as in '<name>.<ext>'")
+        }
+        Error::PartialMulti => {
+            seg_span.error(error.to_string())
+                .help("a multi-segment param must be the entire segment, as in '<path..>'")
+        }
+        Error::BadConstraint(_) => {
+            seg_span.error(error.to_string())
+                .help("constraints are `int`, `uint`, `alpha`, `alnum`, or a regular expression")
+                .note("a '>' in a constraint closes its parameter unless it's in a class, as in \
+                    '[^>]', or closes a '<' in the constraint")
+        }
+        Error::Unconstrainable => {
+            seg_span.error(error.to_string())
+                .help("constrain a single-segment path parameter, as in '<id:int>'")
+        }
         Error::Trailing(multi) => {
             let multi_span = subspan(multi, source, span);
             trailspan(segment, source, span)
@@ -153,6 +210,9 @@ pub fn parse_segments<P: UriPart>(
 
     for result in <RouteSegment<'_, P>>::parse_many(string) {
         match result {
+            Ok(segment) if segment.kind == Kind::Mixed => {
+                segments.extend(Segment::from_mixed(&segment, string, span));
+            },
             Ok(segment) => {
                 let seg_span = subspan(&segment.string, string, span);
                 segments.push(Segment::from(segment, seg_span));
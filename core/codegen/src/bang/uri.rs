@@ -3,7 +3,7 @@ use std::fmt::Display;
 use devise::{syn, Result, ext::SpanDiagnosticExt};
 
 use crate::http::{uri::{Origin, Path, Query}, ext::IntoOwned};
-use crate::http::route::{RouteSegment, Kind};
+use crate::http::route::{RouteSegment, Kind, Piece};
 use crate::attribute::segments::Source;
 
 use crate::syn::{Expr, Ident, Type, spanned::Spanned};
@@ -150,6 +150,18 @@ fn explode_path<'a, I: Iterator<Item = (&'a Ident, &'a Type, &'a Expr)>>(
                 add_binding(bindings, &ident, &ty, &expr, Source::Path);
                 quote_spanned!(expr.span() => &#ident as &dyn #uri_display)
             }
+            Kind::Mixed => {
+                let pieces = segment.pieces.iter().map(|piece| match piece {
+                    Piece::Static(string) => quote!(&#string as &dyn #uri_display),
+                    Piece::Dynamic(..) => {
+                        let (ident, ty, expr) = items.next().expect("one item for each dyn");
+                        add_binding(bindings, &ident, &ty, &expr, Source::Path);
+                        quote_spanned!(expr.span() => &#ident as &dyn #uri_display)
+                    }
+                });
+
+                quote!(&#uri_mod::UriPathSegment(&[#(#pieces),*]) as &dyn #uri_display)
+            }
         }
     });
 
@@ -198,7 +210,8 @@ fn explode_query<'a, I: Iterator<Item = (&'a Ident, &'a Type, &'a ArgExpr)>>(
             Kind::Multi => quote_spanned! { expr.span() =>
                 #query_arg::Value(&#ident as &dyn #uri_display)
             },
-            Kind::Static => unreachable!("Kind::Static returns early")
+            Kind::Static => unreachable!("Kind::Static returns early"),
+            Kind::Mixed => unreachable!("only path segments may be mixed"),
         })
     });
 
@@ -29,6 +29,9 @@ pub struct DataSegment(pub Segment);
 #[derive(Clone, Debug)]
 pub struct Optional<T>(pub Option<T>);
 
+#[derive(Debug)]
+pub struct Timeout(pub std::time::Duration);
+
 impl FromMeta for StringLit {
     fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
         Ok(StringLit::new(String::from_meta(meta)?, meta.value_span()))
@@ -162,8 +165,17 @@ impl FromMeta for Origin {
         let uri = http::uri::Origin::parse_route(&string)
             .map_err(|e| {
                 let span = string.subspan(e.index() + 1..);
-                span.error(format!("invalid path URI: {}", e))
-                    .help("expected path in origin form: \"/path/<param>\"")
+                let diag = span.error(format!("invalid path URI: {}", e))
+                    .help("expected path in origin form: \"/path/<param>\"");
+
+                // Check if the error is in a constraint, as in `<id:[0-9] >`.
+                let prefix = &string[..e.index()];
+                match prefix.rfind('<').map(|i| &prefix[i..]) {
+                    Some(param) if param.contains(':') && !param.contains('>') => {
+                        diag.note("constraints may only contain visible ASCII characters")
+                    }
+                    _ => diag
+                }
             })?;
 
         if !uri.is_normalized() {
@@ -218,6 +230,29 @@ impl FromMeta for RoutePath {
     }
 }
 
+impl FromMeta for Timeout {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        let string = String::from_meta(meta)?;
+        let span = meta.value_span();
+        let duration = http::private::parse_duration(&string)
+            .map_err(|e| span.error(format!("invalid timeout: {}", e))
+                .help("timeouts are written as, e.g., \"500ms\", \"5s\", or \"1m30s\""))?;
+
+        if duration.as_millis() == 0 {
+            return Err(span.error("timeout must be at least 1ms"));
+        }
+
+        Ok(Timeout(duration))
+    }
+}
+
+impl ToTokens for Timeout {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let millis = self.0.as_millis() as u64;
+        tokens.extend(quote!(::std::time::Duration::from_millis(#millis)));
+    }
+}
+
 impl<T: ToTokens> ToTokens for Optional<T> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         define_vars_and_mods!(_Some, _None);
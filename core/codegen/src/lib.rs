@@ -212,12 +212,14 @@ macro_rules! route_attribute {
         /// parameter := 'rank' '=' INTEGER
         ///            | 'format' '=' '"' MEDIA_TYPE '"'
         ///            | 'data' '=' '"' SINGLE_PARAM '"'
+        ///            | 'timeout' '=' '"' DURATION '"'
         ///
         /// SINGLE_PARAM := '<' IDENT '>'
         /// MULTI_PARAM := '<' IDENT '..>'
         ///
         /// URI_SEG := valid, non-percent-encoded HTTP URI segment
         /// MEDIA_TYPE := valid HTTP media type or known shorthand
+        /// DURATION := non-zero duration: (INTEGER ('ms' | 's' | 'm' | 'h'))+
         ///
         /// INTEGER := unsigned integer, as defined by Rust
         /// IDENT := valid identifier, as defined by Rust, except `_`
@@ -312,8 +314,8 @@ macro_rules! route_attribute {
         ///   2. A static structure used by [`routes!`] to generate a [`Route`].
         ///
         ///      The static structure (and resulting [`Route`]) is populated
-        ///      with the name (the function's name), path, query, rank, and
-        ///      format from the route attribute. The handler is set to the
+        ///      with the name (the function's name), path, query, rank, format,
+        ///      and timeout from the route attribute. The handler is set to the
         ///      generated handler.
         ///
         ///   3. A macro used by [`uri!`] to type-check and generate an
@@ -0,0 +1,89 @@
+#[macro_use] extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::{Request, State};
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::request::{FromRequest, Outcome};
+
+/// Counts the number of times it's run.
+struct Counted;
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Counted {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> Outcome<Self, ()> {
+        let counter = req.guard::<State<'_, AtomicUsize>>().await.unwrap();
+        counter.fetch_add(1, Ordering::SeqCst);
+        Outcome::Success(Counted)
+    }
+}
+
+#[get("/user/<id:int>")]
+fn user_by_id(id: i64, _counted: Counted) -> String {
+    format!("id: {}", id)
+}
+
+#[get("/user/<name:[a-z][a-z-]*>")]
+fn user_by_name(name: String) -> String {
+    format!("name: {}", name)
+}
+
+#[get("/user/new")]
+fn new_user() -> &'static str {
+    "new"
+}
+
+#[get("/file/<name>.<ext:alpha>")]
+fn file(name: String, ext: String) -> String {
+    format!("file: {} {}", name, ext)
+}
+
+#[get("/doc/<year:[0-9]{4}>/<name:[^.>]+>.<ext:(?:txt|md)>?<v>")]
+fn doc(year: u16, name: String, ext: String, v: u8) -> String {
+    format!("doc: {} {} {} v{}", year, name, ext, v)
+}
+
+fn get_string(client: &Client, url: &str) -> String {
+    client.get(url).dispatch().into_string().unwrap()
+}
+
+fn get_status(client: &Client, url: &str) -> Status {
+    client.get(url).dispatch().status()
+}
+
+#[test]
+fn test_constrained_params() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![user_by_id, user_by_name, new_user, file, doc])
+        .manage(AtomicUsize::new(0));
+
+    let client = Client::untracked(rocket).unwrap();
+    assert_eq!(get_string(&client, "/user/10"), "id: 10");
+    assert_eq!(get_string(&client, "/user/-10"), "id: -10");
+    assert_eq!(get_string(&client, "/user/bob-smith"), "name: bob-smith");
+    assert_eq!(get_string(&client, "/user/new"), "new");
+    assert_eq!(get_string(&client, "/file/a.1.txt"), "file: a.1 txt");
+    assert_eq!(get_string(&client, "/doc/2020/a-b.md?v=2"), "doc: 2020 a-b md v2");
+
+    assert_eq!(get_status(&client, "/user/Bob"), Status::NotFound);
+    assert_eq!(get_status(&client, "/user/10a"), Status::NotFound);
+    assert_eq!(get_status(&client, "/file/a.txt.1"), Status::NotFound);
+    assert_eq!(get_status(&client, "/doc/20/a.md?v=2"), Status::NotFound);
+    assert_eq!(get_status(&client, "/doc/2020/a.b.md?v=2"), Status::NotFound);
+    assert_eq!(get_status(&client, "/doc/2020/a.rs?v=2"), Status::NotFound);
+
+    // Requests that don't satisfy the constraint never reach the guards.
+    let counter = client.rocket().state::<AtomicUsize>().unwrap();
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_constrained_uris() {
+    assert_eq!(uri!(user_by_id: 10).to_string(), "/user/10");
+    assert_eq!(uri!(user_by_name: "bob").to_string(), "/user/bob");
+    assert_eq!(uri!(file: "a", "txt").to_string(), "/file/a.txt");
+    assert_eq!(uri!(doc: 2020, "a", "md", 2).to_string(), "/doc/2020/a.md?v=2");
+}
@@ -0,0 +1,65 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+
+#[get("/<name>.<ext>", rank = 2)]
+fn file(name: String, ext: String) -> String {
+    format!("file: {} {}", name, ext)
+}
+
+#[get("/<id>.json")]
+fn json(id: usize) -> String {
+    format!("json: {}", id)
+}
+
+#[get("/v<major>.<minor>/<path>")]
+fn versioned(major: u8, minor: u8, path: String) -> String {
+    format!("v{}.{}: {}", major, minor, path)
+}
+
+#[get("/page/<_>-<n>")]
+fn page(n: usize) -> String {
+    format!("page: {}", n)
+}
+
+fn get_string(client: &Client, url: &str) -> String {
+    client.get(url).dispatch().into_string().unwrap()
+}
+
+fn get_status(client: &Client, url: &str) -> Status {
+    client.get(url).dispatch().status()
+}
+
+#[test]
+fn test_partial_segment_params() {
+    let rocket = rocket::ignite().mount("/", routes![file, json, versioned, page]);
+    let client = Client::untracked(rocket).unwrap();
+
+    assert_eq!(get_string(&client, "/hello.txt"), "file: hello txt");
+    assert_eq!(get_string(&client, "/a.tar.gz"), "file: a.tar gz");
+    assert_eq!(get_string(&client, "/a%20b.txt"), "file: a b txt");
+    assert_eq!(get_string(&client, "/10.json"), "json: 10");
+    assert_eq!(get_string(&client, "/ten.json"), "file: ten json");
+
+    assert_eq!(get_string(&client, "/v1.2/a"), "v1.2: a");
+    assert_eq!(get_string(&client, "/v10.20/b"), "v10.20: b");
+    assert_eq!(get_string(&client, "/page/x-3"), "page: 3");
+    assert_eq!(get_string(&client, "/page/x-y-3"), "page: 3");
+
+    assert_eq!(get_status(&client, "/hello"), Status::NotFound);
+    assert_eq!(get_status(&client, "/.txt"), Status::NotFound);
+    assert_eq!(get_status(&client, "/v1/a"), Status::NotFound);
+    assert_eq!(get_status(&client, "/vx.2/a"), Status::NotFound);
+    assert_eq!(get_status(&client, "/page/3"), Status::NotFound);
+}
+
+#[test]
+fn test_partial_segment_uris() {
+    assert_eq!(uri!(file: "hello", "txt").to_string(), "/hello.txt");
+    assert_eq!(uri!(file: name = "a b", ext = "txt").to_string(), "/a%20b.txt");
+    assert_eq!(uri!(file: "a/b", "c").to_string(), "/a%2Fb.c");
+    assert_eq!(uri!(json: 10).to_string(), "/10.json");
+    assert_eq!(uri!(versioned: 1, 2, "a").to_string(), "/v1.2/a");
+    assert_eq!(uri!("/api", versioned: 1, 2, "a").to_string(), "/api/v1.2/a");
+}
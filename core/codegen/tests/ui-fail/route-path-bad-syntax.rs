@@ -66,7 +66,7 @@ fn i1() {}
 #[get("/<!>")]
 fn i2() {}
 
-#[get("/<name>:<id>")]
+#[get("/<name><id>")]
 fn i3() {}
 
 // Check that a data parameter is exactly `<param>`
@@ -102,4 +102,24 @@ fn m2() {}
 #[get("/<>name><")]
 fn m3() {}
 
+// Check that parameter constraints are valid.
+
+#[get("/<id:[a-z>")]
+fn n0() {}
+
+#[get("/<path..:int>")]
+fn n1() {}
+
+#[get("/?<id:int>")]
+fn n2() {}
+
+#[get("/<a:x<y>")]
+fn n3() {}
+
+#[get("/a[b]")]
+fn n4() {}
+
+#[get("/<a:x y>")]
+fn n5() {}
+
 fn main() {  }
@@ -0,0 +1,258 @@
+//! Adoption of sockets passed via systemd socket activation.
+//!
+//! A service manager that activates a service by socket binds the sockets
+//! itself and passes them to the service as the file descriptors starting at
+//! `3`. The number of descriptors is set in `LISTEN_FDS`, the process they are
+//! intended for in `LISTEN_PID`, and, optionally, a colon-separated name for
+//! each in `LISTEN_FDNAMES`. See `sd_listen_fds(3)` for details.
+
+use std::{env, io};
+use std::os::unix::io::{FromRawFd, RawFd};
+
+use socket2::{Domain, Socket};
+use tokio::net::{TcpListener, UnixListener};
+
+/// The first file descriptor passed via socket activation.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// The environment variables that describe passed sockets.
+const LISTEN_VARS: &[&str] = &["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"];
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("socket activation: {}", msg))
+}
+
+/// A listener adopted from a socket passed via socket activation.
+pub enum ActivatedListener {
+    /// A TCP socket.
+    Tcp(TcpListener),
+    /// A Unix domain socket.
+    Unix(UnixListener),
+}
+
+/// A socket passed via socket activation.
+pub struct ActivatedSocket {
+    /// The name assigned to the socket, such as with systemd's
+    /// `FileDescriptorName=`, if any.
+    pub name: Option<String>,
+    /// The listener adopted from the socket.
+    pub listener: ActivatedListener,
+}
+
+/// Adopts the sockets passed to this process via socket activation.
+///
+/// Returns `None` if `LISTEN_PID` is unset or names another process, which
+/// happens when the variables were inherited from a parent that was itself
+/// activated. The variables are removed from the environment either way so
+/// that child processes don't adopt the sockets as well.
+///
+/// Each socket is adopted at most once: the caller takes ownership of every
+/// passed descriptor, which is closed when its listener is dropped.
+pub fn activated_sockets() -> io::Result<Option<Vec<ActivatedSocket>>> {
+    let vars = LISTEN_VARS.iter().map(|var| env::var(var).ok()).collect::<Vec<_>>();
+    for var in LISTEN_VARS {
+        env::remove_var(var);
+    }
+
+    // SAFETY: The service manager passed ownership of the descriptors from
+    // `LISTEN_FDS_START` to this process, and the environment was cleared
+    // above, so each descriptor is adopted only once.
+    let (pid, fds, names) = (vars[0].as_deref(), vars[1].as_deref(), vars[2].as_deref());
+    unsafe { adopt_passed(pid, fds, names, LISTEN_FDS_START) }
+}
+
+/// Adopts the sockets described by the values of `LISTEN_PID`, `LISTEN_FDS`
+/// and `LISTEN_FDNAMES`, numbered from `start`, as `activated_sockets()` does.
+///
+/// # Safety
+///
+/// If `pid` names this process, the caller must own the `LISTEN_FDS`
+/// descriptors from `start` and must not use them after this call.
+unsafe fn adopt_passed(
+    pid: Option<&str>,
+    fds: Option<&str>,
+    names: Option<&str>,
+    start: RawFd,
+) -> io::Result<Option<Vec<ActivatedSocket>>> {
+    let pid = match pid {
+        Some(pid) => pid.trim().parse::<u32>()
+            .map_err(|_| invalid(format!("invalid LISTEN_PID `{}`", pid)))?,
+        None => return Ok(None),
+    };
+
+    if pid != std::process::id() {
+        return Ok(None);
+    }
+
+    let fds = fds.ok_or_else(|| invalid("LISTEN_PID is set but LISTEN_FDS isn't".into()))?;
+
+    let count = fds.trim().parse::<RawFd>()
+        .ok()
+        .filter(|&count| count >= 0)
+        .ok_or_else(|| invalid(format!("invalid LISTEN_FDS `{}`", fds)))?;
+
+    let names = names.map(|names| names.split(':').map(String::from).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    (0..count)
+        .map(|i| {
+            let fd = start + i;
+            let name = names.get(i as usize).filter(|name| !name.is_empty()).cloned();
+            let socket = Socket::from_raw_fd(fd);
+            Ok(ActivatedSocket { name, listener: adopt(fd, socket)? })
+        })
+        .collect::<io::Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Adopts `socket`, passed as `fd`, as a TCP or Unix domain socket listener.
+/// The socket must be a listening stream socket. It is marked close-on-exec so
+/// that it isn't leaked to child processes.
+fn adopt(fd: RawFd, socket: Socket) -> io::Result<ActivatedListener> {
+    let addr = socket.local_addr()
+        .map_err(|e| invalid(format!("descriptor {} is not a socket: {}", fd, e)))?;
+
+    let not_listening = || {
+        invalid(format!("descriptor {} is not a listening TCP or Unix domain socket", fd))
+    };
+
+    if socket_option(fd, libc::SO_TYPE)? != libc::SOCK_STREAM
+        || socket_option(fd, libc::SO_ACCEPTCONN)? == 0
+    {
+        return Err(not_listening());
+    }
+
+    set_cloexec(fd)?;
+    socket.set_nonblocking(true)?;
+    if addr.as_std().is_some() {
+        Ok(ActivatedListener::Tcp(TcpListener::from_std(socket.into_tcp_listener())?))
+    } else if i32::from(addr.family()) == i32::from(Domain::unix()) {
+        Ok(ActivatedListener::Unix(UnixListener::from_std(socket.into_unix_listener())?))
+    } else {
+        Err(not_listening())
+    }
+}
+
+/// Returns the value of the integer, `SOL_SOCKET`-level option `option` of the
+/// socket `fd`.
+fn socket_option(fd: RawFd, option: libc::c_int) -> io::Result<libc::c_int> {
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let ptr = &mut value as *mut libc::c_int as *mut libc::c_void;
+
+    // SAFETY: `ptr` and `len` describe `value`, which is valid for writes.
+    match unsafe { libc::getsockopt(fd, libc::SOL_SOCKET, option, ptr, &mut len) } {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok(value),
+    }
+}
+
+/// Sets the close-on-exec flag of `fd`.
+fn set_cloexec(fd: RawFd) -> io::Result<()> {
+    // SAFETY: `F_GETFD` and `F_SETFD` only read and write the flags of `fd`.
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFD);
+        if flags == -1 || libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+
+    use super::{adopt_passed, ActivatedListener};
+
+    /// Moves `socket` to the descriptor `fd`, as a service manager would.
+    fn pass<S: IntoRawFd>(socket: S, fd: RawFd) {
+        let original = socket.into_raw_fd();
+        unsafe {
+            assert_eq!(libc::dup2(original, fd), fd);
+            libc::close(original);
+        }
+    }
+
+    fn cloexec(fd: RawFd) -> bool {
+        unsafe { libc::fcntl(fd, libc::F_GETFD) & libc::FD_CLOEXEC != 0 }
+    }
+
+
+    #[test]
+    fn test_other_pid_is_ignored() {
+        let other = (std::process::id() + 1).to_string();
+        let sockets = unsafe { adopt_passed(Some(&other), Some("1"), None, 200) };
+        assert!(sockets.unwrap().is_none());
+
+        let sockets = unsafe { adopt_passed(None, Some("1"), None, 200) };
+        assert!(sockets.unwrap().is_none());
+
+        assert!(unsafe { adopt_passed(Some("abc"), Some("1"), None, 200) }.is_err());
+    }
+
+    #[test]
+    fn test_missing_or_invalid_fds() {
+        let pid = std::process::id().to_string();
+        for fds in &[None, Some("abc"), Some("-1"), Some("")] {
+            let sockets = unsafe { adopt_passed(Some(&pid), *fds, None, 200) };
+            assert!(sockets.is_err(), "{:?}", fds);
+        }
+
+        let sockets = unsafe { adopt_passed(Some(&pid), Some("0"), None, 200) };
+        assert!(sockets.unwrap().unwrap().is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn test_names_and_cloexec() {
+        let pid = std::process::id().to_string();
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let tcp_addr = tcp.local_addr().unwrap();
+        let path = std::env::temp_dir().join(format!("rocket-activation-{}.sock", pid));
+        let _ = std::fs::remove_file(&path);
+        let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        pass(tcp, 210);
+        pass(unix, 211);
+        let sockets = unsafe { adopt_passed(Some(&pid), Some("2"), Some("web:"), 210) };
+        let sockets = sockets.unwrap().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[0].name.as_deref(), Some("web"));
+        assert_eq!(sockets[1].name, None);
+        match sockets[0].listener {
+            ActivatedListener::Tcp(ref l) => {
+                assert_eq!(l.local_addr().unwrap(), tcp_addr);
+                assert!(cloexec(l.as_raw_fd()));
+            }
+            _ => panic!("expected a TCP listener"),
+        }
+
+        match sockets[1].listener {
+            ActivatedListener::Unix(ref l) => assert!(cloexec(l.as_raw_fd())),
+            _ => panic!("expected a Unix domain socket listener"),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn test_non_listening_sockets_are_rejected() {
+        use socket2::{Socket, Domain, Type};
+
+        let pid = std::process::id().to_string();
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        pass(udp, 220);
+        assert!(unsafe { adopt_passed(Some(&pid), Some("1"), None, 220) }.is_err());
+
+        let (stream, _) = std::os::unix::net::UnixStream::pair().unwrap();
+        pass(stream, 221);
+        assert!(unsafe { adopt_passed(Some(&pid), Some("1"), None, 221) }.is_err());
+
+        let tcp = Socket::new(Domain::ipv4(), Type::stream(), None).unwrap();
+        let addr: std::net::SocketAddr = "127.0.0.1:0".parse().unwrap();
+        tcp.bind(&addr.into()).unwrap();
+        pass(tcp, 222);
+        assert!(unsafe { adopt_passed(Some(&pid), Some("1"), None, 222) }.is_err());
+    }
+}
@@ -39,6 +39,8 @@ mod accept;
 mod raw_str;
 mod parse;
 mod listener;
+mod proxy;
+#[cfg(unix)] mod activation;
 
 /// Case-preserving, ASCII case-insensitive string types.
 ///
@@ -52,7 +54,7 @@ pub mod uncased {
 // Types that we expose for use by core.
 #[doc(hidden)]
 pub mod private {
-    pub use crate::parse::Indexed;
+    pub use crate::parse::{Indexed, parse_duration};
     pub use smallvec::{SmallVec, Array};
 
     pub mod cookie {
@@ -60,7 +62,14 @@ pub mod private {
         pub use crate::cookies::Key;
     }
 
-    pub use crate::listener::{Incoming, Listener, Connection, bind_tcp};
+    pub use crate::listener::{Incoming, Listener, Connection, ProxyListener, bind_tcp};
+    pub use crate::listener::{MultiListener, AnyConnection, ProtocolSniffer};
+    pub use crate::proxy::ProxiedStream;
+
+    #[cfg(unix)]
+    pub use crate::listener::bind_unix;
+    #[cfg(unix)]
+    pub use crate::activation::{activated_sockets, ActivatedSocket, ActivatedListener};
 }
 
 pub use crate::method::Method;
@@ -71,3 +80,4 @@ pub use crate::header::{Header, HeaderMap};
 pub use crate::raw_str::RawStr;
 pub use crate::media_type::MediaType;
 pub use crate::cookies::{Cookie, CookieJar, SameSite};
+pub use crate::listener::{Listener, Connection, RawCertificate};
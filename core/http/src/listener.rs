@@ -3,35 +3,84 @@ use std::future::Future;
 use std::io;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
 use hyper::server::accept::Accept;
 
-use log::{debug, error};
+use log::{debug, error, warn};
 
 use tokio::time::Delay;
-use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 
-// TODO.async: 'Listener' and 'Connection' provide common enough functionality
-// that they could be introduced in upstream libraries.
-/// A 'Listener' yields incoming connections
+use crate::proxy::ProxiedStream;
+
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+/// A source of incoming connections that Rocket can serve on.
+///
+/// `Listener` is implemented for [`tokio::net::TcpListener`] and, on Unix
+/// platforms, [`tokio::net::UnixListener`]. It can be implemented for any other
+/// transport, such as a socket inherited from a parent process or an in-memory
+/// transport in tests, and then passed to `Rocket::launch_on()` to serve on it.
 pub trait Listener {
+    /// The type of connection yielded by this listener.
     type Connection: Connection;
 
-    /// Return the actual address this listener bound to.
+    /// Returns the address this listener is bound to, if it has one.
+    ///
+    /// Rocket uses this address to report where it launched from and to set
+    /// the `port` in its configuration. Listeners without a network address,
+    /// such as Unix domain sockets, return `None`.
     fn local_addr(&self) -> Option<SocketAddr>;
 
-    /// Try to accept an incoming Connection if ready
+    /// Attempts to accept an incoming connection.
+    ///
+    /// Returns `Poll::Pending` if no connection is ready and arranges for the
+    /// current task to be woken when one is. Errors are considered fatal
+    /// unless they are per-connection errors such as `ConnectionReset`.
     fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>>;
 }
 
-/// A 'Connection' represents an open connection to a client
+/// An open connection to a client, yielded by a [`Listener`].
 pub trait Connection: AsyncRead + AsyncWrite {
+    /// Return the address of the remote peer, if it is known. Connections
+    /// without a network address, such as Unix domain sockets, return `None`.
     fn remote_addr(&self) -> Option<SocketAddr>;
+
+    /// Return the local address the connection was accepted on, if it is
+    /// known. Rocket uses this address to determine the endpoint a request
+    /// arrived on when it serves on several.
+    fn local_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Return the certificate chain presented and verified by the remote peer,
+    /// end-entity certificate first, if there is one. Only TLS connections
+    /// with client authentication enabled can return `Some`.
+    fn peer_certificates(&self) -> Option<Vec<RawCertificate>> {
+        None
+    }
+
+    /// Return the server name the remote peer requested via TLS SNI, if any.
+    /// Only TLS connections can return `Some`.
+    fn server_name(&self) -> Option<String> {
+        None
+    }
 }
 
+/// A DER-encoded X.509 certificate presented by the peer of a [`Connection`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawCertificate(pub Vec<u8>);
+
 /// This is a generic version of hyper's AddrIncoming that is intended to be
 /// usable with listeners other than a plain TCP stream, e.g. TLS and/or Unix
 /// sockets. It does so by bridging the `Listener` trait to what hyper wants (an
@@ -41,18 +90,111 @@ pub struct Incoming<L> {
     listener: L,
     sleep_on_errors: Option<Duration>,
     pending_error_delay: Option<Delay>,
+    connection_limit: Option<ConnectionLimit>,
+}
+
+/// Rejects connections beyond `max` open connections with `rejection`.
+struct ConnectionLimit {
+    max: usize,
+    open: Arc<AtomicUsize>,
+    rejection: Arc<[u8]>,
 }
 
-impl<L: Listener> Incoming<L> {
+/// How long a rejected connection is given to reveal its protocol.
+const REJECTION_SNIFF_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The connection preface an HTTP/2 client sends before anything else.
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Determines, from the first bytes a client sends, whether a connection
+/// speaks HTTP/1 or HTTP/2.
+#[derive(Debug, Default)]
+pub struct ProtocolSniffer {
+    /// Whether the connection speaks HTTP/1, once it's known.
+    http1: Option<bool>,
+    /// The number of bytes of the HTTP/2 preface received so far.
+    preface: usize,
+}
+
+impl ProtocolSniffer {
+    /// Records that `bytes` were received. Returns whether the connection
+    /// speaks HTTP/1 if that is known after `bytes`.
+    pub fn observe(&mut self, bytes: &[u8]) -> Option<bool> {
+        if self.http1.is_none() && !bytes.is_empty() {
+            let expected = &H2_PREFACE[self.preface..];
+            let n = std::cmp::min(expected.len(), bytes.len());
+            if expected[..n] != bytes[..n] {
+                self.http1 = Some(true);
+            } else {
+                self.preface += n;
+                if self.preface == H2_PREFACE.len() {
+                    self.http1 = Some(false);
+                }
+            }
+        }
+
+        self.http1
+    }
+
+    /// Whether the connection speaks HTTP/1, if that is known yet.
+    pub fn http1(&self) -> Option<bool> {
+        self.http1
+    }
+}
+
+/// Writes `rejection` to `stream` if it turns out to be an HTTP/1 connection
+/// and closes it. HTTP/2 connections and connections that send nothing within
+/// `REJECTION_SNIFF_TIMEOUT` are closed without a response: to them, the
+/// HTTP/1 response would be garbage.
+async fn reject<C: AsyncRead + AsyncWrite + Unpin>(mut stream: C, rejection: Arc<[u8]>) {
+    let mut sniffer = ProtocolSniffer::default();
+    let sniff = async {
+        let mut buf = [0; 64];
+        loop {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => return None,
+                Ok(n) => if let Some(http1) = sniffer.observe(&buf[..n]) {
+                    return Some(http1);
+                }
+            }
+        }
+    };
+
+    if let Ok(Some(true)) = tokio::time::timeout(REJECTION_SNIFF_TIMEOUT, sniff).await {
+        let _ = stream.write_all(&rejection).await;
+    }
+
+    let _ = stream.shutdown().await;
+}
+
+impl<L: Listener> Incoming<L> where L::Connection: Send + Unpin + 'static {
     /// Construct an `Incoming` from an existing `Listener`.
     pub fn from_listener(listener: L) -> Self {
         Self {
             listener,
             sleep_on_errors: Some(Duration::from_secs(1)),
             pending_error_delay: None,
+            connection_limit: None,
         }
     }
 
+    /// Limit the number of open connections to `max`.
+    ///
+    /// `open` must count the connections yielded by the listener that are
+    /// still open, including one that was just accepted. A connection
+    /// accepted while more than `max` are open is not yielded: it is closed
+    /// and, if its first bytes show it to be an HTTP/1 connection, `rejection`
+    /// is first written to it verbatim. HTTP/2 connections are closed without
+    /// a response.
+    pub fn set_connection_limit(
+        &mut self,
+        max: usize,
+        open: Arc<AtomicUsize>,
+        rejection: Arc<[u8]>
+    ) {
+        self.connection_limit = Some(ConnectionLimit { max, open, rejection });
+    }
+
     /// Set whether to sleep on accept errors.
     ///
     /// A possible scenario is that the process has hit the max open files
@@ -84,8 +226,14 @@ impl<L: Listener> Incoming<L> {
 
         loop {
             match self.listener.poll_accept(cx) {
-                Poll::Ready(Ok(stream)) => {
-                    return Poll::Ready(Ok(stream));
+                Poll::Ready(Ok(stream)) => match self.connection_limit {
+                    Some(ref limit) if limit.open.load(Ordering::Acquire) > limit.max => {
+                        warn!("connection limit of {} reached: rejecting connection", limit.max);
+                        tokio::spawn(reject(stream, limit.rejection.clone()));
+
+                        continue;
+                    }
+                    _ => return Poll::Ready(Ok(stream)),
                 },
                 Poll::Pending => return Poll::Pending,
                 Poll::Ready(Err(e)) => {
@@ -121,7 +269,9 @@ impl<L: Listener> Incoming<L> {
     }
 }
 
-impl<L: Listener + Unpin> Accept for Incoming<L> {
+impl<L: Listener + Unpin> Accept for Incoming<L>
+    where L::Connection: Send + Unpin + 'static
+{
     type Conn = L::Connection;
     type Error = io::Error;
 
@@ -157,8 +307,28 @@ impl<L: fmt::Debug> fmt::Debug for Incoming<L> {
     }
 }
 
-pub async fn bind_tcp(address: SocketAddr) -> io::Result<TcpListener> {
-    Ok(TcpListener::bind(address).await?)
+/// Binds a TCP listener to `address`.
+///
+/// If `only_v6` is `true` and `address` is an IPv6 address, the listener only
+/// accepts IPv6 connections, allowing an IPv4 listener to bind to the same
+/// port. Otherwise, whether an IPv6 listener also accepts IPv4 connections is
+/// left to the platform.
+pub async fn bind_tcp(address: SocketAddr, only_v6: bool) -> io::Result<TcpListener> {
+    if !only_v6 || address.is_ipv4() {
+        return Ok(TcpListener::bind(address).await?);
+    }
+
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let socket = Socket::new(Domain::ipv6(), Type::stream(), Some(Protocol::tcp()))?;
+    socket.set_only_v6(true)?;
+    #[cfg(unix)] { socket.set_reuse_address(true)?; }
+    socket.bind(&address.into())?;
+    socket.listen(1024)?;
+
+    let listener = socket.into_tcp_listener();
+    listener.set_nonblocking(true)?;
+    TcpListener::from_std(listener)
 }
 
 impl Listener for TcpListener {
@@ -177,4 +347,261 @@ impl Connection for TcpStream {
     fn remote_addr(&self) -> Option<SocketAddr> {
         self.peer_addr().ok()
     }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        TcpStream::local_addr(self).ok()
+    }
+}
+
+/// A TCP listener that decodes a PROXY protocol header at the start of each
+/// connection, reporting the original client as the connection's remote
+/// address.
+///
+/// Connections are decoded concurrently, apart from accepting new ones, so a
+/// slow client can't hold up others; a connection is yielded as soon as its
+/// header is decoded. A connection with an invalid header, without one when a
+/// header is `required`, or that doesn't deliver its header within five
+/// seconds is logged and closed.
+pub struct ProxyListener {
+    listener: TcpListener,
+    required: bool,
+    decoding: FuturesUnordered<BoxFuture<'static, io::Result<ProxiedStream>>>,
+}
+
+impl ProxyListener {
+    /// Decodes PROXY protocol headers on the connections accepted by
+    /// `listener`. Connections without a header are rejected if `required`.
+    pub fn new(listener: TcpListener, required: bool) -> ProxyListener {
+        ProxyListener { listener, required, decoding: FuturesUnordered::new() }
+    }
+}
+
+impl Listener for ProxyListener {
+    type Connection = ProxiedStream;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        let required = self.required;
+        poll_pending(&mut self.listener, &mut self.decoding, cx, |stream| {
+            Box::pin(ProxiedStream::accept(stream, required))
+        })
+    }
+}
+
+/// Accepts every connection that's ready on `listener`, adding the future that
+/// prepares it, made by `prepare`, to `pending`, then returns the first
+/// connection in `pending` that's ready. Connections that fail to be prepared
+/// are logged and dropped so that a misbehaving client can't fail the listener.
+pub(crate) fn poll_pending<C, F>(
+    listener: &mut TcpListener,
+    pending: &mut FuturesUnordered<BoxFuture<'static, io::Result<C>>>,
+    cx: &mut Context<'_>,
+    mut prepare: F,
+) -> Poll<io::Result<C>>
+    where F: FnMut(TcpStream) -> BoxFuture<'static, io::Result<C>>
+{
+    loop {
+        match listener.poll_accept(cx) {
+            Poll::Ready(Ok((stream, _addr))) => pending.push(prepare(stream)),
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => break,
+        }
+    }
+
+    loop {
+        match pending.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(conn))) => return Poll::Ready(Ok(conn)),
+            Poll::Ready(Some(Err(e))) => warn!("rejecting connection: {}", e),
+            Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+trait AnyListener: Send {
+    fn local_addr(&self) -> Option<SocketAddr>;
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<AnyConnection>>;
+}
+
+impl<L: Listener + Send> AnyListener for L where L::Connection: Send + Unpin + 'static {
+    fn local_addr(&self) -> Option<SocketAddr> {
+        Listener::local_addr(self)
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<AnyConnection>> {
+        Listener::poll_accept(self, cx).map_ok(|conn| AnyConnection(Box::new(conn)))
+    }
+}
+
+/// A connection accepted by one of the listeners of a [`MultiListener`].
+pub struct AnyConnection(Box<dyn Connection + Send + Unpin>);
+
+/// A listener that accepts connections from several listeners at once.
+///
+/// The listeners are polled in turn, starting with the one after the listener
+/// that last yielded a connection, so that a busy listener can't starve the
+/// others. An error from any listener is returned as-is.
+#[derive(Default)]
+pub struct MultiListener {
+    listeners: Vec<Box<dyn AnyListener>>,
+    next: usize,
+}
+
+impl MultiListener {
+    /// Returns a `MultiListener` without any listeners.
+    pub fn new() -> MultiListener {
+        MultiListener::default()
+    }
+
+    /// Adds `listener` to the set of listeners accepted from.
+    pub fn push<L>(&mut self, listener: L)
+        where L: Listener + Send + 'static, L::Connection: Send + Unpin + 'static
+    {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Returns the local address of each listener, in the order they were
+    /// added.
+    pub fn local_addrs(&self) -> Vec<Option<SocketAddr>> {
+        self.listeners.iter().map(|l| l.local_addr()).collect()
+    }
+}
+
+impl Listener for MultiListener {
+    type Connection = AnyConnection;
+
+    /// Returns the address of the first listener.
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listeners.first().and_then(|l| l.local_addr())
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        let n = self.listeners.len();
+        for i in 0..n {
+            let index = (self.next + i) % n;
+            if let Poll::Ready(result) = self.listeners[index].poll_accept(cx) {
+                self.next = (index + 1) % n;
+                return Poll::Ready(result);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl AsyncRead for AnyConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for AnyConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_shutdown(cx)
+    }
+}
+
+impl Connection for AnyConnection {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.0.remote_addr()
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.0.local_addr()
+    }
+
+    fn peer_certificates(&self) -> Option<Vec<RawCertificate>> {
+        self.0.peer_certificates()
+    }
+
+    fn server_name(&self) -> Option<String> {
+        self.0.server_name()
+    }
+}
+
+/// Binds a Unix domain socket listener to `path`.
+///
+/// If a socket file already exists at `path` but nothing is accepting
+/// connections on it, it is considered stale, left behind by a previous
+/// process, and is removed before binding. A live socket or a file that isn't
+/// a socket results in an error. If `permissions` is `Some`, the mode of the
+/// socket file is set to the given value after binding.
+#[cfg(unix)]
+pub async fn bind_unix(path: &Path, permissions: Option<u32>) -> io::Result<UnixListener> {
+    use std::fs;
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            let msg = format!("`{}` exists and is not a socket", path.display());
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, msg));
+        }
+
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => {
+                let msg = format!("socket `{}` is in use", path.display());
+                return Err(io::Error::new(io::ErrorKind::AddrInUse, msg));
+            }
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                debug!("removing stale socket `{}`", path.display());
+                fs::remove_file(path)?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let listener = UnixListener::bind(path)?;
+    if let Some(mode) = permissions {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
+
+    Ok(listener)
+}
+
+#[cfg(unix)]
+impl Listener for UnixListener {
+    type Connection = UnixStream;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        use tokio::stream::Stream;
+
+        match Pin::new(self).poll_next(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            Poll::Ready(None) => {
+                let msg = "unix listener unexpectedly closed";
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, msg)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Connection for UnixStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        None
+    }
 }
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+/// Parses a duration written as a sequence of integers, each followed by a
+/// unit: `ms`, `s`, `m`, or `h`. For example, `500ms`, `5s`, and `1m30s`. An
+/// integer without a unit is interpreted as seconds.
+pub fn parse_duration(string: &str) -> Result<Duration, &'static str> {
+    let string = string.trim();
+    if string.is_empty() {
+        return Err("duration is empty");
+    }
+
+    if let Ok(secs) = string.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = Duration::from_secs(0);
+    let mut rest = string;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return Err("expected an integer before the unit");
+        }
+
+        let n: u64 = rest[..digits].parse().map_err(|_| "duration is too large")?;
+        rest = &rest[digits..];
+
+        let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let secs = |factor: u64| n.checked_mul(factor).map(Duration::from_secs);
+        let duration = match &rest[..unit_len] {
+            "ms" => Some(Duration::from_millis(n)),
+            "s" => secs(1),
+            "m" => secs(60),
+            "h" => secs(60 * 60),
+            _ => return Err("unknown unit: expected `ms`, `s`, `m`, or `h`"),
+        };
+
+        total = duration.and_then(|d| total.checked_add(d)).ok_or("duration is too large")?;
+        rest = &rest[unit_len..];
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use super::parse_duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration(" 1s250ms "), Ok(Duration::from_millis(1250)));
+
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("5 s").is_err());
+        assert!(parse_duration("5sec").is_err());
+        assert!(parse_duration("1.5s").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("18446744073709551615h").is_err());
+    }
+}
@@ -2,9 +2,11 @@ mod media_type;
 mod accept;
 mod checkers;
 mod indexed;
+mod duration;
 
 pub use self::media_type::*;
 pub use self::accept::*;
+pub use self::duration::parse_duration;
 
 pub mod uri;
 
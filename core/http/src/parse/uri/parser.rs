@@ -3,6 +3,7 @@ use pear::input::{Extent, Rewind};
 use pear::macros::{parser, switch, parse_current_marker, parse_error, parse_try};
 
 use crate::uri::{Uri, Origin, Authority, Absolute, Host};
+use crate::route::{ParamScanner, Scanned};
 use crate::parse::uri::tables::{is_reg_name_char, is_pchar, is_qchar, is_rchar};
 use crate::parse::uri::RawInput;
 
@@ -37,9 +38,18 @@ pub fn origin<'a>(input: &mut RawInput<'a>) -> Result<'a, Origin<'a>> {
 
 #[parser]
 pub fn rocket_route_origin<'a>(input: &mut RawInput<'a>) -> Result<'a, Origin<'a>> {
-    fn is_pchar_or_rchar(c: &u8) -> bool { is_pchar(c) || is_rchar(c) }
-    fn is_qchar_or_rchar(c: &u8) -> bool { is_qchar(c) || is_rchar(c) }
-    (peek(b'/')?, path_and_query(is_pchar_or_rchar, is_qchar_or_rchar)?).1
+    // Any visible ASCII character, including `/` and `?`, may appear in a
+    // parameter's constraint, as in `<ext:(?:txt|md)>`. Elsewhere, only
+    // `is_char` characters and parameter brackets are allowed.
+    fn or_rchar<F: Fn(&u8) -> bool>(is_char: F) -> impl FnMut(&u8) -> bool {
+        let mut scanner = ParamScanner::default();
+        move |c: &u8| match scanner.step(*c) {
+            Scanned::Constraint => c.is_ascii_graphic(),
+            _ => is_char(c) || is_rchar(c),
+        }
+    }
+
+    (peek(b'/')?, path_and_query(or_rchar(is_pchar), or_rchar(is_qchar))?).1
 }
 
 #[parser]
@@ -48,7 +58,7 @@ fn path_and_query<'a, F, Q>(
     is_path_char: F,
     is_query_char: Q
 ) -> Result<'a, Origin<'a>>
-    where F: Fn(&u8) -> bool + Copy, Q: Fn(&u8) -> bool + Copy
+    where F: FnMut(&u8) -> bool, Q: FnMut(&u8) -> bool
 {
     let path = take_while(is_path_char)?;
     let query = parse_try!(eat(b'?') => take_while(is_query_char)?);
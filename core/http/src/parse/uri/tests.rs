@@ -259,3 +259,25 @@ fn display() {
         "abc", "@):0", "[a]"
     }
 }
+
+fn assert_route_parse(string: &str, path: &str, query: Option<&str>) {
+    match route_origin_from_str(string) {
+        Ok(uri) => assert_eq!((uri.path(), uri.query()), (path, query), "{:?}", string),
+        Err(e) => panic!("{:?} failed to parse: {}", string, e),
+    }
+}
+
+#[test]
+fn route_origin() {
+    assert_route_parse("/<a>/b?<c>", "/<a>/b", Some("<c>"));
+    assert_route_parse("/<id:[0-9]{2}>", "/<id:[0-9]{2}>", None);
+    assert_route_parse("/a/<b:(?:x|y)/z>/c?<d>", "/a/<b:(?:x|y)/z>/c", Some("<d>"));
+    assert_route_parse("/<op:[<>]=?>?<q>", "/<op:[<>]=?>", Some("<q>"));
+    assert_route_parse("/<id:(?P<n>x)>?a", "/<id:(?P<n>x)>", Some("a"));
+    assert_route_parse("/<a>?<b:c?d>", "/<a>", Some("<b:c?d>"));
+
+    // Characters reserved for constraints are only accepted in one.
+    for string in &["/a[b]", "/a{b}", "/<a>|<b>", "/<a:x>^", "/<a:x y>", "/<a[0-9]>"] {
+        assert!(route_origin_from_str(string).is_err(), "{:?} parsed", string);
+    }
+}
@@ -0,0 +1,249 @@
+//! Decoding of PROXY protocol headers.
+//!
+//! A proxy that forwards TCP connections can begin each connection with a
+//! PROXY protocol header reporting the address of the original client. Both
+//! the human-readable version 1 and the binary version 2 of the protocol are
+//! supported. The header is expected to arrive in the connection's first
+//! segment, as the protocol requires of senders; a partial header is rejected,
+//! as is a connection that doesn't deliver its header within `HEADER_TIMEOUT`.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio::net::TcpStream;
+
+use crate::listener::Connection;
+
+/// The prefix of a version 1 header.
+const V1_PREFIX: &[u8] = b"PROXY ";
+
+/// The maximum length of a version 1 header, including the trailing CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// The signature that begins a version 2 header.
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The length of the fixed part of a version 2 header.
+const V2_HEADER_LEN: usize = 16;
+
+/// The maximum time to wait for a connection's PROXY protocol header.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("PROXY protocol: {}", msg))
+}
+
+/// A TCP stream whose remote address may have been reported by a proxy via
+/// the PROXY protocol.
+pub struct ProxiedStream {
+    stream: TcpStream,
+    source: Option<SocketAddr>,
+}
+
+impl ProxiedStream {
+    /// Wraps `stream` without decoding a PROXY protocol header.
+    pub fn direct(stream: TcpStream) -> ProxiedStream {
+        ProxiedStream { stream, source: None }
+    }
+
+    /// Reads the PROXY protocol header at the start of `stream`. If there is
+    /// no header, the connection is rejected when `required` and is used as-is
+    /// otherwise. A header that doesn't report a source address, such as one
+    /// for a health check, leaves the remote address as the peer's. Fails with
+    /// `TimedOut` if the header isn't read within `HEADER_TIMEOUT`.
+    pub async fn accept(mut stream: TcpStream, required: bool) -> io::Result<ProxiedStream> {
+        let source = tokio::time::timeout(HEADER_TIMEOUT, read_header(&mut stream, required))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "PROXY protocol: timed out"))??;
+
+        Ok(ProxiedStream { stream, source })
+    }
+}
+
+/// Consumes the PROXY protocol header at the start of `stream`, if any, and
+/// returns the source address it reports.
+async fn read_header(stream: &mut TcpStream, required: bool) -> io::Result<Option<SocketAddr>> {
+    let mut buf = [0; V1_MAX_LEN];
+    let n = stream.peek(&mut buf).await?;
+    let data = &buf[..n];
+
+    if data.starts_with(V2_SIGNATURE) {
+        if n < V2_HEADER_LEN {
+            return Err(invalid("incomplete header"));
+        }
+
+        let len = V2_HEADER_LEN + u16::from_be_bytes([data[14], data[15]]) as usize;
+        let mut header = vec![0; len];
+        stream.read_exact(&mut header).await?;
+        parse_v2(&header)
+    } else if data.starts_with(V1_PREFIX) {
+        let end = data.windows(2).position(|w| w == b"\r\n")
+            .ok_or_else(|| invalid("incomplete or oversized v1 header"))?;
+
+        let mut header = vec![0; end + 2];
+        stream.read_exact(&mut header).await?;
+        parse_v1(&header[..end])
+    } else if V2_SIGNATURE.starts_with(data) || V1_PREFIX.starts_with(data) {
+        Err(invalid("incomplete header"))
+    } else if required {
+        Err(invalid("missing required header"))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Parses the version 1 header `line`, without its trailing CRLF.
+fn parse_v1(line: &[u8]) -> io::Result<Option<SocketAddr>> {
+    let line = std::str::from_utf8(line).map_err(|_| invalid("non-ASCII v1 header"))?;
+    let fields = line.split(' ').collect::<Vec<_>>();
+    let ip = match fields.get(1) {
+        Some(&"TCP4") if fields.len() == 6 => {
+            fields[2].parse::<Ipv4Addr>().map(IpAddr::from)
+        }
+        Some(&"TCP6") if fields.len() == 6 => {
+            fields[2].parse::<Ipv6Addr>().map(IpAddr::from)
+        }
+        Some(&"UNKNOWN") => return Ok(None),
+        _ => return Err(invalid("malformed v1 header")),
+    };
+
+    let ip = ip.map_err(|_| invalid("invalid v1 source address"))?;
+    let port = fields[4].parse::<u16>().map_err(|_| invalid("invalid v1 source port"))?;
+    Ok(Some(SocketAddr::new(ip, port)))
+}
+
+/// Parses the complete version 2 header `header`.
+fn parse_v2(header: &[u8]) -> io::Result<Option<SocketAddr>> {
+    let (version, command) = (header[12] >> 4, header[12] & 0x0f);
+    if version != 2 {
+        return Err(invalid("unsupported version"));
+    }
+
+    match command {
+        // `LOCAL`: the connection was made by the proxy itself.
+        0x0 => return Ok(None),
+        // `PROXY`: the connection was relayed on behalf of a client.
+        0x1 => {}
+        _ => return Err(invalid("unknown v2 command")),
+    }
+
+    let addresses = &header[V2_HEADER_LEN..];
+    let port = |at: usize| u16::from_be_bytes([addresses[at], addresses[at + 1]]);
+    match header[13] >> 4 {
+        // `AF_INET`: 4-byte source and destination addresses, then ports.
+        0x1 if addresses.len() >= 12 => {
+            let mut ip = [0; 4];
+            ip.copy_from_slice(&addresses[..4]);
+            Ok(Some(SocketAddr::new(Ipv4Addr::from(ip).into(), port(8))))
+        }
+        // `AF_INET6`: 16-byte source and destination addresses, then ports.
+        0x2 if addresses.len() >= 36 => {
+            let mut ip = [0; 16];
+            ip.copy_from_slice(&addresses[..16]);
+            Ok(Some(SocketAddr::new(Ipv6Addr::from(ip).into(), port(32))))
+        }
+        0x1 | 0x2 => Err(invalid("truncated v2 addresses")),
+        // `AF_UNSPEC` or `AF_UNIX`: there is no network address to report.
+        _ => Ok(None),
+    }
+}
+
+impl AsyncRead for ProxiedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ProxiedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+impl Connection for ProxiedStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.source.or_else(|| self.stream.peer_addr().ok())
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.stream.local_addr().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::{parse_v1, parse_v2};
+
+    fn addr(s: &str) -> Option<SocketAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_v1() {
+        let parse = |s: &str| parse_v1(s.as_bytes()).ok();
+
+        assert_eq!(parse("PROXY TCP4 192.0.2.1 198.51.100.1 56324 443"),
+            Some(addr("192.0.2.1:56324")));
+        assert_eq!(parse("PROXY TCP6 2001:db8::1 2001:db8::2 56324 443"),
+            Some(addr("[2001:db8::1]:56324")));
+        assert_eq!(parse("PROXY UNKNOWN"), Some(None));
+        assert_eq!(parse("PROXY UNKNOWN ffff::1 ffff::2 1 2"), Some(None));
+
+        assert!(parse("PROXY TCP4 2001:db8::1 198.51.100.1 56324 443").is_none());
+        assert!(parse("PROXY TCP4 192.0.2.1 198.51.100.1 65536 443").is_none());
+        assert!(parse("PROXY TCP4 192.0.2.1 198.51.100.1 56324").is_none());
+        assert!(parse("PROXY UDP4 192.0.2.1 198.51.100.1 56324 443").is_none());
+    }
+
+    #[test]
+    fn test_v2() {
+        let header = |ver_cmd: u8, family: u8, addresses: &[u8]| {
+            let mut header = super::V2_SIGNATURE.to_vec();
+            header.extend_from_slice(&[ver_cmd, family]);
+            header.extend_from_slice(&(addresses.len() as u16).to_be_bytes());
+            header.extend_from_slice(addresses);
+            parse_v2(&header).ok()
+        };
+
+        let v4 = [192, 0, 2, 1, 198, 51, 100, 1, 0xdc, 0x04, 0x01, 0xbb];
+        assert_eq!(header(0x21, 0x11, &v4), Some(addr("192.0.2.1:56324")));
+
+        let mut v6 = vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        v6.extend_from_slice(&[0; 16]);
+        v6.extend_from_slice(&[0xdc, 0x04, 0x01, 0xbb]);
+        assert_eq!(header(0x21, 0x21, &v6), Some(addr("[2001:db8::1]:56324")));
+
+        // Trailing TLVs are ignored.
+        let mut tlvs = v4.to_vec();
+        tlvs.extend_from_slice(&[0x04, 0x00, 0x01, 0x00]);
+        assert_eq!(header(0x21, 0x11, &tlvs), Some(addr("192.0.2.1:56324")));
+
+        assert_eq!(header(0x20, 0x11, &v4), Some(None));
+        assert_eq!(header(0x21, 0x00, &[]), Some(None));
+        assert_eq!(header(0x21, 0x11, &v4[..8]), None);
+        assert_eq!(header(0x11, 0x11, &v4), None);
+        assert_eq!(header(0x22, 0x11, &v4), None);
+    }
+}
@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 use std::marker::PhantomData;
 
+use regex::Regex;
+use regex_syntax::hir::{self, Hir, HirKind, ClassUnicode, ClassUnicodeRange};
+use regex_syntax::hir::{RepetitionKind, RepetitionRange};
+use smallvec::SmallVec;
 use unicode_xid::UnicodeXID;
 
 use crate::ext::IntoOwned;
@@ -14,6 +18,165 @@ pub enum Kind {
     Static,
     Single,
     Multi,
+    /// A path segment that mixes static text and single-segment parameters,
+    /// such as `<name>.<ext>`. Its `pieces` describe it.
+    Mixed,
+}
+
+/// A piece of a path segment of kind [`Kind::Mixed`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Piece<'a> {
+    /// Static text.
+    Static(Cow<'a, str>),
+    /// A parameter with the given name and constraint, if any.
+    Dynamic(Cow<'a, str>, Option<Constraint>),
+}
+
+/// A constraint on the raw value of a path parameter, checked when routing, as
+/// in `<id:int>` or `<slug:[a-z-]+>`.
+///
+/// A constraint is either one of the named constraints below or a regular
+/// expression that must match the entire raw value:
+///
+///   * `int`: an optionally signed sequence of ASCII digits
+///   * `uint`: a sequence of ASCII digits
+///   * `alpha`: a sequence of ASCII letters
+///   * `alnum`: a sequence of ASCII letters and digits
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    source: String,
+    regex: Regex,
+}
+
+/// Every non-empty string matched by a pattern starts with a character in
+/// `first` and consists only of characters in `chars`.
+struct Alphabet {
+    first: ClassUnicode,
+    chars: ClassUnicode,
+    nullable: bool,
+}
+
+impl Constraint {
+    /// Parses `source`, the text after the `:` in a parameter, into a
+    /// constraint. Returns `None` if `source` is empty or an invalid regular
+    /// expression.
+    pub fn parse(source: &str) -> Option<Constraint> {
+        let pattern = match source {
+            "" => return None,
+            "int" => "[+-]?[0-9]+",
+            "uint" => "[0-9]+",
+            "alpha" => "[a-zA-Z]+",
+            "alnum" => "[a-zA-Z0-9]+",
+            regex => regex,
+        };
+
+        let regex = Regex::new(&format!("^(?:{})$", pattern)).ok()?;
+        Some(Constraint { source: source.into(), regex })
+    }
+
+    /// The constraint as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `true` if the raw parameter value `value` satisfies `self`.
+    pub fn matches(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+
+    /// Returns `true` if no non-empty value can satisfy both `self` and
+    /// `other`. This is conservative: `false` may be returned for disjoint
+    /// constraints, but `true` is only returned if they are provably disjoint.
+    pub fn is_disjoint(&self, other: &Constraint) -> bool {
+        fn disjoint(a: &ClassUnicode, b: &ClassUnicode) -> bool {
+            let mut intersection = a.clone();
+            intersection.intersect(b);
+            intersection.ranges().is_empty()
+        }
+
+        match (self.alphabet(), other.alphabet()) {
+            (Some(a), Some(b)) => disjoint(&a.first, &b.first) || disjoint(&a.chars, &b.chars),
+            _ => false,
+        }
+    }
+
+    fn alphabet(&self) -> Option<Alphabet> {
+        let hir = regex_syntax::Parser::new().parse(self.regex.as_str()).ok()?;
+        Some(Alphabet::of(&hir))
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Constraint) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Constraint {  }
+
+impl Alphabet {
+    /// Returns a conservative alphabet of the strings matched by `hir`.
+    fn of(hir: &Hir) -> Alphabet {
+        let class = |chars: ClassUnicode| {
+            Alphabet { first: chars.clone(), chars, nullable: false }
+        };
+
+        let range = |start: char, end: char| {
+            class(ClassUnicode::new(vec![ClassUnicodeRange::new(start, end)]))
+        };
+
+        match hir.kind() {
+            HirKind::Empty | HirKind::Anchor(_) | HirKind::WordBoundary(_) => Alphabet::empty(),
+            HirKind::Literal(hir::Literal::Unicode(c)) => range(*c, *c),
+            HirKind::Literal(hir::Literal::Byte(b)) => range(*b as char, *b as char),
+            HirKind::Class(hir::Class::Unicode(unicode)) => class(unicode.clone()),
+            HirKind::Class(hir::Class::Bytes(bytes)) => class(ClassUnicode::new(bytes.iter()
+                .map(|r| ClassUnicodeRange::new(r.start() as char, r.end() as char)))),
+            HirKind::Repetition(repetition) => {
+                let mut alphabet = Alphabet::of(&repetition.hir);
+                alphabet.nullable |= match repetition.kind {
+                    RepetitionKind::ZeroOrOne | RepetitionKind::ZeroOrMore => true,
+                    RepetitionKind::OneOrMore => false,
+                    RepetitionKind::Range(RepetitionRange::Exactly(min))
+                        | RepetitionKind::Range(RepetitionRange::AtLeast(min))
+                        | RepetitionKind::Range(RepetitionRange::Bounded(min, _)) => min == 0,
+                };
+
+                alphabet
+            }
+            HirKind::Group(group) => Alphabet::of(&group.hir),
+            HirKind::Concat(hirs) => {
+                let mut alphabet = Alphabet::empty();
+                for hir in hirs {
+                    let next = Alphabet::of(hir);
+                    if alphabet.nullable {
+                        alphabet.first.union(&next.first);
+                    }
+
+                    alphabet.chars.union(&next.chars);
+                    alphabet.nullable &= next.nullable;
+                }
+
+                alphabet
+            }
+            HirKind::Alternation(hirs) => {
+                let mut alphabet = Alphabet { nullable: false, ..Alphabet::empty() };
+                for hir in hirs {
+                    let next = Alphabet::of(hir);
+                    alphabet.first.union(&next.first);
+                    alphabet.chars.union(&next.chars);
+                    alphabet.nullable |= next.nullable;
+                }
+
+                alphabet
+            }
+        }
+    }
+
+    /// The alphabet of the empty string.
+    fn empty() -> Alphabet {
+        Alphabet { first: ClassUnicode::empty(), chars: ClassUnicode::empty(), nullable: true }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,9 +185,25 @@ pub struct RouteSegment<'a, P: UriPart> {
     pub kind: Kind,
     pub name: Cow<'a, str>,
     pub index: Option<usize>,
+    pub pieces: Vec<Piece<'a>>,
+    pub constraint: Option<Constraint>,
     _part: PhantomData<P>,
 }
 
+impl IntoOwned for Piece<'_> {
+    type Owned = Piece<'static>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Piece::Static(text) => Piece::Static(IntoOwned::into_owned(text)),
+            Piece::Dynamic(name, constraint) => {
+                Piece::Dynamic(IntoOwned::into_owned(name), constraint)
+            }
+        }
+    }
+}
+
 impl<P: UriPart + 'static> IntoOwned for RouteSegment<'_, P> {
     type Owned = RouteSegment<'static, P>;
 
@@ -35,6 +214,8 @@ impl<P: UriPart + 'static> IntoOwned for RouteSegment<'_, P> {
             kind: self.kind,
             name: IntoOwned::into_owned(self.name),
             index: self.index,
+            pieces: self.pieces.into_iter().map(IntoOwned::into_owned).collect(),
+            constraint: self.constraint,
             _part: PhantomData
         }
     }
@@ -48,7 +229,11 @@ pub enum Error<'a> {
     MissingClose,
     Malformed,
     Uri,
-    Trailing(&'a str)
+    Trailing(&'a str),
+    Adjacent,
+    PartialMulti,
+    BadConstraint(&'a str),
+    Unconstrainable,
 }
 
 impl std::fmt::Display for Error<'_> {
@@ -60,7 +245,11 @@ impl std::fmt::Display for Error<'_> {
             MissingClose => "parameter is missing a closing bracket".fmt(f),
             Malformed => "malformed parameter or identifier".fmt(f),
             Uri => "segment contains invalid URI characters".fmt(f),
-            Trailing(i) => write!(f, "unexpected trailing text after `{}`", i)
+            Trailing(i) => write!(f, "unexpected trailing text after `{}`", i),
+            Adjacent => "adjacent parameters must be separated by static text".fmt(f),
+            PartialMulti => "multi-segment parameters cannot share a segment".fmt(f),
+            BadConstraint(c) => write!(f, "`{}` is not a valid constraint", c),
+            Unconstrainable => "only single-segment path parameters can be constrained".fmt(f),
         }
     }
 }
@@ -89,14 +278,241 @@ fn is_valid_ident(string: &str) -> bool {
     }
 }
 
+/// What a byte fed to a [`ParamScanner`] is part of.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum Scanned {
+    /// Text outside of a parameter.
+    Text,
+    /// The `<` opening a parameter.
+    Open,
+    /// A parameter's name or the `:` following it.
+    Name,
+    /// A parameter's constraint.
+    Constraint,
+    /// The `>` closing a parameter.
+    Close,
+}
+
+/// Scans a route a byte at a time, tracking where its parameters begin and
+/// end. A `>` in a constraint doesn't close the parameter when it's escaped,
+/// in a character class, or closes a `<` in the constraint, so constraints like
+/// `<op:[<>]=?>` and `<id:(?P<n>[0-9]+)>` are scanned whole.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct ParamScanner {
+    param: bool,
+    constraint: bool,
+    escaped: bool,
+    /// The depth of nested character classes in the constraint.
+    classes: usize,
+    /// `Some(negated)` immediately after a class opens, where `]` is literal.
+    class_start: Option<bool>,
+    /// The number of unclosed `<` in the constraint.
+    groups: usize,
+}
+
+impl ParamScanner {
+    /// Scans the next byte, `c`, returning what it's part of.
+    pub(crate) fn step(&mut self, c: u8) -> Scanned {
+        if !self.param {
+            self.param = c == b'<';
+            return if self.param { Scanned::Open } else { Scanned::Text };
+        }
+
+        if !self.constraint {
+            match c {
+                b'>' => {
+                    *self = ParamScanner::default();
+                    return Scanned::Close;
+                }
+                b':' => self.constraint = true,
+                _ => {}
+            }
+
+            return Scanned::Name;
+        }
+
+        let class_start = self.class_start.take();
+        if self.escaped {
+            self.escaped = false;
+        } else if c == b'\\' {
+            self.escaped = true;
+        } else if self.classes > 0 {
+            match c {
+                b'[' => {
+                    self.classes += 1;
+                    self.class_start = Some(false);
+                }
+                b'^' if class_start == Some(false) => self.class_start = Some(true),
+                b']' if class_start.is_none() => self.classes -= 1,
+                _ => {}
+            }
+        } else {
+            match c {
+                b'[' => {
+                    self.classes = 1;
+                    self.class_start = Some(false);
+                }
+                b'<' => self.groups += 1,
+                b'>' if self.groups > 0 => self.groups -= 1,
+                b'>' => {
+                    *self = ParamScanner::default();
+                    return Scanned::Close;
+                }
+                _ => {}
+            }
+        }
+
+        Scanned::Constraint
+    }
+
+    /// Returns `true` if the last byte scanned was in an unclosed parameter.
+    pub(crate) fn in_param(&self) -> bool {
+        self.param
+    }
+}
+
+/// Returns the index of the `>` closing the parameter that `string` starts
+/// with, if there is one.
+fn find_close(string: &str) -> Option<usize> {
+    let mut scanner = ParamScanner::default();
+    string.bytes().position(|c| scanner.step(c) == Scanned::Close)
+}
+
+/// Returns the constraint of the parameter left unclosed at the end of
+/// `segment`, if any, as in `<id:[a-z>` or `<a>.<b:x<y>`.
+fn unclosed_constraint(segment: &str) -> Option<&str> {
+    let (mut scanner, mut start) = (ParamScanner::default(), None);
+    for (i, c) in segment.bytes().enumerate() {
+        match scanner.step(c) {
+            Scanned::Name if c == b':' => start = Some(i + 1),
+            Scanned::Close => start = None,
+            _ => {}
+        }
+    }
+
+    start.filter(|_| scanner.in_param()).map(|i| &segment[i..])
+}
+
+/// Returns `true` if `segment` contains parameters but isn't exactly one.
+fn is_mixed(segment: &str) -> bool {
+    let mut scanner = ParamScanner::default();
+    let whole = segment.starts_with('<')
+        && segment.bytes().enumerate().all(|(i, c)| match scanner.step(c) {
+            Scanned::Name => c != b'<',
+            Scanned::Close => i == segment.len() - 1,
+            _ => true,
+        });
+
+    !whole && segment.contains('<') && segment.contains('>')
+}
+
+/// Splits `string` at each `P::DELIMITER` that isn't within a parameter.
+fn split_segments<P: UriPart>(string: &str) -> Vec<&str> {
+    let (mut scanner, mut start, mut segments) = (ParamScanner::default(), 0, vec![]);
+    for (i, c) in string.bytes().enumerate() {
+        if scanner.step(c) == Scanned::Text && c == P::DELIMITER as u8 {
+            segments.push(&string[start..i]);
+            start = i + 1;
+        }
+    }
+
+    segments.push(&string[start..]);
+    segments
+}
+
+/// Splits `param`, the text between a parameter's brackets, into its name and
+/// its constraint, if any.
+fn split_constraint<P: UriPart>(param: &str) -> Result<(&str, Option<Constraint>), Error<'_>> {
+    let (name, source) = match param.find(':') {
+        Some(i) => (&param[..i], &param[(i + 1)..]),
+        None => return Ok((param, None)),
+    };
+
+    if P::DELIMITER != '/' || name.ends_with("..") {
+        return Err(Unconstrainable);
+    }
+
+    let constraint = Constraint::parse(source).ok_or(BadConstraint(source))?;
+    Ok((name, Some(constraint)))
+}
+
+/// Parses `segment`, which mixes static text and parameters, into its pieces.
+fn parse_pieces<P: UriPart>(segment: &str) -> Result<Vec<Piece<'_>>, Error<'_>> {
+    // Every `<` must be closed by a `>` before the next bracket outside of a
+    // constraint.
+    let mut scanner = ParamScanner::default();
+    for c in segment.bytes() {
+        match scanner.step(c) {
+            Scanned::Name if c == b'<' => return Err(Malformed),
+            Scanned::Text if c == b'>' => return Err(Malformed),
+            _ => {}
+        }
+    }
+
+    if scanner.in_param() {
+        return Err(Malformed);
+    }
+
+    let mut pieces = vec![];
+    let mut rest = segment;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = find_close(rest).ok_or(Malformed)?;
+            let (name, constraint) = split_constraint::<P>(&rest[1..end])?;
+            if name.ends_with("..") {
+                return Err(PartialMulti);
+            } else if name.is_empty() {
+                return Err(Empty);
+            } else if !is_valid_ident(name) {
+                return Err(Ident(name));
+            } else if let Some(Piece::Dynamic(..)) = pieces.last() {
+                return Err(Adjacent);
+            }
+
+            pieces.push(Piece::Dynamic(name.into(), constraint));
+            rest = &rest[(end + 1)..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if unsafe_percent_encode::<P>(text) != text {
+                return Err(Uri);
+            }
+
+            pieces.push(Piece::Static(text.into()));
+            rest = &rest[end..];
+        }
+    }
+
+    Ok(pieces)
+}
+
 impl<'a, P: UriPart> RouteSegment<'a, P> {
     pub fn parse_one(segment: &'a str) -> Result<Self, Error<'_>> {
-        let (string, index) = (segment.into(), None);
+        let (string, index, pieces) = (segment.into(), None, vec![]);
+
+        // A `>` that's escaped, in a class, or closing a `<` in a constraint
+        // doesn't close its parameter.
+        if P::DELIMITER == '/' {
+            if let Some(source) = unclosed_constraint(segment) {
+                return Err(BadConstraint(source));
+            }
+        }
+
+        // Only path segments may mix static text and parameters.
+        if P::DELIMITER == '/' && is_mixed(segment) {
+            let pieces = parse_pieces::<P>(segment)?;
+            let (name, kind, constraint) = (segment.into(), Kind::Mixed, None);
+            return Ok(RouteSegment {
+                string, name, kind, index, pieces, constraint,
+                _part: PhantomData
+            });
+        }
 
         // Check if this is a dynamic param. If so, check its well-formedness.
         if segment.starts_with('<') && segment.ends_with('>') {
             let mut kind = Kind::Single;
-            let mut name = &segment[1..(segment.len() - 1)];
+            let param = &segment[1..(segment.len() - 1)];
+            let (mut name, constraint) = split_constraint::<P>(param)?;
             if name.ends_with("..") {
                 kind = Kind::Multi;
                 name = &name[..(name.len() - 2)];
@@ -112,7 +528,10 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
             }
 
             let name = name.into();
-            return Ok(RouteSegment { string, name, kind, index, _part: PhantomData });
+            return Ok(RouteSegment {
+                string, name, kind, index, pieces, constraint,
+                _part: PhantomData
+            });
         } else if segment.is_empty() {
             return Err(Empty);
         } else if segment.starts_with('<') && segment.len() > 1
@@ -125,9 +544,10 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
         }
 
         Ok(RouteSegment {
-            string, index,
+            string, index, pieces,
             name: segment.into(),
             kind: Kind::Static,
+            constraint: None,
             _part: PhantomData
         })
     }
@@ -137,7 +557,7 @@ impl<'a, P: UriPart> RouteSegment<'a, P> {
     ) -> impl Iterator<Item = SResult<'_, P>> {
         let mut last_multi_seg: Option<&str> = None;
         // We check for empty segments when we parse an `Origin` in `FromMeta`.
-        string.split(P::DELIMITER)
+        split_segments::<P>(string).into_iter()
             .filter(|s| !s.is_empty())
             .enumerate()
             .map(move |(i, seg)| {
@@ -160,6 +580,84 @@ impl<'a> RouteSegment<'a, Path> {
     pub fn parse(uri: &'a Origin<'_>) -> impl Iterator<Item = SResult<'a, Path>> {
         Self::parse_many(uri.path())
     }
+
+    /// Splits `segment`, a request path segment, into the values of the
+    /// parameters of this segment of kind [`Kind::Mixed`], in order. Returns
+    /// `None` if `segment` doesn't match or if `self` isn't mixed.
+    ///
+    /// Every value is non-empty. When there is more than one way to split
+    /// `segment`, later parameters match as little as possible: `<name>.<ext>`
+    /// splits `a.tar.gz` into `a.tar` and `gz`. Constraints are checked against
+    /// that split only: `None` is returned if any value violates its
+    /// parameter's constraint, even if another split would satisfy them all.
+    pub fn split_mixed<'s>(&self, segment: &'s str) -> Option<SmallVec<[&'s str; 2]>> {
+        if self.kind != Kind::Mixed {
+            return None;
+        }
+
+        let (mut rest, mut pieces) = (segment, &self.pieces[..]);
+        if let Some((Piece::Static(prefix), tail)) = pieces.split_first() {
+            if !rest.starts_with(&**prefix) {
+                return None;
+            }
+
+            rest = &rest[prefix.len()..];
+            pieces = tail;
+        }
+
+        if let Some((Piece::Static(suffix), init)) = pieces.split_last() {
+            if !rest.ends_with(&**suffix) {
+                return None;
+            }
+
+            rest = &rest[..(rest.len() - suffix.len())];
+            pieces = init;
+        }
+
+        // `pieces` now alternates parameters and static text, starting and
+        // ending with a parameter. Match the rightmost occurrence of each piece
+        // of text that leaves a non-empty value for the parameter after it.
+        let mut values = SmallVec::<[&str; 2]>::new();
+        for pair in pieces.rchunks(2) {
+            match pair {
+                [Piece::Static(text), Piece::Dynamic(..)] => {
+                    let last = rest.chars().next_back()?.len_utf8();
+                    let start = rest[..(rest.len() - last)].rfind(&**text)?;
+                    values.push(&rest[(start + text.len())..]);
+                    rest = &rest[..start];
+                }
+                [Piece::Dynamic(..)] if !rest.is_empty() => {
+                    values.push(rest);
+                    rest = "";
+                }
+                _ => return None,
+            }
+        }
+
+        values.reverse();
+        let constraints = self.pieces.iter().filter_map(|piece| match piece {
+            Piece::Dynamic(_, constraint) => Some(constraint),
+            Piece::Static(_) => None,
+        });
+
+        let mut checks = values.iter().zip(constraints);
+        match checks.all(|(value, c)| c.as_ref().map_or(true, |c| c.matches(value))) {
+            true => Some(values),
+            false => None,
+        }
+    }
+
+    /// Returns `true` if `segment`, a request path segment, matches this
+    /// segment, including any constraints on its parameters. A segment of kind
+    /// [`Kind::Multi`] matches every segment.
+    pub fn matches(&self, segment: &str) -> bool {
+        match self.kind {
+            Kind::Static => self.string == segment,
+            Kind::Single => self.constraint.as_ref().map_or(true, |c| c.matches(segment)),
+            Kind::Multi => true,
+            Kind::Mixed => self.split_mixed(segment).is_some(),
+        }
+    }
 }
 
 impl<'a> RouteSegment<'a, Query> {
@@ -167,3 +665,198 @@ impl<'a> RouteSegment<'a, Query> {
         uri.query().map(|q| Self::parse_many(q))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteSegment, Kind, Piece, Error, Constraint};
+    use crate::uri::{Path, Query};
+
+    fn path(segment: &str) -> Result<RouteSegment<'_, Path>, Error<'_>> {
+        RouteSegment::parse_one(segment)
+    }
+
+    fn split<'s>(pattern: &str, segment: &'s str) -> Option<Vec<&'s str>> {
+        let pattern = path(pattern).expect("valid pattern");
+        pattern.split_mixed(segment).map(|values| values.into_vec())
+    }
+
+    fn constraint(source: &str) -> Constraint {
+        Constraint::parse(source).expect("valid constraint")
+    }
+
+    fn disjoint(a: &str, b: &str) -> bool {
+        constraint(a).is_disjoint(&constraint(b))
+    }
+
+    #[test]
+    fn test_parse_mixed() {
+        let segment = path("<name>.<ext>").unwrap();
+        assert_eq!(segment.kind, Kind::Mixed);
+        assert_eq!(segment.pieces, vec![
+            Piece::Dynamic("name".into(), None),
+            Piece::Static(".".into()),
+            Piece::Dynamic("ext".into(), None),
+        ]);
+
+        let segment = path("v<version>").unwrap();
+        assert_eq!(segment.kind, Kind::Mixed);
+        assert_eq!(segment.pieces, vec![
+            Piece::Static("v".into()),
+            Piece::Dynamic("version".into(), None),
+        ]);
+
+        assert_eq!(path("<name>").unwrap().kind, Kind::Single);
+        assert_eq!(path("<path..>").unwrap().kind, Kind::Multi);
+        assert!(path("<name>").unwrap().pieces.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mixed_errors() {
+        assert_eq!(path("<a><b>").unwrap_err(), Error::Adjacent);
+        assert_eq!(path("<a..>.txt").unwrap_err(), Error::PartialMulti);
+        assert_eq!(path("<>.txt").unwrap_err(), Error::Empty);
+        assert_eq!(path("<a-b>.txt").unwrap_err(), Error::Ident("a-b"));
+        assert_eq!(path("<a>.<b").unwrap_err(), Error::Malformed);
+        assert_eq!(path("<<a>.txt").unwrap_err(), Error::Malformed);
+        assert_eq!(path("<a>>.txt").unwrap_err(), Error::Malformed);
+        assert_eq!(path("<>a><").unwrap_err(), Error::Malformed);
+        assert_eq!(path("<a>%20<b>").unwrap_err(), Error::Uri);
+
+        // Queries may not mix static text and parameters.
+        assert!(<RouteSegment<'_, Query>>::parse_one("<a>.<b>").is_err());
+    }
+
+    #[test]
+    fn test_split_mixed() {
+        assert_eq!(split("<name>.<ext>", "file.txt"), Some(vec!["file", "txt"]));
+        assert_eq!(split("<name>.<ext>", "a.tar.gz"), Some(vec!["a.tar", "gz"]));
+        assert_eq!(split("<name>.<ext>", "file..txt"), Some(vec!["file.", "txt"]));
+        assert_eq!(split("<name>.<ext>", "file."), None);
+        assert_eq!(split("<name>.<ext>", ".txt"), None);
+        assert_eq!(split("<name>.<ext>", "file"), None);
+
+        assert_eq!(split("v<version>", "v2"), Some(vec!["2"]));
+        assert_eq!(split("v<version>", "v"), None);
+        assert_eq!(split("v<version>", "2"), None);
+
+        assert_eq!(split("<id>.json", "10.json"), Some(vec!["10"]));
+        assert_eq!(split("<id>.json", "10.xml"), None);
+
+        assert_eq!(split("a<x>-<y>b", "a1-2b"), Some(vec!["1", "2"]));
+        assert_eq!(split("a<x>-<y>b", "a1-2-3b"), Some(vec!["1-2", "3"]));
+        assert_eq!(split("a<x>-<y>b", "a-b"), None);
+        assert_eq!(split("a<x>a", "aa"), None);
+        assert_eq!(split("<x>aa<y>", "baaa"), Some(vec!["b", "a"]));
+
+        // Only mixed segments split.
+        assert_eq!(split("<name>", "file.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_constraints() {
+        let segment = path("<id:int>").unwrap();
+        assert_eq!(segment.kind, Kind::Single);
+        assert_eq!(segment.name, "id");
+        assert_eq!(segment.constraint.as_ref().map(|c| c.as_str()), Some("int"));
+
+        let segment = path("<slug:[a-z-]+>").unwrap();
+        assert_eq!(segment.name, "slug");
+        assert_eq!(segment.constraint.as_ref().map(|c| c.as_str()), Some("[a-z-]+"));
+
+        let segment = path("<name:alpha>.<ext:(?:txt|md)>").unwrap();
+        assert_eq!(segment.kind, Kind::Mixed);
+        assert_eq!(segment.pieces, vec![
+            Piece::Dynamic("name".into(), Some(constraint("alpha"))),
+            Piece::Static(".".into()),
+            Piece::Dynamic("ext".into(), Some(constraint("(?:txt|md)"))),
+        ]);
+
+        assert!(path("<id>").unwrap().constraint.is_none());
+        assert!(path("<_:uint>").unwrap().constraint.is_some());
+
+        // Brackets in a constraint don't end its parameter.
+        let segment = path("<op:[<>]=?>").unwrap();
+        assert_eq!(segment.kind, Kind::Single);
+        assert_eq!(segment.constraint.as_ref().map(|c| c.as_str()), Some("[<>]=?"));
+
+        let segment = path("<id:(?P<n>[0-9]+)>").unwrap();
+        assert_eq!(segment.kind, Kind::Single);
+        assert_eq!(segment.name, "id");
+
+        let segment = path("<a:[^>\\]]+>.<b:[]>]>").unwrap();
+        assert_eq!(segment.kind, Kind::Mixed);
+        assert_eq!(segment.pieces, vec![
+            Piece::Dynamic("a".into(), Some(constraint("[^>\\]]+"))),
+            Piece::Static(".".into()),
+            Piece::Dynamic("b".into(), Some(constraint("[]>]"))),
+        ]);
+
+        // Neither do delimiters.
+        let segments = <RouteSegment<'_, Path>>::parse_many("/a/<b:x/y|z>/<c>")
+            .map(|segment| segment.unwrap().string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(segments, vec!["a", "<b:x/y|z>", "<c>"]);
+    }
+
+    #[test]
+    fn test_parse_constraint_errors() {
+        assert_eq!(path("<id:[a-z>").unwrap_err(), Error::BadConstraint("[a-z>"));
+        assert_eq!(path("<id:x<y>").unwrap_err(), Error::BadConstraint("x<y>"));
+        assert_eq!(path("<a>.<b:[>").unwrap_err(), Error::BadConstraint("[>"));
+        assert_eq!(path("<id:>").unwrap_err(), Error::BadConstraint(""));
+        assert_eq!(path("<:int>").unwrap_err(), Error::Empty);
+        assert_eq!(path("<a-b:int>").unwrap_err(), Error::Ident("a-b"));
+        assert_eq!(path("<path..:int>").unwrap_err(), Error::Unconstrainable);
+        assert_eq!(path("<a:(>.txt").unwrap_err(), Error::BadConstraint("("));
+
+        let query = <RouteSegment<'_, Query>>::parse_one("<id:int>");
+        assert_eq!(query.unwrap_err(), Error::Unconstrainable);
+    }
+
+    #[test]
+    fn test_constraint_matches() {
+        assert!(constraint("int").matches("10"));
+        assert!(constraint("int").matches("-10"));
+        assert!(!constraint("int").matches("10a"));
+        assert!(!constraint("int").matches("-"));
+        assert!(constraint("uint").matches("0"));
+        assert!(!constraint("uint").matches("-1"));
+        assert!(constraint("alpha").matches("Bob"));
+        assert!(!constraint("alpha").matches("Bob1"));
+        assert!(constraint("alnum").matches("Bob1"));
+        assert!(!constraint("alnum").matches("Bob-1"));
+
+        // Regular expressions must match the entire value.
+        assert!(constraint("[a-z-]+").matches("hello-world"));
+        assert!(!constraint("[a-z-]+").matches("hello world"));
+        assert!(!constraint("a|b").matches("ab"));
+        assert!(constraint("a|b").matches("b"));
+
+        let segment = path("<id:int>").unwrap();
+        assert!(segment.matches("10"));
+        assert!(!segment.matches("new"));
+        assert!(path("<id>").unwrap().matches("new"));
+
+        assert_eq!(split("<name>.<ext:alpha>", "a.1.txt"), Some(vec!["a.1", "txt"]));
+        assert_eq!(split("<name>.<ext:alpha>", "a.txt.1"), None);
+        assert_eq!(split("v<major:uint>", "v1"), Some(vec!["1"]));
+        assert_eq!(split("v<major:uint>", "vx"), None);
+    }
+
+    #[test]
+    fn test_constraint_disjointness() {
+        assert!(disjoint("int", "alpha"));
+        assert!(disjoint("uint", "alpha"));
+        assert!(disjoint("uint", "[a-z-]+"));
+        assert!(disjoint("a[0-9]+", "b[0-9]+"));
+        assert!(disjoint("(?:foo|bar)", "[0-9]+"));
+        assert!(disjoint("x?[0-9]+", "y[0-9]*"));
+
+        assert!(!disjoint("int", "uint"));
+        assert!(!disjoint("alpha", "alnum"));
+        assert!(!disjoint("int", "[0-9a-f]+"));
+        assert!(!disjoint("x?[0-9]+", "[0-9]"));
+        assert!(!disjoint("a[0-9]*", "[a-z]+"));
+    }
+}
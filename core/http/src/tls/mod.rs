@@ -0,0 +1,441 @@
+use std::io;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use parking_lot::RwLock;
+use rustls::internal::pemfile;
+use rustls::sign::CertifiedKey;
+use rustls::{Certificate, ClientHello, ServerConfig, Session};
+use tokio::net::TcpListener;
+use tokio_rustls::{TlsAcceptor, server::TlsStream};
+use tokio_rustls::rustls;
+
+use crate::listener::{Connection, Listener, RawCertificate, poll_pending};
+use crate::proxy::ProxiedStream;
+
+mod key;
+
+/// TLS settings for [`bind_tls()`]. Each `R` is a reader over PEM data.
+pub struct Config<R> {
+    /// The certificate chain to present to clients that don't request one of
+    /// the names in `sni`.
+    pub cert_chain: R,
+    /// The private key corresponding to the end-entity certificate.
+    pub private_key: R,
+    /// Certificates selected by the server name a client requests via SNI.
+    pub sni: Vec<NamedCert<R>>,
+    /// Trust roots for verifying client certificates. When `None`, clients
+    /// are not asked for certificates.
+    pub ca_certs: Option<R>,
+    /// Whether clients must present a valid certificate. When `false`, a
+    /// client may connect without one but any certificate it does present
+    /// must be valid. Has no effect when `ca_certs` is `None`.
+    pub mandatory_mtls: bool,
+    /// Names of the protocol versions to enable, such as `TLSv1.3`. When
+    /// `None`, all supported versions are enabled.
+    pub protocols: Option<Vec<String>>,
+    /// Names of the cipher suites to enable, in order of preference, such as
+    /// `TLS13_AES_256_GCM_SHA384`. When `None`, all supported cipher suites
+    /// are enabled.
+    pub ciphers: Option<Vec<String>>,
+    /// Whether to select a cipher suite by the server's order of preference
+    /// instead of the client's.
+    pub prefer_server_cipher_order: bool,
+}
+
+/// A certificate chain and private key presented to clients requesting one of
+/// `names` via SNI.
+pub struct NamedCert<R> {
+    /// The server names this certificate is selected for. A name may begin
+    /// with `*.` to match any single label in its place.
+    pub names: Vec<String>,
+    /// The certificate chain to present.
+    pub cert_chain: R,
+    /// The private key corresponding to the end-entity certificate.
+    pub private_key: R,
+}
+
+fn load_certs(reader: &mut dyn io::BufRead) -> io::Result<Vec<Certificate>> {
+    pemfile::certs(reader)
+        .map_err(|_| io::Error::new(io::ErrorKind::Other, "invalid certificate"))
+}
+
+fn load_certified_key(
+    cert_chain: &mut dyn io::BufRead,
+    private_key: &mut dyn io::BufRead,
+) -> io::Result<CertifiedKey> {
+    let cert_chain = load_certs(cert_chain).map_err(|e| {
+        let msg = format!("malformed TLS certificate chain: {}", e);
+        io::Error::new(e.kind(), msg)
+    })?;
+
+    let key = key::load_private_key(private_key).map_err(|e| {
+        let msg = format!("invalid TLS private key: {}", e);
+        io::Error::new(e.kind(), msg)
+    })?;
+
+    Ok(CertifiedKey::new(cert_chain, Arc::new(key)))
+}
+
+/// The certificates a `CertResolver` selects among.
+struct Certs {
+    default: CertifiedKey,
+    named: HashMap<String, CertifiedKey>,
+}
+
+impl Certs {
+    fn load<R: io::BufRead>(config: &mut Config<R>) -> io::Result<Certs> {
+        let default = load_certified_key(&mut config.cert_chain, &mut config.private_key)?;
+        let mut named = HashMap::new();
+        for cert in &mut config.sni {
+            let key = load_certified_key(&mut cert.cert_chain, &mut cert.private_key)
+                .map_err(|e| {
+                    let msg = format!("for `{}`: {}", cert.names.join(", "), e);
+                    io::Error::new(e.kind(), msg)
+                })?;
+
+            for name in &cert.names {
+                if named.insert(name.to_ascii_lowercase(), key.clone()).is_some() {
+                    let msg = format!("TLS server name `{}` is configured more than once", name);
+                    return Err(io::Error::new(io::ErrorKind::Other, msg));
+                }
+            }
+        }
+
+        Ok(Certs { default, named })
+    }
+
+    fn select(&self, server_name: Option<&str>) -> &CertifiedKey {
+        let name = match server_name {
+            Some(name) => name.to_ascii_lowercase(),
+            None => return &self.default,
+        };
+
+        let wildcard = || name.find('.').map(|i| format!("*{}", &name[i..]));
+        self.named.get(&name)
+            .or_else(|| wildcard().and_then(|w| self.named.get(&w)))
+            .unwrap_or(&self.default)
+    }
+}
+
+/// Resolves handshakes to a certificate chain and signing key, selected by
+/// the server name requested via SNI, that can be replaced while the listener
+/// is running.
+///
+/// A reload only affects handshakes that begin after it completes; existing
+/// connections keep the certificate they negotiated.
+pub struct CertResolver {
+    current: RwLock<Certs>,
+}
+
+impl CertResolver {
+    /// Replaces all certificate chains and private keys with those read from
+    /// `config`. The `ca_certs` and `mandatory_mtls` settings are ignored. On
+    /// error, the current certificates and keys remain in use.
+    pub fn reload<R: io::BufRead>(&self, mut config: Config<R>) -> io::Result<()> {
+        let certs = Certs::load(&mut config)?;
+        *self.current.write() = certs;
+        Ok(())
+    }
+}
+
+impl rustls::ResolvesServerCert for CertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<CertifiedKey> {
+        let server_name = client_hello.server_name().map(<&str>::from);
+        Some(self.current.read().select(server_name).clone())
+    }
+}
+
+/// A TCP listener that serves TLS.
+///
+/// Handshakes, and the decoding of PROXY protocol headers that precede them,
+/// run concurrently, apart from accepting new connections, so a slow client
+/// can't hold up others. A connection that fails either is logged and closed.
+pub struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    resolver: Arc<CertResolver>,
+    proxy_protocol: Option<bool>,
+    pending: FuturesUnordered<BoxFuture<'static, io::Result<TlsStream<ProxiedStream>>>>,
+}
+
+impl TlsListener {
+    /// Returns the certificate resolver used for new handshakes.
+    pub fn resolver(&self) -> &Arc<CertResolver> {
+        &self.resolver
+    }
+
+    /// Decodes a PROXY protocol header, which precedes the TLS handshake, at
+    /// the start of each connection. Connections without a header are
+    /// rejected if `required`. A connection with an invalid header, or that
+    /// doesn't deliver it within five seconds, is logged and closed.
+    pub fn set_proxy_protocol(&mut self, required: bool) {
+        self.proxy_protocol = Some(required);
+    }
+}
+
+impl Listener for TlsListener {
+    type Connection = TlsStream<ProxiedStream>;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        let (acceptor, proxy_protocol) = (&self.acceptor, self.proxy_protocol);
+        poll_pending(&mut self.listener, &mut self.pending, cx, |stream| {
+            let acceptor = acceptor.clone();
+            Box::pin(async move {
+                let stream = match proxy_protocol {
+                    Some(required) => ProxiedStream::accept(stream, required).await?,
+                    None => ProxiedStream::direct(stream),
+                };
+
+                acceptor.accept(stream).await
+            })
+        })
+    }
+}
+
+fn load_client_verifier(
+    reader: &mut dyn io::BufRead,
+    mandatory: bool,
+) -> io::Result<Arc<dyn rustls::ClientCertVerifier>> {
+    use std::io::{Error, ErrorKind::Other};
+
+    let mut roots = rustls::RootCertStore::empty();
+    let (valid, invalid) = roots.add_pem_file(reader)
+        .map_err(|_| Error::new(Other, "invalid certificate"))?;
+
+    if valid == 0 {
+        return Err(Error::new(Other, "no valid certificates found; is the file malformed?"));
+    } else if invalid > 0 {
+        return Err(Error::new(Other, format!("{} certificate(s) are unusable", invalid)));
+    }
+
+    Ok(match mandatory {
+        true => rustls::AllowAnyAuthenticatedClient::new(roots),
+        false => rustls::AllowAnyAnonymousOrAuthenticatedClient::new(roots),
+    })
+}
+
+fn protocol_versions(names: &[String]) -> io::Result<Vec<rustls::ProtocolVersion>> {
+    use rustls::ProtocolVersion::{TLSv1_2, TLSv1_3};
+
+    if names.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no TLS protocols enabled"));
+    }
+
+    names.iter()
+        .map(|name| match &*name.to_ascii_lowercase() {
+            "tlsv1.2" => Ok(TLSv1_2),
+            "tlsv1.3" => Ok(TLSv1_3),
+            _ => {
+                let msg = format!("unknown TLS protocol `{}`: expected `TLSv1.2` or `TLSv1.3`",
+                    name);
+
+                Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+            }
+        })
+        .collect()
+}
+
+fn cipher_suites(names: &[String]) -> io::Result<Vec<&'static rustls::SupportedCipherSuite>> {
+    let name_of = |suite: &rustls::SupportedCipherSuite| format!("{:?}", suite.suite);
+
+    if names.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no TLS cipher suites enabled"));
+    }
+
+    names.iter()
+        .map(|name| {
+            rustls::ALL_CIPHERSUITES.iter()
+                .find(|suite| name_of(**suite).eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| {
+                    let known = rustls::ALL_CIPHERSUITES.iter()
+                        .map(|suite| name_of(*suite))
+                        .collect::<Vec<_>>();
+
+                    let msg = format!("unknown TLS cipher suite `{}`: expected one of {}",
+                        name, known.join(", "));
+
+                    io::Error::new(io::ErrorKind::InvalidInput, msg)
+                })
+        })
+        .collect()
+}
+
+pub async fn bind_tls<R: io::BufRead + Send>(
+    address: SocketAddr,
+    config: Config<R>,
+) -> io::Result<TlsListener> {
+    let listener = TcpListener::bind(address).await?;
+    TlsListener::new(listener, config)
+}
+
+impl TlsListener {
+    /// Serves TLS with `config` on the connections accepted by `listener`.
+    pub fn new<R: io::BufRead>(
+        listener: TcpListener,
+        mut config: Config<R>,
+    ) -> io::Result<TlsListener> {
+        let certs = Certs::load(&mut config)?;
+        let resolver = Arc::new(CertResolver { current: RwLock::new(certs) });
+
+        let client_auth = match config.ca_certs {
+            Some(ref mut ca_certs) => {
+                load_client_verifier(ca_certs, config.mandatory_mtls).map_err(|e| {
+                    let msg = format!("malformed TLS CA certificates: {}", e);
+                    io::Error::new(e.kind(), msg)
+                })?
+            }
+            None => rustls::NoClientAuth::new(),
+        };
+
+        let mut tls_config = ServerConfig::new(client_auth);
+        let cache = rustls::ServerSessionMemoryCache::new(1024);
+        tls_config.set_persistence(cache);
+        tls_config.ticketer = rustls::Ticketer::new();
+        tls_config.cert_resolver = resolver.clone();
+        tls_config.ignore_client_order = config.prefer_server_cipher_order;
+        if let Some(protocols) = &config.protocols {
+            tls_config.versions = protocol_versions(protocols)?;
+        }
+
+        if let Some(ciphers) = &config.ciphers {
+            tls_config.ciphersuites = cipher_suites(ciphers)?;
+        }
+
+        let versions = &tls_config.versions;
+        let usable = |s: &&rustls::SupportedCipherSuite| {
+            versions.iter().any(|&v| s.usable_for_version(v))
+        };
+
+        if !tls_config.ciphersuites.iter().any(usable) {
+            let msg = "none of the enabled TLS cipher suites can be used with the enabled \
+                protocols";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        }
+
+        let acceptor = TlsAcceptor::from(Arc::new(tls_config));
+        let pending = FuturesUnordered::new();
+
+        Ok(TlsListener { listener, acceptor, resolver, proxy_protocol: None, pending })
+    }
+}
+
+impl Connection for TlsStream<ProxiedStream> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.get_ref().0.remote_addr()
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.get_ref().0.local_addr()
+    }
+
+    fn peer_certificates(&self) -> Option<Vec<RawCertificate>> {
+        self.get_ref().1.get_peer_certificates()
+            .map(|certs| certs.into_iter().map(|cert| RawCertificate(cert.0)).collect())
+    }
+
+    fn server_name(&self) -> Option<String> {
+        self.get_ref().1.get_sni_hostname().map(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Certs, Config, NamedCert, protocol_versions, cipher_suites};
+
+    macro_rules! private {
+        ($file:literal) => (
+            &include_bytes!(concat!("../../../../examples/tls/private/", $file))[..]
+        )
+    }
+
+    #[test]
+    fn test_sni_selection() {
+        let mut config = Config {
+            cert_chain: private!("cert.pem"),
+            private_key: private!("key.pem"),
+            sni: vec![
+                NamedCert {
+                    names: vec!["ecdsa.rocket.rs".into()],
+                    cert_chain: private!("ecdsa_cert.pem"),
+                    private_key: private!("ecdsa_key.pem"),
+                },
+                NamedCert {
+                    names: vec!["*.ed25519.rocket.rs".into(), "Ed25519.rocket.rs".into()],
+                    cert_chain: private!("ed25519_cert.pem"),
+                    private_key: private!("ed25519_key.pem"),
+                },
+            ],
+            ca_certs: None,
+            mandatory_mtls: false,
+            protocols: None,
+            ciphers: None,
+            prefer_server_cipher_order: false,
+        };
+
+        let certs = Certs::load(&mut config).unwrap();
+        let default = &certs.default.cert[0];
+        let ecdsa = &certs.named["ecdsa.rocket.rs"].cert[0];
+        let ed25519 = &certs.named["ed25519.rocket.rs"].cert[0];
+        assert_ne!(default, ecdsa);
+        assert_ne!(default, ed25519);
+
+        assert_eq!(&certs.select(None).cert[0], default);
+        assert_eq!(&certs.select(Some("rocket.rs")).cert[0], default);
+        assert_eq!(&certs.select(Some("ecdsa.rocket.rs")).cert[0], ecdsa);
+        assert_eq!(&certs.select(Some("ECDSA.rocket.rs")).cert[0], ecdsa);
+        assert_eq!(&certs.select(Some("x.ecdsa.rocket.rs")).cert[0], default);
+        assert_eq!(&certs.select(Some("ed25519.rocket.rs")).cert[0], ed25519);
+        assert_eq!(&certs.select(Some("a.ed25519.rocket.rs")).cert[0], ed25519);
+        assert_eq!(&certs.select(Some("a.b.ed25519.rocket.rs")).cert[0], default);
+    }
+
+    #[test]
+    fn test_duplicate_server_names() {
+        let named = |name: &str| NamedCert {
+            names: vec![name.into()],
+            cert_chain: private!("ecdsa_cert.pem"),
+            private_key: private!("ecdsa_key.pem"),
+        };
+
+        let mut config = Config {
+            cert_chain: private!("cert.pem"),
+            private_key: private!("key.pem"),
+            sni: vec![named("rocket.rs"), named("Rocket.rs")],
+            ca_certs: None,
+            mandatory_mtls: false,
+            protocols: None,
+            ciphers: None,
+            prefer_server_cipher_order: false,
+        };
+
+        let err = Certs::load(&mut config).err().unwrap();
+        assert!(err.to_string().contains("more than once"), "{}", err);
+    }
+
+    #[test]
+    fn test_protocol_and_cipher_names() {
+        let names = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(protocol_versions(&names(&["TLSv1.2", "tlsv1.3"])).unwrap().len(), 2);
+        let err = protocol_versions(&names(&["TLSv1.1"])).unwrap_err();
+        assert!(err.to_string().contains("unknown TLS protocol `TLSv1.1`"), "{}", err);
+        assert!(protocol_versions(&[]).is_err());
+
+        let ciphers = names(&["TLS13_AES_256_GCM_SHA384", "tls_ecdhe_rsa_with_aes_128_gcm_sha256"]);
+        assert_eq!(cipher_suites(&ciphers).unwrap().len(), 2);
+        let err = cipher_suites(&names(&["TLS_RSA_WITH_RC4_128_MD5"])).unwrap_err();
+        assert!(err.to_string().contains("unknown TLS cipher suite"), "{}", err);
+        assert!(err.to_string().contains("TLS13_AES_256_GCM_SHA384"), "{}", err);
+        assert!(cipher_suites(&[]).is_err());
+    }
+}
@@ -413,6 +413,19 @@ pub enum UriQueryArgument<'a> {
     Value(&'a dyn UriDisplay<Query>)
 }
 
+// Used by code generation.
+#[doc(hidden)]
+pub struct UriPathSegment<'a>(pub &'a [&'a dyn UriDisplay<Path>]);
+
+// Used by code generation.
+impl UriDisplay<Path> for UriPathSegment<'_> {
+    fn fmt(&self, f: &mut Formatter<'_, Path>) -> fmt::Result {
+        // The pieces are written at once so that they form a single segment.
+        let segment = self.0.iter().map(|piece| piece.to_string()).collect::<String>();
+        f.write_raw(segment)
+    }
+}
+
 // Used by code generation.
 #[doc(hidden)]
 pub struct UriArguments<'a> {
@@ -170,7 +170,8 @@ impl<'a> Origin<'a> {
     }
 
     // Parses an `Origin` that may contain `<` or `>` characters which are
-    // invalid according to the RFC but used by Rocket's routing URIs.
+    // invalid according to the RFC but used by Rocket's routing URIs, as well
+    // as any visible ASCII in parameter constraints like `<id:[0-9]{2}>`.
     // Don't use this outside of Rocket!
     #[doc(hidden)]
     pub fn parse_route(string: &'a str) -> Result<Origin<'a>, Error<'a>> {
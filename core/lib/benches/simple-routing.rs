@@ -36,6 +36,29 @@ fn rocket() -> rocket::Rocket {
         ])
 }
 
+// An application with many routes, like a large API, mounted programmatically.
+fn many_routes_rocket() -> rocket::Rocket {
+    use rocket::{Route, Request, Data};
+    use rocket::handler::{HandlerFuture, Outcome};
+    use rocket::http::Method;
+
+    fn handler<'r>(req: &'r Request<'_>, _: Data) -> HandlerFuture<'r> {
+        Outcome::from(req, "resource").pin()
+    }
+
+    let routes: Vec<_> = (0..600)
+        .map(|i| match i % 3 {
+            0 => format!("/resource{}", i),
+            1 => format!("/resource{}/<id>", i),
+            _ => format!("/resource{}/<id>/<rest..>", i),
+        })
+        .map(|path| Route::new(Method::Get, path, handler))
+        .collect();
+
+    let config = rocket::Config::figment().merge(("log_level", "off"));
+    rocket::custom(config).mount("/", routes)
+}
+
 use bencher::Bencher;
 use rocket::local::blocking::Client;
 
@@ -109,6 +132,23 @@ fn bench_simple_routing(b: &mut Bencher) {
     });
 }
 
+fn bench_many_routes(b: &mut Bencher) {
+    let client = Client::tracked(many_routes_rocket()).unwrap();
+
+    // Hold all of the requests we're going to make during the benchmark.
+    let mut requests = vec![];
+    requests.push(client.get("/resource0"));
+    requests.push(client.get("/resource301/abc"));
+    requests.push(client.get("/resource599/abc/def/ghi"));
+    requests.push(client.get("/missing"));
+
+    b.iter(|| {
+        for request in &requests {
+            request.clone().dispatch();
+        }
+    });
+}
+
 benchmark_main!(benches);
 benchmark_group! {
     benches,
@@ -117,4 +157,5 @@ benchmark_group! {
     bench_get_put_post_index,
     bench_dynamic,
     bench_simple_routing,
+    bench_many_routes,
 }
@@ -23,6 +23,8 @@ pub struct StaticRouteInfo {
     pub handler: StaticHandler,
     /// The route's rank, if any.
     pub rank: Option<isize>,
+    /// The route's handler timeout, if any.
+    pub timeout: Option<std::time::Duration>,
 }
 
 /// Information generated by the `catch` attribute during codegen.
@@ -0,0 +1,55 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Whether Rocket must be started via systemd socket activation.
+///
+/// With socket activation, a service manager such as systemd binds the
+/// service's sockets itself and passes them to the service when it starts, via
+/// the `LISTEN_FDS` and `LISTEN_PID` environment variables. Because the
+/// sockets outlive the service, connections are queued rather than refused
+/// while the service restarts, enabling zero-downtime deploys.
+///
+/// With `socket_activation` configured, Rocket adopts every TCP and Unix domain
+/// socket passed to it instead of binding to the configured `endpoints`,
+/// `address` and `port`, or `unix` socket. A TCP socket is served with the
+/// configuration of the matching endpoint, if any: the endpoint whose `name`
+/// is the socket's `FileDescriptorName=` or, failing that, the endpoint bound to
+/// the socket's address. If no `endpoints` are configured, the top-level `tls`
+/// configuration is used for every TCP socket.
+///
+/// With `required`, launch fails if no sockets were passed. With `optional`,
+/// Rocket binds its sockets itself, as if socket activation were not
+/// configured, which is useful when the same configuration is used both under
+/// systemd and during development.
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use rocket::config::SocketActivation;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("socket_activation", "required"));
+///
+/// let config = rocket::Config::from(figment);
+/// assert_eq!(config.socket_activation, Some(SocketActivation::Required));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketActivation {
+    /// Adopt passed sockets if there are any, binding otherwise:
+    /// `"optional"`.
+    Optional,
+    /// Fail to launch if no sockets were passed: `"required"`.
+    Required,
+}
+
+impl fmt::Display for SocketActivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocketActivation::Optional => write!(f, "optional"),
+            SocketActivation::Required => write!(f, "required"),
+        }
+    }
+}
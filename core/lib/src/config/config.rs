@@ -6,7 +6,8 @@ use figment::value::{Map, Dict};
 use serde::{Deserialize, Serialize};
 use yansi::Paint;
 
-use crate::config::{SecretKey, TlsConfig, LogLevel};
+use crate::config::{SecretKey, TlsConfig, UnixConfig, ShutdownConfig, Timeouts, LogLevel};
+use crate::config::{LoadLimits, ProxyProtocol, IpRange, Endpoint, SocketActivation};
 use crate::data::Limits;
 
 /// Rocket server configuration.
@@ -57,6 +58,15 @@ pub struct Config {
     pub address: IpAddr,
     /// Port to serve on. **(default: `8000`)**
     pub port: u16,
+    /// Endpoints to serve on instead of `address`, `port`, and `tls`, if any.
+    /// **(default: `[]`)**
+    pub endpoints: Vec<Endpoint>,
+    /// Unix domain socket to serve on instead of `address` and `port`, if
+    /// any. **(default: `None`)**
+    pub unix: Option<UnixConfig>,
+    /// Whether to adopt sockets passed via systemd socket activation, if at
+    /// all. **(default: `None`)**
+    pub socket_activation: Option<SocketActivation>,
     /// Number of threads to use for executing futures. **(default: `cores * 2`)**
     pub workers: u16,
     /// Keep-alive timeout in seconds; disabled when `0`. **(default: `5`)**
@@ -70,11 +80,30 @@ pub struct Config {
     pub secret_key: SecretKey,
     /// The TLS configuration, if any. **(default: `None`)**
     pub tls: Option<TlsConfig>,
+    /// Whether to decode PROXY protocol headers, if at all. **(default:
+    /// `None`)**
+    pub proxy_protocol: Option<ProxyProtocol>,
+    /// Address ranges of proxies whose forwarding headers are trusted.
+    /// **(default: `[]`)**
+    pub trusted_proxies: Vec<IpRange>,
     /// Streaming read size limits. **(default: [`Limits::default()`])**
     pub limits: Limits,
+    /// Request processing timeouts. **(default: [`Timeouts::default()`])**
+    pub timeouts: Timeouts,
+    /// Connection and in-flight request limits. **(default:
+    /// [`LoadLimits::default()`])**
+    pub load: LoadLimits,
+    /// Whether to respond with `405 Method Not Allowed` instead of `404 Not
+    /// Found` when only routes for other methods match a request's path.
+    /// **(default: `true`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub method_not_allowed: bool,
     /// Whether `ctrl-c` initiates a server shutdown. **(default: `true`)**
     #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
     pub ctrlc: bool,
+    /// Graceful shutdown configuration. **(default:
+    /// [`ShutdownConfig::default()`])**
+    pub shutdown: ShutdownConfig,
 }
 
 impl Default for Config {
@@ -130,14 +159,23 @@ impl Config {
         Config {
             address: Ipv4Addr::new(127, 0, 0, 1).into(),
             port: 8000,
+            endpoints: vec![],
+            unix: None,
+            socket_activation: None,
             workers: num_cpus::get() as u16 * 2,
             keep_alive: 5,
             log_level: LogLevel::Normal,
             cli_colors: true,
             secret_key: SecretKey::zero(),
             tls: None,
+            proxy_protocol: None,
+            trusted_proxies: vec![],
             limits: Limits::default(),
+            timeouts: Timeouts::default(),
+            load: LoadLimits::default(),
+            method_not_allowed: true,
             ctrlc: true,
+            shutdown: ShutdownConfig::default(),
         }
     }
 
@@ -272,17 +310,50 @@ impl Config {
         cfg!(feature = "tls") && self.tls.is_some()
     }
 
+    /// Returns the endpoints to serve on: `endpoints` if it is set, and
+    /// otherwise a single endpoint formed by `address`, `port`, and `tls`.
+    pub(crate) fn bind_endpoints(&self) -> Vec<Endpoint> {
+        if !self.endpoints.is_empty() {
+            return self.endpoints.clone();
+        }
+
+        let endpoint = Endpoint::new(self.address, self.port);
+        match self.tls.clone() {
+            Some(tls) => vec![endpoint.with_tls(tls)],
+            None => vec![endpoint],
+        }
+    }
+
     pub(crate) fn pretty_print(&self, profile: &Profile) {
         use crate::logger::PaintExt;
 
         launch_info!("{}Configured for {}.", Paint::emoji("🔧 "), profile);
         launch_info_!("address: {}", Paint::default(&self.address).bold());
         launch_info_!("port: {}", Paint::default(&self.port).bold());
+        if !self.endpoints.is_empty() {
+            let endpoints = self.endpoints.iter()
+                .map(|endpoint| endpoint.to_string())
+                .collect::<Vec<_>>();
+
+            launch_info_!("endpoints: {}", Paint::default(endpoints.join(", ")).bold());
+        }
+
+        if let Some(ref unix) = self.unix {
+            launch_info_!("unix socket: {}", Paint::default(unix.path().display()).bold());
+        }
+
+        if let Some(mode) = self.socket_activation {
+            launch_info_!("socket activation: {}", Paint::default(mode).bold());
+        }
+
         launch_info_!("workers: {}", Paint::default(self.workers).bold());
         launch_info_!("log level: {}", Paint::default(self.log_level).bold());
         launch_info_!("secret key: {:?}", Paint::default(&self.secret_key).bold());
         launch_info_!("limits: {}", Paint::default(&self.limits).bold());
+        launch_info_!("timeouts: {}", Paint::default(&self.timeouts).bold());
+        launch_info_!("load limits: {}", Paint::default(&self.load).bold());
         launch_info_!("cli colors: {}", Paint::default(&self.cli_colors).bold());
+        launch_info_!("method not allowed: {}", Paint::default(&self.method_not_allowed).bold());
 
         let ka = self.keep_alive;
         if ka > 0 {
@@ -295,6 +366,25 @@ impl Config {
             true => launch_info_!("tls: {}", Paint::default("enabled").bold()),
             false => launch_info_!("tls: {}", Paint::default("disabled").bold()),
         }
+
+        match self.proxy_protocol {
+            Some(mode) => launch_info_!("proxy protocol: {}", Paint::default(mode).bold()),
+            None => launch_info_!("proxy protocol: {}", Paint::default("disabled").bold()),
+        }
+
+        if self.trusted_proxies.is_empty() {
+            launch_info_!("trusted proxies: {}", Paint::default("none").bold());
+        } else {
+            let proxies = self.trusted_proxies.iter()
+                .map(|range| range.to_string())
+                .collect::<Vec<_>>();
+
+            launch_info_!("trusted proxies: {}", Paint::default(proxies.join(", ")).bold());
+        }
+
+        let shutdown = &self.shutdown;
+        launch_info_!("shutdown: {}", Paint::default(format_args!("grace = {}s, mercy = {}s",
+                    shutdown.grace, shutdown.mercy)).bold());
     }
 }
 
@@ -0,0 +1,202 @@
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TlsConfig;
+use crate::request::{FromRequest, Outcome, Request};
+
+/// An address and port to serve on, with its own TLS configuration.
+///
+/// By default, Rocket serves on a single endpoint formed by the top-level
+/// `address`, `port`, and `tls` configuration parameters. When `endpoints` is
+/// set, Rocket instead serves on every endpoint in the list at once, and the
+/// top-level `address`, `port`, and `tls` are ignored. All endpoints share the
+/// same routes, managed state, and limits. An endpoint's TLS configuration is
+/// not inherited from the top-level `tls`: an endpoint without `tls` is served
+/// over plain HTTP.
+///
+/// The endpoint a request arrived on is available to handlers via
+/// [`Request::endpoint()`] and the `&Endpoint` request guard, which forwards if
+/// the endpoint is unknown. An endpoint's optional `name` makes it easy to
+/// write request guards that restrict routes to an endpoint.
+///
+/// [`Request::endpoint()`]: crate::Request::endpoint()
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use std::net::Ipv4Addr;
+/// use rocket::config::Endpoint;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("endpoints", vec![
+///         Endpoint::new(Ipv4Addr::UNSPECIFIED, 8000),
+///         Endpoint::new(Ipv4Addr::LOCALHOST, 9000).named("admin"),
+///     ]));
+///
+/// let config = rocket::Config::from(figment);
+/// assert_eq!(config.endpoints[1].name.as_deref(), Some("admin"));
+/// assert_eq!(config.endpoints[1].port, 9000);
+/// ```
+///
+/// # Request Guard
+///
+/// The following request guard only succeeds for requests that arrived on the
+/// endpoint named `admin`, forwarding all others:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::config::Endpoint;
+/// use rocket::request::{FromRequest, Outcome, Request};
+///
+/// struct Admin;
+///
+/// #[rocket::async_trait]
+/// impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+///     type Error = ();
+///
+///     async fn from_request(req: &'a Request<'r>) -> Outcome<Self, ()> {
+///         match req.endpoint().and_then(|e| e.name.as_deref()) {
+///             Some("admin") => Outcome::Success(Admin),
+///             _ => Outcome::Forward(()),
+///         }
+///     }
+/// }
+///
+/// #[get("/stats")]
+/// fn stats(_admin: Admin) -> &'static str {
+///     "all systems go"
+/// }
+///
+/// #[get("/endpoint")]
+/// fn endpoint(endpoint: &Endpoint) -> String {
+///     endpoint.to_string()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Endpoint {
+    /// A name identifying the endpoint, if any. **(default: `None`)**
+    #[serde(default)]
+    pub name: Option<String>,
+    /// IP address to serve on.
+    pub address: IpAddr,
+    /// Port to serve on.
+    pub port: u16,
+    /// The TLS configuration, if any. **(default: `None`)**
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+}
+
+impl Endpoint {
+    /// Returns an unnamed endpoint at `address` and `port` without TLS.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv6Addr;
+    /// use rocket::config::Endpoint;
+    ///
+    /// let endpoint = Endpoint::new(Ipv6Addr::UNSPECIFIED, 8000);
+    /// assert_eq!(endpoint.to_string(), "http://[::]:8000");
+    /// ```
+    pub fn new<A: Into<IpAddr>>(address: A, port: u16) -> Endpoint {
+        Endpoint { name: None, address: address.into(), port, tls: None }
+    }
+
+    /// Sets the name of the endpoint to `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv4Addr;
+    /// use rocket::config::Endpoint;
+    ///
+    /// let endpoint = Endpoint::new(Ipv4Addr::LOCALHOST, 9000).named("admin");
+    /// assert_eq!(endpoint.name.as_deref(), Some("admin"));
+    /// ```
+    pub fn named<S: Into<String>>(mut self, name: S) -> Endpoint {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Serves the endpoint over TLS configured by `tls`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv4Addr;
+    /// use rocket::config::{Endpoint, TlsConfig};
+    ///
+    /// let tls = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// let endpoint = Endpoint::new(Ipv4Addr::UNSPECIFIED, 443).with_tls(tls);
+    /// assert!(endpoint.tls.is_some());
+    /// ```
+    pub fn with_tls(mut self, tls: TlsConfig) -> Endpoint {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Returns the socket address of the endpoint.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.address, self.port)
+    }
+
+    /// Returns `true` if the endpoint is served over TLS.
+    pub fn tls_enabled(&self) -> bool {
+        cfg!(feature = "tls") && self.tls.is_some()
+    }
+
+    /// Returns `true` if a connection accepted on `local` could have been
+    /// accepted by a listener bound to this endpoint with an unspecified
+    /// address, that is, one listening on every interface.
+    fn accepts_any(&self, local: SocketAddr) -> bool {
+        self.port == local.port()
+            && self.address.is_unspecified()
+            && (self.address.is_ipv6() || local.is_ipv4())
+    }
+}
+
+/// Returns the endpoint in `endpoints` that accepted a connection whose local
+/// address is `local`. An endpoint bound to the exact address is preferred to
+/// one bound to an unspecified address. If there is only one endpoint, it is
+/// returned regardless of `local`.
+pub(crate) fn find(
+    endpoints: &[Arc<Endpoint>],
+    local: Option<SocketAddr>
+) -> Option<&Arc<Endpoint>> {
+    if let [endpoint] = endpoints {
+        return Some(endpoint);
+    }
+
+    let local = local?;
+    endpoints.iter()
+        .find(|e| e.socket_addr() == local)
+        .or_else(|| endpoints.iter().find(|e| e.accepts_any(local)))
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let proto = if self.tls_enabled() { "https" } else { "http" };
+        write!(f, "{}://{}", proto, self.socket_addr())?;
+        if let Some(ref name) = self.name {
+            write!(f, " ({})", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for &'a Endpoint {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.endpoint() {
+            Some(endpoint) => Outcome::Success(endpoint),
+            None => Outcome::Forward(()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Limits on the number of connections and requests Rocket serves at once.
+///
+/// When either limit is reached, Rocket sheds load rather than queueing work:
+///
+///   * A connection accepted while `max_connections` connections are already
+///     open is closed. If its first bytes show it to be an HTTP/1 connection,
+///     it is first sent the `503 Service Unavailable` catcher's response;
+///     HTTP/2 connections are closed without a response.
+///   * A request received while `max_requests` requests are already in flight
+///     is answered by the `503 Service Unavailable` catcher without being
+///     routed.
+///
+/// In both cases, the response carries a `Retry-After` header with the value
+/// of `retry_after`. A limit that is not set is disabled. The current number of
+/// open connections and in-flight requests can be observed via
+/// [`Load`](crate::Load).
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("load.max_connections", 1024))
+///     .merge(("load.max_requests", 256))
+///     .merge(("load.retry_after", 5));
+///
+/// let config = rocket::Config::from(figment);
+/// assert_eq!(config.load.max_connections, Some(1024));
+/// assert_eq!(config.load.max_requests, Some(256));
+/// assert_eq!(config.load.retry_after, 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LoadLimits {
+    /// Maximum number of open connections. **(default: `None`)**
+    #[serde(default)]
+    pub max_connections: Option<usize>,
+    /// Maximum number of requests in flight. **(default: `None`)**
+    #[serde(default)]
+    pub max_requests: Option<usize>,
+    /// Seconds clients are asked to wait, via `Retry-After`, before retrying a
+    /// shed request. **(default: `1`)**
+    pub retry_after: u32,
+}
+
+impl Default for LoadLimits {
+    fn default() -> Self {
+        LoadLimits { max_connections: None, max_requests: None, retry_after: 1 }
+    }
+}
+
+impl fmt::Display for LoadLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_limit = |limit: Option<usize>| match limit {
+            Some(n) => n.to_string(),
+            None => "unlimited".into(),
+        };
+
+        write!(f, "max connections = {}, max requests = {}, retry after = {}s",
+            fmt_limit(self.max_connections), fmt_limit(self.max_requests),
+            self.retry_after)
+    }
+}
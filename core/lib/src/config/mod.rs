@@ -101,20 +101,37 @@
 mod secret_key;
 mod config;
 mod tls;
+mod unix;
+mod shutdown;
+mod timeouts;
+mod load;
+mod proxy;
+mod endpoint;
+mod activation;
 
 #[doc(hidden)] pub use config::pretty_print_error;
 
 pub use config::Config;
 pub use crate::logger::LogLevel;
 pub use secret_key::SecretKey;
-pub use tls::TlsConfig;
+pub use tls::{TlsConfig, SniCert, MutualTls};
+pub use unix::UnixConfig;
+pub use shutdown::{ShutdownConfig, Sig};
+pub use timeouts::Timeouts;
+pub use load::LoadLimits;
+pub use proxy::{ProxyProtocol, IpRange};
+pub use endpoint::Endpoint;
+pub use activation::SocketActivation;
+pub(crate) use endpoint::find as find_endpoint;
 
 #[cfg(test)]
 mod tests {
-    use std::net::Ipv4Addr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
     use figment::Figment;
 
-    use crate::config::{Config, TlsConfig};
+    use crate::config::{Config, TlsConfig, SniCert, MutualTls, UnixConfig};
+    use crate::config::{ShutdownConfig, Sig, Timeouts, LoadLimits, ProxyProtocol};
+    use crate::config::{IpRange, Endpoint, SocketActivation};
     use crate::logger::LogLevel;
     use crate::data::{Limits, ToByteUnit};
 
@@ -209,6 +226,7 @@ mod tests {
             jail.create_file("Rocket.toml", r#"
                 [global]
                 ctrlc = 0
+                method_not_allowed = false
 
                 [global.tls]
                 certs = "/ssl/cert.pem"
@@ -223,6 +241,7 @@ mod tests {
             let config = Config::from(Config::figment());
             assert_eq!(config, Config {
                 ctrlc: false,
+                method_not_allowed: false,
                 tls: Some(TlsConfig::from_paths("/ssl/cert.pem", "/ssl/key.pem")),
                 limits: Limits::default()
                     .limit("forms", 1.mebibytes())
@@ -245,6 +264,182 @@ mod tests {
                 ..Config::default()
             });
 
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "cert.pem"
+                key = "key.pem"
+                reload_interval = 60
+
+                [global.tls.mutual]
+                ca_certs = "ca.pem"
+                mandatory = true
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths(
+                    jail.directory().join("cert.pem"), jail.directory().join("key.pem")
+                ).with_mutual(MutualTls::from_path(jail.directory().join("ca.pem"))
+                    .with_mandatory(true))
+                    .with_reload_interval(60)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "cert.pem"
+                key = "key.pem"
+                protocols = ["TLSv1.3"]
+                ciphers = ["TLS13_AES_256_GCM_SHA384", "TLS13_AES_128_GCM_SHA256"]
+                prefer_server_cipher_order = true
+
+                [[global.tls.sni]]
+                names = ["rocket.rs", "*.rocket.rs"]
+                certs = "/ssl/rocket.pem"
+                key = "/ssl/rocket-key.pem"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths(
+                    jail.directory().join("cert.pem"), jail.directory().join("key.pem")
+                ).with_sni(SniCert::from_paths(
+                    vec!["rocket.rs", "*.rocket.rs"], "/ssl/rocket.pem", "/ssl/rocket-key.pem"
+                ))
+                    .with_protocols(vec!["TLSv1.3"])
+                    .with_ciphers(vec!["TLS13_AES_256_GCM_SHA384", "TLS13_AES_128_GCM_SHA256"])
+                    .with_prefer_server_cipher_order(true)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.unix]
+                path = "/run/rocket.sock"
+                permissions = 0o660
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                unix: Some(UnixConfig::from_path("/run/rocket.sock").with_permissions(0o660)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.timeouts]
+                handler = "1m30s"
+                headers = 10
+                body_idle = "500ms"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                timeouts: Timeouts {
+                    handler: Some(std::time::Duration::from_secs(90)),
+                    headers: Some(std::time::Duration::from_secs(10)),
+                    body_idle: Some(std::time::Duration::from_millis(500)),
+                },
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.load]
+                max_connections = 1024
+                retry_after = 10
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                load: LoadLimits {
+                    max_connections: Some(1024),
+                    max_requests: None,
+                    retry_after: 10,
+                },
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                proxy_protocol = "optional"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                proxy_protocol: Some(ProxyProtocol::Optional),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                socket_activation = "required"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                socket_activation: Some(SocketActivation::Required),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                trusted_proxies = ["10.0.0.0/8", "2001:db8::/32", "127.0.0.1"]
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            let ranges = ["10.0.0.0/8", "2001:db8::/32", "127.0.0.1/32"].iter()
+                .map(|range| range.parse::<IpRange>().unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(config, Config { trusted_proxies: ranges, ..Config::default() });
+
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                endpoints = [
+                    { address = "0.0.0.0", port = 80 },
+                    { address = "::", port = 80 },
+                    { name = "admin", address = "127.0.0.1", port = 9000 },
+                    { address = "::", port = 443, tls = { certs = "/ssl/c.pem", key = "k.pem" } },
+                ]
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            let tls = TlsConfig::from_paths("/ssl/c.pem", jail.directory().join("k.pem"));
+            assert_eq!(config, Config {
+                endpoints: vec![
+                    Endpoint::new(Ipv4Addr::UNSPECIFIED, 80),
+                    Endpoint::new(Ipv6Addr::UNSPECIFIED, 80),
+                    Endpoint::new(Ipv4Addr::LOCALHOST, 9000).named("admin"),
+                    Endpoint::new(Ipv6Addr::UNSPECIFIED, 443).with_tls(tls),
+                ],
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.shutdown]
+                signals = ["term", "hup"]
+                grace = 20
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                shutdown: ShutdownConfig {
+                    signals: vec![Sig::Term, Sig::Hup].into_iter().collect(),
+                    grace: 20,
+                    ..ShutdownConfig::default()
+                },
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.unix]
+                path = "rocket.sock"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                unix: Some(UnixConfig::from_path(jail.directory().join("rocket.sock"))),
+                ..Config::default()
+            });
+
             jail.set_env("ROCKET_CONFIG", "Other.toml");
             jail.create_file("Other.toml", r#"
                 [default]
@@ -271,6 +466,77 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_find_endpoint() {
+        use std::sync::Arc;
+        use crate::config::find_endpoint;
+
+        let endpoints = vec![
+            Arc::new(Endpoint::new(Ipv4Addr::UNSPECIFIED, 80)),
+            Arc::new(Endpoint::new(Ipv6Addr::UNSPECIFIED, 80)),
+            Arc::new(Endpoint::new(Ipv4Addr::LOCALHOST, 9000)),
+            Arc::new(Endpoint::new(Ipv4Addr::UNSPECIFIED, 9000)),
+        ];
+
+        let find = |local: &str| {
+            let local = local.parse().ok();
+            find_endpoint(&endpoints, local).map(|e| e.socket_addr().to_string())
+        };
+
+        assert_eq!(find("10.0.0.1:80").as_deref(), Some("0.0.0.0:80"));
+        assert_eq!(find("[2001:db8::1]:80").as_deref(), Some("[::]:80"));
+        assert_eq!(find("[::ffff:10.0.0.1]:80").as_deref(), Some("[::]:80"));
+        assert_eq!(find("127.0.0.1:9000").as_deref(), Some("127.0.0.1:9000"));
+        assert_eq!(find("10.0.0.1:9000").as_deref(), Some("0.0.0.0:9000"));
+        assert_eq!(find("[::1]:9000"), None);
+        assert_eq!(find("10.0.0.1:8000"), None);
+        assert_eq!(find(""), None);
+
+        // A single endpoint accepts every connection.
+        assert!(find_endpoint(&endpoints[2..3], None).is_some());
+    }
+
+    #[test]
+    fn test_zero_timeouts() {
+        let extract = |key: &str, value: figment::value::Value| {
+            Figment::from(Config::default()).merge((key, value)).extract::<Config>()
+        };
+
+        for key in &["timeouts.handler", "timeouts.headers", "timeouts.body_idle"] {
+            assert!(extract(key, 0.into()).is_err());
+            assert!(extract(key, "0s".into()).is_err());
+            assert!(extract(key, "0ms0s".into()).is_err());
+            assert!(extract(key, 1.into()).is_ok());
+            assert!(extract(key, "1ms".into()).is_ok());
+        }
+
+        let error = extract("timeouts.handler", 0.into()).unwrap_err();
+        assert!(error.to_string().contains("nonzero duration"), "{}", error);
+    }
+
+    #[test]
+    fn test_ip_range() {
+        let range = |s: &str| s.parse::<IpRange>();
+        let contains = |r: &str, ip: &str| range(r).unwrap().contains(ip.parse().unwrap());
+
+        assert!(contains("10.0.0.0/8", "10.255.0.1"));
+        assert!(!contains("10.0.0.0/8", "11.0.0.1"));
+        assert!(contains("192.168.1.0/23", "192.168.0.9"));
+        assert!(!contains("192.168.1.0/23", "192.168.2.9"));
+        assert!(contains("10.0.0.1", "10.0.0.1"));
+        assert!(!contains("10.0.0.1", "10.0.0.2"));
+        assert!(contains("0.0.0.0/0", "203.0.113.7"));
+        assert!(contains("2001:db8::/32", "2001:db8:cafe::17"));
+        assert!(!contains("2001:db8::/32", "2001:db9::1"));
+        assert!(contains("10.0.0.0/8", "::ffff:10.0.0.1"));
+        assert!(!contains("10.0.0.0/8", "::1"));
+
+        assert!(range("10.0.0.0/33").is_err());
+        assert!(range("2001:db8::/129").is_err());
+        assert!(range("10.0.0/8").is_err());
+        assert!(range("localhost").is_err());
+    }
+
     #[test]
     fn test_profiles_merge() {
         figment::Jail::expect_with(|jail| {
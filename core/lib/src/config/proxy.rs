@@ -0,0 +1,161 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Serialize, Serializer, Deserializer};
+
+/// Whether connections must begin with a PROXY protocol header.
+///
+/// When Rocket is deployed behind a TCP load balancer, the peer of every
+/// connection is the balancer. A balancer that speaks the PROXY protocol
+/// (version 1 or 2) begins each connection with a header naming the original
+/// client. With `proxy_protocol` configured, Rocket decodes this header on both
+/// plain TCP and TLS listeners, and [`Request::remote()`] reports the original
+/// client.
+///
+/// With `required`, a connection without a header is rejected. With `optional`,
+/// such a connection is served as-is, which is useful while migrating a
+/// balancer to the PROXY protocol. Only enable the PROXY protocol when every
+/// client connects through a trusted proxy: a client connecting directly could
+/// otherwise report any address.
+///
+/// The PROXY protocol is not supported on Unix domain sockets: launch fails
+/// if `required` is combined with `unix`, and `optional` is ignored.
+///
+/// [`Request::remote()`]: crate::Request::remote()
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use rocket::config::ProxyProtocol;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("proxy_protocol", "required"));
+///
+/// let config = rocket::Config::from(figment);
+/// assert_eq!(config.proxy_protocol, Some(ProxyProtocol::Required));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// Decode a header if a connection begins with one: `"optional"`.
+    Optional,
+    /// Reject connections that don't begin with a header: `"required"`.
+    Required,
+}
+
+impl fmt::Display for ProxyProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyProtocol::Optional => write!(f, "optional"),
+            ProxyProtocol::Required => write!(f, "required"),
+        }
+    }
+}
+
+/// A range of IP addresses in CIDR notation, such as `10.0.0.0/8` or
+/// `2001:db8::/32`. A single address, such as `10.0.0.1`, is a range containing
+/// only that address.
+///
+/// Ranges are used to configure `trusted_proxies`, the proxies whose
+/// `Forwarded` and `X-Forwarded-*` headers Rocket believes. See
+/// [`Request::client_ip()`] for details.
+///
+/// [`Request::client_ip()`]: crate::Request::client_ip()
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use rocket::config::IpRange;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("trusted_proxies", ["10.0.0.0/8", "::1"]));
+///
+/// let config = rocket::Config::from(figment);
+/// let range: IpRange = "10.0.0.0/8".parse().unwrap();
+/// assert_eq!(config.trusted_proxies[0], range);
+/// assert!(range.contains("10.1.2.3".parse().unwrap()));
+/// assert!(!range.contains("11.0.0.1".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Returns `true` if `ip` is in this range. An IPv4-mapped IPv6 address
+    /// is considered to be the IPv4 address it maps.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        fn matches(a: &[u8], b: &[u8], prefix: u8) -> bool {
+            let (bytes, bits) = ((prefix / 8) as usize, prefix % 8);
+            if a[..bytes] != b[..bytes] {
+                return false;
+            }
+
+            bits == 0 || (a[bytes] ^ b[bytes]) >> (8 - bits) == 0
+        }
+
+        let ip = match ip {
+            IpAddr::V6(v6) => match v6.segments() {
+                [0, 0, 0, 0, 0, 0xffff, ..] => IpAddr::V4(v6.to_ipv4().unwrap()),
+                _ => ip,
+            },
+            IpAddr::V4(_) => ip,
+        };
+
+        match (self.addr, ip) {
+            (IpAddr::V4(a), IpAddr::V4(b)) => matches(&a.octets(), &b.octets(), self.prefix),
+            (IpAddr::V6(a), IpAddr::V6(b)) => matches(&a.octets(), &b.octets(), self.prefix),
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.find('/') {
+            Some(i) => (&s[..i], Some(&s[(i + 1)..])),
+            None => (s, None),
+        };
+
+        let addr: IpAddr = addr.trim().parse()
+            .map_err(|_| format!("invalid IP address `{}`", addr))?;
+
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>().ok()
+                .filter(|&prefix| prefix <= max)
+                .ok_or_else(|| format!("invalid prefix length `{}`", prefix))?,
+            None => max,
+        };
+
+        Ok(IpRange { addr, prefix })
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl Serialize for IpRange {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        ser.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpRange {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(de)?;
+        IpRange::from_str(&string).map_err(|_| de::Error::invalid_value(
+            de::Unexpected::Str(&string),
+            &"an IP address or CIDR range, such as `10.0.0.0/8`"
+        ))
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::collections::HashSet;
+
+use futures::future::{FutureExt, BoxFuture};
+use serde::{Deserialize, Serialize};
+
+/// Graceful shutdown configuration: which signals trigger a shutdown and how
+/// long to wait for in-flight requests and connections to finish.
+///
+/// Once shutdown is triggered, via [`Shutdown`](crate::Shutdown), `ctrl-c`
+/// when [`Config::ctrlc`](crate::Config::ctrlc) is enabled, or one of the
+/// configured `signals`, Rocket stops accepting new connections and proceeds
+/// as follows:
+///
+///   1. In-flight requests are given `grace` seconds to complete. Idle
+///      connections are closed as soon as possible.
+///   2. If `grace` elapses, requests still in flight are cancelled, and open
+///      connections are given `mercy` more seconds to close on their own.
+///   3. If `mercy` elapses, all remaining connections are forcibly closed.
+///
+/// The hard deadline for shutdown is thus `grace + mercy` seconds after it is
+/// triggered. If shutdown completes within `grace`, it is _clean_ and
+/// [`Rocket::launch()`](crate::Rocket::launch()) returns `Ok`. Otherwise,
+/// launch returns an [`ErrorKind::Shutdown`](crate::error::ErrorKind::Shutdown)
+/// error.
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use rocket::config::Sig;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("shutdown.signals", ["term", "hup"]))
+///     .merge(("shutdown.grace", 10))
+///     .merge(("shutdown.mercy", 5));
+///
+/// let config = rocket::Config::from(figment);
+/// assert!(config.shutdown.signals.contains(&Sig::Term));
+/// assert!(config.shutdown.signals.contains(&Sig::Hup));
+/// assert_eq!(config.shutdown.grace, 10);
+/// assert_eq!(config.shutdown.mercy, 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ShutdownConfig {
+    /// Unix signals that trigger a shutdown. Ignored on other platforms.
+    /// **(default: `["term"]`)**
+    pub signals: HashSet<Sig>,
+    /// Seconds in-flight requests have to complete once shutdown is
+    /// triggered. **(default: `2`)**
+    pub grace: u32,
+    /// Seconds connections have to close after `grace` elapses before they
+    /// are forcibly closed. **(default: `3`)**
+    pub mercy: u32,
+}
+
+/// A Unix signal that can trigger a graceful shutdown.
+///
+/// Signals are named in lowercase without the `SIG` prefix: `term` for
+/// `SIGTERM`, `hup` for `SIGHUP`, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sig {
+    /// `SIGTERM`: termination request, as sent by process managers.
+    Term,
+    /// `SIGHUP`: hangup of the controlling terminal.
+    Hup,
+    /// `SIGINT`: interrupt, as sent by `ctrl-c`.
+    Int,
+    /// `SIGQUIT`: quit request.
+    Quit,
+    /// `SIGUSR1`: user-defined signal 1.
+    Usr1,
+    /// `SIGUSR2`: user-defined signal 2.
+    Usr2,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        ShutdownConfig {
+            signals: vec![Sig::Term].into_iter().collect(),
+            grace: 2,
+            mercy: 3,
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// Returns a future that resolves when `ctrl-c` is pressed, if `ctrlc` is
+    /// `true`, or when any of the configured signals is received. Signals that
+    /// can't be listened for are logged and ignored.
+    pub(crate) fn signal(&self, ctrlc: bool) -> BoxFuture<'static, ()> {
+        let mut signals: Vec<BoxFuture<'static, ()>> = vec![];
+        if ctrlc {
+            signals.push(async {
+                if let Err(e) = tokio::signal::ctrl_c().await {
+                    // Error setting up ctrl-c signal. Let the user know.
+                    warn!("Failed to enable `ctrl-c` graceful signal shutdown.");
+                    info_!("Error: {}", e);
+                    futures::future::pending::<()>().await;
+                }
+            }.boxed());
+        }
+
+        #[cfg(unix)]
+        for &sig in &self.signals {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let kind = match sig {
+                Sig::Term => SignalKind::terminate(),
+                Sig::Hup => SignalKind::hangup(),
+                Sig::Int => SignalKind::interrupt(),
+                Sig::Quit => SignalKind::quit(),
+                Sig::Usr1 => SignalKind::user_defined1(),
+                Sig::Usr2 => SignalKind::user_defined2(),
+            };
+
+            match signal(kind) {
+                Ok(mut stream) => signals.push(async move {
+                    stream.recv().await;
+                    info!("Received {}.", sig);
+                }.boxed()),
+                Err(e) => {
+                    warn!("Failed to enable `{}` graceful signal shutdown.", sig);
+                    info_!("Error: {}", e);
+                }
+            }
+        }
+
+        match signals.is_empty() {
+            true => futures::future::pending().boxed(),
+            false => futures::future::select_all(signals).map(|_| ()).boxed(),
+        }
+    }
+}
+
+impl fmt::Display for Sig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Sig::Term => "SIGTERM",
+            Sig::Hup => "SIGHUP",
+            Sig::Int => "SIGINT",
+            Sig::Quit => "SIGQUIT",
+            Sig::Usr1 => "SIGUSR1",
+            Sig::Usr2 => "SIGUSR2",
+        };
+
+        name.fmt(f)
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Timeouts bounding how long Rocket spends on a request.
+///
+/// Durations are configured either as an integer number of seconds or as a
+/// string of integers with units: `ms`, `s`, `m`, or `h`. For instance, `5`,
+/// `"5s"`, `"500ms"`, and `"1m30s"` are all valid durations. A timeout that is
+/// not set is disabled. A zero duration, like `0` or `"0s"`, is rejected: it
+/// would time out every request.
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use std::time::Duration;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("timeouts.handler", "1m30s"));
+///
+/// let config = rocket::Config::from(figment);
+/// assert_eq!(config.timeouts.handler, Some(Duration::from_secs(90)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct Timeouts {
+    /// Maximum time a route's handler may run, including its request and data
+    /// guards. When exceeded, the handler is cancelled and the request fails
+    /// with a `503 Service Unavailable`. Can be overridden per route with the
+    /// `timeout` route attribute parameter. **(default: `None`)**
+    #[serde(default, with = "duration")]
+    pub handler: Option<Duration>,
+    /// Maximum time to wait for a request's headers once a connection is
+    /// awaiting a new request. When exceeded, the connection is closed. If part
+    /// of an HTTP/1 request was received, the `408 Request Timeout` catcher's
+    /// response is written first. **(default: `None`)**
+    #[serde(default, with = "duration")]
+    pub headers: Option<Duration>,
+    /// Maximum time to wait for more request body data while the body is
+    /// being read. When exceeded, the read fails and the request is answered
+    /// by the `408 Request Timeout` catcher. **(default: `None`)**
+    #[serde(default, with = "duration")]
+    pub body_idle: Option<Duration>,
+}
+
+impl fmt::Display for Timeouts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timeouts = [
+            ("handler", self.handler),
+            ("headers", self.headers),
+            ("body_idle", self.body_idle),
+        ];
+
+        let mut enabled = timeouts.iter()
+            .filter_map(|(name, timeout)| timeout.map(|t| (name, t)))
+            .peekable();
+
+        if enabled.peek().is_none() {
+            return write!(f, "disabled");
+        }
+
+        for (i, (name, timeout)) in enabled.enumerate() {
+            if i > 0 { write!(f, ", ")?; }
+            write!(f, "{} = {:?}", name, timeout)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// (De)serializes an `Option<Duration>` from a nonzero integer number of
+/// seconds or a string like `"500ms"` or `"1m30s"`.
+pub(crate) mod duration {
+    use std::fmt;
+    use std::time::Duration;
+
+    use serde::{Serializer, Deserializer, de};
+
+    use crate::http::private::parse_duration;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, ser: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(d) if d.subsec_nanos() == 0 => ser.serialize_some(&format!("{}s", d.as_secs())),
+            Some(d) => ser.serialize_some(&format!("{}ms", d.as_millis())),
+            None => ser.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(de: D) -> Result<Option<Duration>, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Option<Duration>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a nonzero duration in seconds or a string like \"5s\" or \"500ms\"")
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+                de.deserialize_any(self)
+            }
+
+            fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Self::Value, E> {
+                match secs {
+                    0 => Err(E::invalid_value(de::Unexpected::Unsigned(secs), &self)),
+                    _ => Ok(Some(Duration::from_secs(secs))),
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Self::Value, E> {
+                match secs {
+                    secs if secs > 0 => self.visit_u64(secs as u64),
+                    _ => Err(E::invalid_value(de::Unexpected::Signed(secs), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, string: &str) -> Result<Self::Value, E> {
+                match parse_duration(string) {
+                    Ok(d) if d == Duration::from_secs(0) => {
+                        Err(E::invalid_value(de::Unexpected::Str(string), &self))
+                    }
+                    Ok(d) => Ok(Some(d)),
+                    Err(e) => Err(E::custom(format!("invalid duration `{}`: {}", string, e))),
+                }
+            }
+        }
+
+        de.deserialize_option(Visitor)
+    }
+}
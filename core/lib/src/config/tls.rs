@@ -5,7 +5,9 @@ use serde::{Deserialize, Serialize};
 ///
 /// Both `certs` and `key` can be configured as a path or as raw bytes. `certs`
 /// must be a DER-encoded X.509 TLS certificate chain, while `key` must be a
-/// DER-encoded ASN.1 key in either PKCS#8 or PKCS#1 format.
+/// DER-encoded ASN.1 key in PKCS#1 (RSA), SEC1 (ECDSA), or PKCS#8 format. RSA,
+/// ECDSA P-256 and P-384, and Ed25519 keys are supported; the key's type is
+/// detected automatically.
 ///
 /// The following example illustrates manual configuration:
 ///
@@ -23,15 +25,132 @@ use serde::{Deserialize, Serialize};
 ///
 /// When a path is configured in a file source, such as `Rocket.toml`, relative
 /// paths are interpreted as being relative to the source file's directory.
+///
+/// To serve several hostnames from one listener, configure `sni` with a list of
+/// [`SniCert`] entries, each with its own `names`, `certs`, and `key`. The
+/// certificate is selected by the server name the client requests via SNI;
+/// the top-level `certs` and `key` are used when the client requests no name or
+/// a name without an entry. The requested name is available to handlers via
+/// the [`ServerName`](crate::tls::ServerName) request guard.
+///
+/// Client certificate verification, or mutual TLS, is enabled by configuring
+/// `mutual`. See [`MutualTls`] for details.
+///
+/// The enabled protocol versions and cipher suites can be restricted with
+/// `protocols` and `ciphers`. Protocol names are `TLSv1.2` and `TLSv1.3`.
+/// Cipher suite names are the IANA names of the suites, such as
+/// `TLS13_AES_256_GCM_SHA384` or `TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384`.
+/// Names are case-insensitive. Unknown names, or a set of cipher suites of
+/// which none is usable with the enabled protocols, cause launch to fail.
+/// When `prefer_server_cipher_order` is `true`, the cipher suite is chosen by
+/// the order of `ciphers` rather than by the client's preference.
+///
+/// When `reload_interval` is set, every certificate chain and key, including
+/// those in `sni`, is re-read every `reload_interval` seconds while the server
+/// is running. A reload can also be triggered on demand via
+/// [`Rocket::tls_reloader()`]. Either way, only new handshakes use the reloaded
+/// certificates; existing connections are left alone. If reloading fails, the
+/// error is logged and the previous certificates remain in use.
+///
+/// [`Rocket::tls_reloader()`]: crate::Rocket::tls_reloader()
 #[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
 pub struct TlsConfig {
     /// Path or raw bytes for the DER-encoded X.509 TLS certificate chain.
     pub(crate) certs: Either<RelativePathBuf, Vec<u8>>,
-    /// Path or raw bytes to DER-encoded ASN.1 key in either PKCS#8 or PKCS#1
+    /// Path or raw bytes to DER-encoded ASN.1 key in PKCS#1, SEC1, or PKCS#8
+    /// format.
+    pub(crate) key: Either<RelativePathBuf, Vec<u8>>,
+    /// Certificates selected by SNI server name.
+    #[serde(default)]
+    pub(crate) sni: Vec<SniCert>,
+    /// Client certificate verification settings, if any.
+    #[serde(default)]
+    pub(crate) mutual: Option<MutualTls>,
+    /// Seconds between re-reading `certs` and `key`, if any.
+    #[serde(default)]
+    pub(crate) reload_interval: Option<u64>,
+    /// Names of enabled protocol versions. All are enabled when `None`.
+    #[serde(default)]
+    pub(crate) protocols: Option<Vec<String>>,
+    /// Names of enabled cipher suites, in order of preference. All are enabled
+    /// when `None`.
+    #[serde(default)]
+    pub(crate) ciphers: Option<Vec<String>>,
+    /// Whether to prefer the order of `ciphers` over the client's.
+    #[serde(default)]
+    pub(crate) prefer_server_cipher_order: bool,
+}
+
+/// A certificate chain and private key selected for a set of SNI server names.
+///
+/// `certs` and `key` are configured exactly as in [`TlsConfig`]. Each entry in
+/// `names` is either an exact server name, matched case-insensitively, or a
+/// wildcard such as `*.example.com`, which matches any single label in place of
+/// the `*`. Exact names take precedence over wildcards, and each name may
+/// appear in at most one entry.
+///
+/// ```rust
+/// # use rocket::figment::{Figment, providers::{Format, Toml}};
+/// let toml = Toml::string(r#"
+///     [tls]
+///     certs = "/ssl/default/certs.pem"
+///     key = "/ssl/default/key.pem"
+///
+///     [[tls.sni]]
+///     names = ["api.example.com", "*.api.example.com"]
+///     certs = "/ssl/api/certs.pem"
+///     key = "/ssl/api/key.pem"
+/// "#);
+///
+/// let config = rocket::Config::from(Figment::from(rocket::Config::default()).merge(toml));
+/// let sni = config.tls.as_ref().unwrap().sni();
+/// assert_eq!(sni[0].names(), &["api.example.com", "*.api.example.com"]);
+/// ```
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct SniCert {
+    /// The server names this certificate is selected for.
+    pub(crate) names: Vec<String>,
+    /// Path or raw bytes for the DER-encoded X.509 TLS certificate chain.
+    pub(crate) certs: Either<RelativePathBuf, Vec<u8>>,
+    /// Path or raw bytes to DER-encoded ASN.1 key in PKCS#1, SEC1, or PKCS#8
     /// format.
     pub(crate) key: Either<RelativePathBuf, Vec<u8>>,
 }
 
+/// Mutual TLS configuration: trust roots for client certificates and whether
+/// clients must present one.
+///
+/// `ca_certs` can be configured as a path or as raw bytes and must contain one
+/// or more PEM-encoded X.509 certificates. A client certificate is accepted
+/// only if it chains to one of them. When `mandatory` is `true`, clients that
+/// don't present a valid certificate are rejected during the handshake. When
+/// `false`, the default, clients may connect without a certificate.
+///
+/// The verified certificate, if any, is available to handlers via the
+/// `ClientCertificate` request guard, enabled with the `mtls` feature.
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("tls.certs", "/ssl/certs.pem"))
+///     .merge(("tls.key", "/ssl/key.pem"))
+///     .merge(("tls.mutual.ca_certs", "/ssl/ca.pem"))
+///     .merge(("tls.mutual.mandatory", true));
+///
+/// let config = rocket::Config::from(figment);
+/// let mutual = config.tls.as_ref().unwrap().mutual().unwrap();
+/// assert!(mutual.ca_certs().is_left());
+/// assert!(mutual.mandatory());
+/// ```
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct MutualTls {
+    /// Path or raw bytes for the PEM-encoded trusted CA certificates.
+    pub(crate) ca_certs: Either<RelativePathBuf, Vec<u8>>,
+    /// Whether clients must present a valid certificate.
+    #[serde(default)]
+    pub(crate) mandatory: bool,
+}
+
 impl TlsConfig {
     /// Constructs a `TlsConfig` from paths to a `certs` certificate-chain
     /// a `key` private-key. This method does no validation; it simply creates a
@@ -49,7 +168,13 @@ impl TlsConfig {
     {
         TlsConfig {
             certs: Either::Left(certs.as_ref().to_path_buf().into()),
-            key: Either::Left(key.as_ref().to_path_buf().into())
+            key: Either::Left(key.as_ref().to_path_buf().into()),
+            sni: vec![],
+            mutual: None,
+            reload_interval: None,
+            protocols: None,
+            ciphers: None,
+            prefer_server_cipher_order: false,
         }
     }
 
@@ -70,7 +195,13 @@ impl TlsConfig {
     pub fn from_bytes(certs: &[u8], key: &[u8]) -> Self {
         TlsConfig {
             certs: Either::Right(certs.to_vec().into()),
-            key: Either::Right(key.to_vec().into())
+            key: Either::Right(key.to_vec().into()),
+            sni: vec![],
+            mutual: None,
+            reload_interval: None,
+            protocols: None,
+            ciphers: None,
+            prefer_server_cipher_order: false,
         }
     }
 
@@ -90,10 +221,7 @@ impl TlsConfig {
     /// assert!(cert_bytes.iter().all(|&b| b == 0));
     /// ```
     pub fn certs(&self) -> either::Either<std::path::PathBuf, &[u8]> {
-        match &self.certs {
-            Either::Left(path) => either::Either::Left(path.relative()),
-            Either::Right(bytes) => either::Either::Right(&bytes),
-        }
+        to_either(&self.certs)
     }
 
     /// Returns the value of the `key` parameter.
@@ -113,11 +241,394 @@ impl TlsConfig {
     /// assert_eq!(key_path, Path::new("/etc/ssl/key.pem"));
     /// ```
     pub fn key(&self) -> either::Either<std::path::PathBuf, &[u8]> {
-        match &self.key {
-            Either::Left(path) => either::Either::Left(path.relative()),
-            Either::Right(bytes) => either::Either::Right(&bytes),
+        to_either(&self.key)
+    }
+
+    /// Adds `cert` to the certificates selected by SNI server name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{TlsConfig, SniCert};
+    ///
+    /// let names = vec!["api.example.com"];
+    /// let api = SniCert::from_paths(names, "/ssl/api/certs.pem", "/ssl/api/key.pem");
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_sni(api);
+    ///
+    /// assert_eq!(tls_config.sni()[0].names(), &["api.example.com"]);
+    /// ```
+    pub fn with_sni(mut self, cert: SniCert) -> Self {
+        self.sni.push(cert);
+        self
+    }
+
+    /// Returns the certificates selected by SNI server name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(tls_config.sni().is_empty());
+    /// ```
+    pub fn sni(&self) -> &[SniCert] {
+        &self.sni
+    }
+
+    /// Enables client certificate verification with `mutual`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{TlsConfig, MutualTls};
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_mutual(MutualTls::from_path("/ssl/ca.pem"));
+    ///
+    /// assert!(tls_config.mutual().is_some());
+    /// ```
+    pub fn with_mutual(mut self, mutual: MutualTls) -> Self {
+        self.mutual = Some(mutual);
+        self
+    }
+
+    /// Returns the client certificate verification settings, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(tls_config.mutual().is_none());
+    /// ```
+    pub fn mutual(&self) -> Option<&MutualTls> {
+        self.mutual.as_ref()
+    }
+
+    /// Re-reads `certs` and `key` every `seconds` seconds while the server is
+    /// running.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_reload_interval(3600);
+    ///
+    /// assert_eq!(tls_config.reload_interval(), Some(3600));
+    /// ```
+    pub fn with_reload_interval(mut self, seconds: u64) -> Self {
+        self.reload_interval = Some(seconds);
+        self
+    }
+
+    /// Returns the number of seconds between certificate reloads, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(tls_config.reload_interval(), None);
+    /// ```
+    pub fn reload_interval(&self) -> Option<u64> {
+        self.reload_interval
+    }
+
+    /// Enables only the protocol versions named in `protocols`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_protocols(vec!["TLSv1.3"]);
+    ///
+    /// assert_eq!(tls_config.protocols().unwrap(), &["TLSv1.3"]);
+    /// ```
+    pub fn with_protocols<I, S>(mut self, protocols: I) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        self.protocols = Some(protocols.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the names of the enabled protocol versions, or `None` if all
+    /// supported versions are enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(tls_config.protocols().is_none());
+    /// ```
+    pub fn protocols(&self) -> Option<&[String]> {
+        self.protocols.as_deref()
+    }
+
+    /// Enables only the cipher suites named in `ciphers`, in order of
+    /// preference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_ciphers(vec!["TLS13_AES_256_GCM_SHA384", "TLS13_AES_128_GCM_SHA256"]);
+    ///
+    /// assert_eq!(tls_config.ciphers().unwrap().len(), 2);
+    /// ```
+    pub fn with_ciphers<I, S>(mut self, ciphers: I) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        self.ciphers = Some(ciphers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the names of the enabled cipher suites, or `None` if all
+    /// supported cipher suites are enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(tls_config.ciphers().is_none());
+    /// ```
+    pub fn ciphers(&self) -> Option<&[String]> {
+        self.ciphers.as_deref()
+    }
+
+    /// Sets whether the cipher suite is chosen by the server's order of
+    /// preference, that of `ciphers`, instead of the client's.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_prefer_server_cipher_order(true);
+    ///
+    /// assert!(tls_config.prefer_server_cipher_order());
+    /// ```
+    pub fn with_prefer_server_cipher_order(mut self, prefer: bool) -> Self {
+        self.prefer_server_cipher_order = prefer;
+        self
+    }
+
+    /// Returns whether the cipher suite is chosen by the server's order of
+    /// preference instead of the client's.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert!(!tls_config.prefer_server_cipher_order());
+    /// ```
+    pub fn prefer_server_cipher_order(&self) -> bool {
+        self.prefer_server_cipher_order
+    }
+}
+
+impl SniCert {
+    /// Constructs an `SniCert` for the server names `names` from paths to a
+    /// `certs` certificate-chain and a `key` private-key. This method does no
+    /// validation; it simply creates a structure suitable for passing into a
+    /// [`TlsConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SniCert;
+    ///
+    /// let names = vec!["example.com", "*.example.com"];
+    /// let cert = SniCert::from_paths(names, "/ssl/certs.pem", "/ssl/key.pem");
+    /// ```
+    pub fn from_paths<I, S, C, K>(names: I, certs: C, key: K) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>,
+              C: AsRef<std::path::Path>, K: AsRef<std::path::Path>
+    {
+        SniCert {
+            names: names.into_iter().map(Into::into).collect(),
+            certs: Either::Left(certs.as_ref().to_path_buf().into()),
+            key: Either::Left(key.as_ref().to_path_buf().into()),
+        }
+    }
+
+    /// Constructs an `SniCert` for the server names `names` from byte buffers
+    /// to a `certs` certificate-chain and a `key` private-key. This method does
+    /// no validation; it simply creates a structure suitable for passing into a
+    /// [`TlsConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SniCert;
+    ///
+    /// # let certs_buf = &[];
+    /// # let key_buf = &[];
+    /// let cert = SniCert::from_bytes(vec!["example.com"], certs_buf, key_buf);
+    /// ```
+    pub fn from_bytes<I, S>(names: I, certs: &[u8], key: &[u8]) -> Self
+        where I: IntoIterator<Item = S>, S: Into<String>
+    {
+        SniCert {
+            names: names.into_iter().map(Into::into).collect(),
+            certs: Either::Right(certs.to_vec().into()),
+            key: Either::Right(key.to_vec().into()),
+        }
+    }
+
+    /// Returns the server names this certificate is selected for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SniCert;
+    ///
+    /// let cert = SniCert::from_paths(vec!["example.com"], "/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(cert.names(), &["example.com"]);
+    /// ```
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the value of the `certs` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::SniCert;
+    ///
+    /// let cert = SniCert::from_paths(vec!["example.com"], "/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(cert.certs().left().unwrap(), Path::new("/ssl/certs.pem"));
+    /// ```
+    pub fn certs(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        to_either(&self.certs)
+    }
+
+    /// Returns the value of the `key` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::SniCert;
+    ///
+    /// let cert = SniCert::from_paths(vec!["example.com"], "/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(cert.key().left().unwrap(), Path::new("/ssl/key.pem"));
+    /// ```
+    pub fn key(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        to_either(&self.key)
+    }
+}
+
+impl MutualTls {
+    /// Constructs a `MutualTls` from a path to the trusted CA certificates.
+    /// Client certificates are optional. This method does no validation; it
+    /// simply creates a structure suitable for passing into a [`TlsConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert!(!mutual.mandatory());
+    /// ```
+    pub fn from_path<C: AsRef<std::path::Path>>(ca_certs: C) -> Self {
+        MutualTls {
+            ca_certs: Either::Left(ca_certs.as_ref().to_path_buf().into()),
+            mandatory: false,
+        }
+    }
+
+    /// Constructs a `MutualTls` from a byte buffer containing the trusted CA
+    /// certificates. Client certificates are optional. This method does no
+    /// validation; it simply creates a structure suitable for passing into a
+    /// [`TlsConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// # let ca_certs_buf = &[];
+    /// let mutual = MutualTls::from_bytes(ca_certs_buf);
+    /// ```
+    pub fn from_bytes(ca_certs: &[u8]) -> Self {
+        MutualTls {
+            ca_certs: Either::Right(ca_certs.to_vec().into()),
+            mandatory: false,
         }
     }
+
+    /// Sets whether clients must present a valid certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem").with_mandatory(true);
+    /// assert!(mutual.mandatory());
+    /// ```
+    pub fn with_mandatory(mut self, mandatory: bool) -> Self {
+        self.mandatory = mandatory;
+        self
+    }
+
+    /// Returns the value of the `ca_certs` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert_eq!(mutual.ca_certs().left().unwrap(), Path::new("/ssl/ca.pem"));
+    /// ```
+    pub fn ca_certs(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        to_either(&self.ca_certs)
+    }
+
+    /// Returns whether clients must present a valid certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::MutualTls;
+    ///
+    /// let mutual = MutualTls::from_path("/ssl/ca.pem");
+    /// assert!(!mutual.mandatory());
+    /// ```
+    pub fn mandatory(&self) -> bool {
+        self.mandatory
+    }
+}
+
+fn to_either(
+    value: &Either<RelativePathBuf, Vec<u8>>
+) -> either::Either<std::path::PathBuf, &[u8]> {
+    match value {
+        Either::Left(path) => either::Either::Left(path.relative()),
+        Either::Right(bytes) => either::Either::Right(&bytes),
+    }
 }
 
 #[cfg(feature = "tls")]
@@ -125,7 +636,7 @@ type Reader = Box<dyn std::io::BufRead + Sync + Send>;
 
 #[cfg(feature = "tls")]
 impl TlsConfig {
-    pub(crate) fn to_readers(&self) -> std::io::Result<(Reader, Reader)> {
+    pub(crate) fn to_native_config(&self) -> std::io::Result<crate::http::tls::Config<Reader>> {
         use std::{io::{self, Error}, fs};
         use yansi::Paint;
 
@@ -144,6 +655,23 @@ impl TlsConfig {
             }
         }
 
-        Ok((to_reader(&self.certs)?, to_reader(&self.key)?))
+        let sni = self.sni.iter()
+            .map(|cert| Ok(crate::http::tls::NamedCert {
+                names: cert.names.clone(),
+                cert_chain: to_reader(&cert.certs)?,
+                private_key: to_reader(&cert.key)?,
+            }))
+            .collect::<io::Result<_>>()?;
+
+        Ok(crate::http::tls::Config {
+            cert_chain: to_reader(&self.certs)?,
+            private_key: to_reader(&self.key)?,
+            sni,
+            ca_certs: self.mutual.as_ref().map(|m| to_reader(&m.ca_certs)).transpose()?,
+            mandatory_mtls: self.mutual.as_ref().map_or(false, |m| m.mandatory),
+            protocols: self.protocols.clone(),
+            ciphers: self.ciphers.clone(),
+            prefer_server_cipher_order: self.prefer_server_cipher_order,
+        })
     }
 }
@@ -0,0 +1,110 @@
+use figment::value::magic::RelativePathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Unix domain socket configuration: a socket path and optional permissions.
+///
+/// When configured, Rocket binds to a Unix domain socket at `path` instead of
+/// the TCP `address` and `port`. This is useful when Rocket is only reachable
+/// through a reverse proxy running on the same host. If `permissions` is set,
+/// the socket file's mode is set to its value after binding.
+///
+/// A stale socket file left behind by a previous process, that is, one which
+/// no longer accepts connections, is removed at launch. Launch fails if the
+/// socket is live or if `path` exists but is not a socket. Neither TLS nor the
+/// PROXY protocol is supported on Unix domain sockets: launch also fails if TLS
+/// is configured or if `proxy_protocol` is `required`, and an `optional`
+/// `proxy_protocol` is ignored.
+///
+/// The following example illustrates manual configuration:
+///
+/// ```rust
+/// # use rocket::figment::Figment;
+/// use std::path::Path;
+///
+/// let figment = Figment::from(rocket::Config::default())
+///     .merge(("unix.path", "/run/rocket.sock"))
+///     .merge(("unix.permissions", 0o660));
+///
+/// let config = rocket::Config::from(figment);
+/// let unix_config = config.unix.as_ref().unwrap();
+/// assert_eq!(unix_config.path(), Path::new("/run/rocket.sock"));
+/// assert_eq!(unix_config.permissions(), Some(0o660));
+/// ```
+///
+/// When a path is configured in a file source, such as `Rocket.toml`, relative
+/// paths are interpreted as being relative to the source file's directory.
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct UnixConfig {
+    /// Path to the socket file.
+    pub(crate) path: RelativePathBuf,
+    /// Mode to set on the socket file after binding, if any.
+    #[serde(default)]
+    pub(crate) permissions: Option<u32>,
+}
+
+impl UnixConfig {
+    /// Constructs a `UnixConfig` from a path to a socket file. The socket's
+    /// permissions are left as created. This method does no validation; it
+    /// simply creates a structure suitable for passing into a
+    /// [`Config`](crate::Config).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/rocket.sock");
+    /// ```
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Self {
+        UnixConfig {
+            path: path.as_ref().to_path_buf().into(),
+            permissions: None,
+        }
+    }
+
+    /// Sets the mode of the socket file to `mode` after binding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/rocket.sock")
+    ///     .with_permissions(0o660);
+    ///
+    /// assert_eq!(unix_config.permissions(), Some(0o660));
+    /// ```
+    pub fn with_permissions(mut self, mode: u32) -> Self {
+        self.permissions = Some(mode);
+        self
+    }
+
+    /// Returns the path to the socket file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/rocket.sock");
+    /// assert_eq!(unix_config.path(), Path::new("/run/rocket.sock"));
+    /// ```
+    pub fn path(&self) -> std::path::PathBuf {
+        self.path.relative()
+    }
+
+    /// Returns the mode set on the socket file after binding, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/rocket.sock");
+    /// assert_eq!(unix_config.permissions(), None);
+    /// ```
+    pub fn permissions(&self) -> Option<u32> {
+        self.permissions
+    }
+}
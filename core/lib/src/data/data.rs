@@ -1,4 +1,7 @@
 use std::io::Cursor;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use crate::http::hyper;
 use crate::ext::AsyncReadBody;
@@ -45,17 +48,24 @@ pub struct Data {
     buffer: Vec<u8>,
     is_complete: bool,
     stream: AsyncReadBody,
+    timed_out: Option<Arc<AtomicBool>>,
 }
 
 impl Data {
-    pub(crate) async fn from_hyp(body: hyper::Body) -> Data {
-        // TODO.async: This used to also set the read timeout to 5 seconds.
-        // Such a short read timeout is likely no longer necessary, but some
-        // kind of idle timeout should be implemented.
+    /// Creates a `Data` reading from `body`. If `idle` is set, reads fail once
+    /// no body data arrives for that long.
+    pub(crate) async fn from_hyp(body: hyper::Body, idle: Option<Duration>) -> Data {
+        let (stream, timed_out) = match idle {
+            Some(limit) => {
+                let timed_out = Arc::new(AtomicBool::new(false));
+                let stream = AsyncReadBody::with_idle_timeout(body, limit, timed_out.clone());
+                (stream, Some(timed_out))
+            }
+            None => (AsyncReadBody::from(body), None),
+        };
 
-        let stream = AsyncReadBody::from(body);
         let buffer = Vec::with_capacity(PEEK_BYTES / 8);
-        Data { buffer, stream, is_complete: false }
+        Data { buffer, stream, is_complete: false, timed_out }
     }
 
     /// This creates a `data` object from a local data source `data`.
@@ -65,9 +75,18 @@ impl Data {
             buffer: data,
             stream: AsyncReadBody::empty(),
             is_complete: true,
+            timed_out: None,
         }
     }
 
+    /// Returns a closure reporting whether a read from this data's stream has
+    /// exceeded the body idle timeout. The closure remains valid after `self`
+    /// is consumed.
+    pub(crate) fn idle_timeout_watch(&self) -> impl Fn() -> bool + Send + Sync + 'static {
+        let timed_out = self.timed_out.clone();
+        move || timed_out.as_ref().map_or(false, |t| t.load(Ordering::Acquire))
+    }
+
     /// Returns the raw data stream, limited to `limit` bytes.
     ///
     /// The stream contains all of the data in the body of the request,
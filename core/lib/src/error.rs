@@ -10,13 +10,14 @@ use crate::router::Route;
 /// An error that occurs during launch.
 ///
 /// An `Error` is returned by [`launch()`](crate::Rocket::launch()) when
-/// launching an application fails or, more rarely, when the runtime fails after
-/// lauching.
+/// launching an application fails, when the server doesn't shut down cleanly,
+/// or, more rarely, when the runtime fails after lauching.
 ///
 /// # Panics
 ///
-/// A value of this type panics if it is dropped without first being inspected.
-/// An _inspection_ occurs when any method is called. For instance, if
+/// A value of this type panics if it is dropped without first being inspected,
+/// unless its kind is [`ErrorKind::Shutdown`], which is only logged. An
+/// _inspection_ occurs when any method is called. For instance, if
 /// `println!("Error: {}", e)` is called, where `e: Error`, the `Display::fmt`
 /// method being called by `println!` results in `e` being marked as inspected;
 /// a subsequent `drop` of the value will _not_ result in a panic. The following
@@ -69,7 +70,8 @@ pub struct Error {
 /// this is represented by the `Io` variant. A launch error may also occur
 /// because of ill-defined routes that lead to collisions or because a fairing
 /// encountered an error; these are represented by the `Collision` and
-/// `FailedFairing` variants, respectively.
+/// `FailedFairing` variants, respectively. A `Shutdown` error is returned when
+/// connections were still open when the shutdown grace period elapsed.
 #[derive(Debug)]
 pub enum ErrorKind {
     /// Binding to the provided address/port failed.
@@ -82,6 +84,11 @@ pub enum ErrorKind {
     Collision(Vec<(Route, Route)>),
     /// A launch fairing reported an error.
     FailedFairings(Vec<&'static str>),
+    /// The shutdown grace period elapsed with the contained number of
+    /// connections still open. See [`ShutdownConfig`].
+    ///
+    /// [`ShutdownConfig`]: crate::config::ShutdownConfig
+    Shutdown(usize),
 }
 
 impl From<ErrorKind> for Error {
@@ -139,7 +146,8 @@ impl fmt::Display for ErrorKind {
             ErrorKind::Io(e) => write!(f, "I/O error: {}", e),
             ErrorKind::Collision(_) => write!(f, "route collisions detected"),
             ErrorKind::FailedFairings(_) => write!(f, "a launch fairing failed"),
-            ErrorKind::Runtime(e) => write!(f, "runtime error: {}", e)
+            ErrorKind::Runtime(e) => write!(f, "runtime error: {}", e),
+            ErrorKind::Shutdown(n) => write!(f, "shutdown with {} connection(s) open", n),
         }
     }
 }
@@ -199,6 +207,12 @@ impl Drop for Error {
                 info_!("{}", err);
                 panic!("aborting due to runtime failure");
             }
+            // Running out of time to drain connections is an expected outcome
+            // of shutting down, not a misuse, so it's only logged.
+            ErrorKind::Shutdown(n) => {
+                warn!("Rocket did not shut down gracefully.");
+                info_!("{} connection(s) were still open when the grace period elapsed.", n);
+            }
         }
     }
 }
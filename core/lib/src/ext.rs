@@ -1,11 +1,22 @@
+use std::collections::VecDeque;
 use std::io::{self, Cursor};
+use std::net::SocketAddr;
 use std::pin::Pin;
-use std::task::{Poll, Context};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::task::{Poll, Context, Waker};
+use std::time::Duration;
 
 use futures::{ready, stream::Stream};
-use tokio::io::AsyncRead;
+use futures::channel::oneshot;
+use futures::future::{Future, Shared};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::time::{delay_for, Delay};
+use parking_lot::Mutex;
 
 use crate::http::hyper::{self, Bytes, HttpBody};
+use crate::http::{Listener, Connection, RawCertificate};
+use crate::http::private::ProtocolSniffer;
 
 pub struct IntoBytesStream<R> {
     inner: R,
@@ -48,6 +59,30 @@ impl<T: AsyncRead> AsyncReadExt for T { }
 pub struct AsyncReadBody {
     inner: hyper::Body,
     state: State,
+    idle: Option<IdleTimeout>,
+}
+
+/// Fails a body read once no data has arrived for `limit`, recording the
+/// failure in `expired`.
+struct IdleTimeout {
+    limit: Duration,
+    delay: Option<Delay>,
+    expired: Arc<AtomicBool>,
+}
+
+impl IdleTimeout {
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        let limit = self.limit;
+        let delay = self.delay.get_or_insert_with(|| delay_for(limit));
+        match Pin::new(delay).poll(cx) {
+            Poll::Ready(()) => {
+                self.expired.store(true, Ordering::Release);
+                let msg = format!("no request body data received in {:?}", limit);
+                Err(io::Error::new(io::ErrorKind::TimedOut, msg))
+            }
+            Poll::Pending => Ok(()),
+        }
+    }
 }
 
 enum State {
@@ -58,13 +93,20 @@ enum State {
 
 impl AsyncReadBody {
     pub fn empty() -> Self {
-        Self { inner: hyper::Body::empty(), state: State::Done }
+        Self { inner: hyper::Body::empty(), state: State::Done, idle: None }
+    }
+
+    /// Fails reads from `body` with a `TimedOut` error if no data arrives
+    /// for `limit`, setting `expired` when it does.
+    pub fn with_idle_timeout(body: hyper::Body, limit: Duration, expired: Arc<AtomicBool>) -> Self {
+        let idle = Some(IdleTimeout { limit, delay: None, expired });
+        Self { inner: body, state: State::Pending, idle }
     }
 }
 
 impl From<hyper::Body> for AsyncReadBody {
     fn from(body: hyper::Body) -> Self {
-        Self { inner: body, state: State::Pending }
+        Self { inner: body, state: State::Pending, idle: None }
     }
 }
 
@@ -77,7 +119,22 @@ impl AsyncRead for AsyncReadBody {
         loop {
             match self.state {
                 State::Pending => {
-                    match ready!(Pin::new(&mut self.inner).poll_data(cx)) {
+                    let data = match Pin::new(&mut self.inner).poll_data(cx) {
+                        Poll::Ready(data) => data,
+                        Poll::Pending => {
+                            if let Some(ref mut idle) = self.idle {
+                                idle.poll_expired(cx)?;
+                            }
+
+                            return Poll::Pending;
+                        }
+                    };
+
+                    if let Some(ref mut idle) = self.idle {
+                        idle.delay = None;
+                    }
+
+                    match data {
                         Some(Ok(bytes)) => {
                             self.state = State::Partial(Cursor::new(bytes));
                         }
@@ -99,3 +156,430 @@ impl AsyncRead for AsyncReadBody {
         }
     }
 }
+
+/// A future that resolves once its paired `oneshot::Sender` sends `()`. Clones
+/// all resolve together.
+pub type Trigger = Shared<oneshot::Receiver<()>>;
+
+/// Wraps a `Listener` so that every accepted connection is counted while open
+/// and aborted once `force` is triggered.
+pub struct CancellableListener<L> {
+    listener: L,
+    force: Trigger,
+    open: Arc<AtomicUsize>,
+}
+
+/// A connection accepted by a `CancellableListener`.
+pub struct CancellableIo<C> {
+    io: C,
+    force: Trigger,
+    open: Arc<AtomicUsize>,
+}
+
+impl<L> CancellableListener<L> {
+    pub fn new(listener: L, force: Trigger, open: Arc<AtomicUsize>) -> Self {
+        CancellableListener { listener, force, open }
+    }
+}
+
+impl<L: Listener + Unpin> Listener for CancellableListener<L> {
+    type Connection = CancellableIo<L::Connection>;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        let io = ready!(self.listener.poll_accept(cx))?;
+        self.open.fetch_add(1, Ordering::AcqRel);
+        Poll::Ready(Ok(CancellableIo { io, force: self.force.clone(), open: self.open.clone() }))
+    }
+}
+
+impl<C> CancellableIo<C> {
+    /// The wrapped connection.
+    pub fn inner(&self) -> &C {
+        &self.io
+    }
+
+    fn poll_forced(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        match Pin::new(&mut self.force).poll(cx) {
+            Poll::Ready(Ok(())) => {
+                let msg = "connection forcibly closed by server shutdown";
+                Err(io::Error::new(io::ErrorKind::ConnectionAborted, msg))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<C> Drop for CancellableIo<C> {
+    fn drop(&mut self) {
+        self.open.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<C: Connection + Unpin> Connection for CancellableIo<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.io.remote_addr()
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    fn peer_certificates(&self) -> Option<Vec<RawCertificate>> {
+        self.io.peer_certificates()
+    }
+
+    fn server_name(&self) -> Option<String> {
+        self.io.server_name()
+    }
+}
+
+impl<C: AsyncRead + Unpin> AsyncRead for CancellableIo<C> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        self.poll_forced(cx)?;
+        Pin::new(&mut self.io).poll_read(cx, buf)
+    }
+}
+
+impl<C: AsyncWrite + Unpin> AsyncWrite for CancellableIo<C> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        self.poll_forced(cx)?;
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_forced(cx)?;
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
+
+/// Tracks the requests in progress on a single connection so that its
+/// `HeaderTimeoutIo` knows when it is waiting on a new request's headers.
+#[derive(Default)]
+pub struct RequestTracker {
+    in_flight: AtomicUsize,
+    idle_periods: AtomicUsize,
+}
+
+/// Marks a request as in progress until dropped.
+pub struct InFlight(Arc<RequestTracker>);
+
+impl RequestTracker {
+    /// Marks a request as in progress until the returned guard is dropped.
+    pub fn start(self: &Arc<Self>) -> InFlight {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        InFlight(self.clone())
+    }
+
+    /// Returns an identifier for the current idle period if no request is in
+    /// progress and `None` otherwise.
+    fn idle_period(&self) -> Option<usize> {
+        match self.in_flight.load(Ordering::Acquire) {
+            0 => Some(self.idle_periods.load(Ordering::Acquire)),
+            _ => None
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.idle_periods.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Wraps a `Listener` so that connections which fail to deliver a request's
+/// headers within `limit` are closed. Connections that had begun sending an
+/// HTTP/1 request are sent `response` first; idle keep-alive connections and
+/// HTTP/2 connections are closed without a response.
+pub struct HeaderTimeoutListener<L> {
+    listener: L,
+    limit: Option<Duration>,
+    response: Arc<[u8]>,
+}
+
+/// A connection accepted by a `HeaderTimeoutListener`.
+pub struct HeaderTimeoutIo<C> {
+    io: C,
+    limit: Option<Duration>,
+    requests: Arc<RequestTracker>,
+    deadline: Option<(usize, Delay)>,
+    response: Arc<[u8]>,
+    written: Option<usize>,
+    /// The idle period in which request bytes were last received, if any.
+    received: Option<usize>,
+    /// Determines whether the connection speaks HTTP/1.
+    protocol: ProtocolSniffer,
+}
+
+impl<L> HeaderTimeoutListener<L> {
+    /// `response` is written to HTTP/1 connections, verbatim, on timeout. No
+    /// timeout is enforced if `limit` is `None`.
+    pub fn new(listener: L, limit: Option<Duration>, response: Arc<[u8]>) -> Self {
+        HeaderTimeoutListener { listener, limit, response }
+    }
+}
+
+impl<L: Listener + Unpin> Listener for HeaderTimeoutListener<L> {
+    type Connection = HeaderTimeoutIo<L::Connection>;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn poll_accept(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Self::Connection>> {
+        let io = ready!(self.listener.poll_accept(cx))?;
+        Poll::Ready(Ok(HeaderTimeoutIo {
+            io,
+            limit: self.limit,
+            requests: Arc::new(RequestTracker::default()),
+            deadline: None,
+            response: self.response.clone(),
+            written: None,
+            received: None,
+            protocol: ProtocolSniffer::default(),
+        }))
+    }
+}
+
+impl<C> HeaderTimeoutIo<C> {
+    /// The tracker to notify of requests made on this connection.
+    pub fn requests(&self) -> &Arc<RequestTracker> {
+        &self.requests
+    }
+
+    /// Returns `true` if the header timeout has elapsed, (re)arming it at the
+    /// start of each idle period.
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> bool {
+        let (limit, period) = match (self.limit, self.requests.idle_period()) {
+            (Some(limit), Some(period)) => (limit, period),
+            _ => {
+                self.deadline = None;
+                return false;
+            }
+        };
+
+        match self.deadline {
+            Some((armed, ref mut delay)) if armed == period => Pin::new(delay).poll(cx).is_ready(),
+            _ => {
+                let mut delay = delay_for(limit);
+                let expired = Pin::new(&mut delay).poll(cx).is_ready();
+                self.deadline = Some((period, delay));
+                expired
+            }
+        }
+    }
+
+    /// Records that `bytes` were received, noting whether they begin a request
+    /// in the current idle period and, from the first bytes, the protocol.
+    fn observe(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            return;
+        }
+
+        if let Some(period) = self.requests.idle_period() {
+            self.received = Some(period);
+        }
+
+        self.protocol.observe(bytes);
+    }
+
+    /// Whether the timeout response should be written: only when part of an
+    /// HTTP/1 request arrived in the idle period that timed out. Anything else
+    /// would be an unsolicited response or, on HTTP/2, garbage.
+    fn should_respond(&self) -> bool {
+        let timed_out = self.deadline.as_ref().map(|(period, _)| *period);
+        self.protocol.http1() == Some(true) && timed_out.is_some() && self.received == timed_out
+    }
+}
+
+impl<C: AsyncWrite + Unpin> HeaderTimeoutIo<C> {
+    /// Writes out the timeout response, if it should be, then fails with
+    /// `TimedOut`.
+    fn poll_timed_out(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+        let skip = if self.should_respond() { 0 } else { self.response.len() };
+        let written = self.written.get_or_insert(skip);
+        while *written < self.response.len() {
+            match ready!(Pin::new(&mut self.io).poll_write(cx, &self.response[*written..]))? {
+                0 => break,
+                n => *written += n,
+            }
+        }
+
+        ready!(Pin::new(&mut self.io).poll_flush(cx))?;
+        let msg = "request headers not received before the header timeout";
+        Poll::Ready(Err(io::Error::new(io::ErrorKind::TimedOut, msg)))
+    }
+}
+
+impl<C: Connection + Unpin> Connection for HeaderTimeoutIo<C> {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        self.io.remote_addr()
+    }
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    fn peer_certificates(&self) -> Option<Vec<RawCertificate>> {
+        self.io.peer_certificates()
+    }
+
+    fn server_name(&self) -> Option<String> {
+        self.io.server_name()
+    }
+}
+
+impl<C: AsyncRead + AsyncWrite + Unpin> AsyncRead for HeaderTimeoutIo<C> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        if self.written.is_some() || self.poll_expired(cx) {
+            return self.poll_timed_out(cx);
+        }
+
+        let n = ready!(Pin::new(&mut self.io).poll_read(cx, buf))?;
+        self.observe(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<C: AsyncWrite + Unpin> AsyncWrite for HeaderTimeoutIo<C> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+}
+
+/// Bytes written to one end of a `Duplex` pair, to be read from the other.
+#[derive(Default)]
+struct Pipe {
+    buffer: VecDeque<u8>,
+    closed: bool,
+    reader: Option<Waker>,
+    writer: Option<Waker>,
+}
+
+/// One end of an in-memory, bidirectional byte stream created by `duplex()`.
+pub struct Duplex {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+    capacity: usize,
+}
+
+/// Returns the two connected ends of an in-memory, bidirectional byte stream.
+/// Each direction buffers up to `capacity` bytes.
+pub fn duplex(capacity: usize) -> (Duplex, Duplex) {
+    let (a, b) = (Arc::new(Mutex::new(Pipe::default())), Arc::new(Mutex::new(Pipe::default())));
+    let one = Duplex { read: a.clone(), write: b.clone(), capacity };
+    let two = Duplex { read: b, write: a, capacity };
+    (one, two)
+}
+
+impl Pipe {
+    fn wake(waker: &mut Option<Waker>) {
+        if let Some(waker) = waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+        Pipe::wake(&mut self.reader);
+        Pipe::wake(&mut self.writer);
+    }
+}
+
+impl AsyncRead for Duplex {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        let mut pipe = self.read.lock();
+        if pipe.buffer.is_empty() && !buf.is_empty() {
+            if pipe.closed {
+                return Poll::Ready(Ok(0));
+            }
+
+            pipe.reader = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let n = std::cmp::min(buf.len(), pipe.buffer.len());
+        for (byte, slot) in pipe.buffer.drain(..n).zip(buf.iter_mut()) {
+            *slot = byte;
+        }
+
+        Pipe::wake(&mut pipe.writer);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for Duplex {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        let mut pipe = self.write.lock();
+        if pipe.closed {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        let n = std::cmp::min(buf.len(), self.capacity - pipe.buffer.len());
+        if n == 0 && !buf.is_empty() {
+            pipe.writer = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        pipe.buffer.extend(&buf[..n]);
+        Pipe::wake(&mut pipe.reader);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.write.lock().close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for Duplex {
+    fn drop(&mut self) {
+        self.read.lock().close();
+        self.write.lock().close();
+    }
+}
@@ -13,7 +13,8 @@ use crate::fairing::{Fairing, Kind, Info};
 /// # Usage
 ///
 /// Use the [`on_attach`](#method.on_attach), [`on_launch`](#method.on_launch),
-/// [`on_request`](#method.on_request), or [`on_response`](#method.on_response)
+/// [`try_on_launch`](#method.try_on_launch), [`on_request`](#method.on_request),
+/// [`on_response`](#method.on_response), or [`on_shutdown`](#method.on_shutdown)
 /// constructors to create an `AdHoc` structure from a function or closure.
 /// Then, simply attach the structure to the `Rocket` instance.
 ///
@@ -69,6 +70,11 @@ enum AdHocKind {
     /// An ad-hoc **launch** fairing. Called just before Rocket launches.
     Launch(Mutex<Option<Box<dyn FnOnce(&Rocket) + Send + 'static>>>),
 
+    /// An ad-hoc, fallible and asynchronous **launch** fairing. Called just
+    /// before Rocket launches.
+    TryLaunch(Mutex<Option<Box<dyn for<'a> FnOnce(&'a Rocket)
+        -> BoxFuture<'a, Result<(), ()>> + Send + 'static>>>),
+
     /// An ad-hoc **request** fairing. Called when a request is received.
     Request(Box<dyn for<'a> Fn(&'a mut Request<'_>, &'a Data)
         -> BoxFuture<'a, ()> + Send + Sync + 'static>),
@@ -77,6 +83,10 @@ enum AdHocKind {
     /// sent to a client.
     Response(Box<dyn for<'a> Fn(&'a Request<'_>, &'a mut Response<'_>)
         -> BoxFuture<'a, ()> + Send + Sync + 'static>),
+
+    /// An ad-hoc **shutdown** fairing. Called after the server has shut down.
+    Shutdown(Mutex<Option<Box<dyn for<'a> FnOnce(&'a Rocket)
+        -> BoxFuture<'a, ()> + Send + 'static>>>),
 }
 
 impl AdHoc {
@@ -156,6 +166,32 @@ impl AdHoc {
         AdHoc { name, kind: AdHocKind::Launch(Mutex::new(Some(Box::new(f)))) }
     }
 
+    /// Constructs an `AdHoc` launch fairing named `name`. The function `f` will
+    /// be called and the returned `Future` will be `await`ed by Rocket just
+    /// prior to launching. If the `Future` resolves to `Err`, launch is
+    /// aborted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// // A fairing that aborts launch if the configured port is privileged.
+    /// let fairing = AdHoc::try_on_launch("Port Check", |rocket| {
+    ///     Box::pin(async move {
+    ///         match rocket.config().port {
+    ///             0..=1023 => Err(()),
+    ///             _ => Ok(())
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    pub fn try_on_launch<F: Send + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'a> FnOnce(&'a Rocket) -> BoxFuture<'a, Result<(), ()>>
+    {
+        AdHoc { name, kind: AdHocKind::TryLaunch(Mutex::new(Some(Box::new(f)))) }
+    }
+
     /// Constructs an `AdHoc` request fairing named `name`. The function `f`
     /// will be called and the returned `Future` will be `await`ed by Rocket
     /// when a new request is received.
@@ -212,6 +248,29 @@ impl AdHoc {
     {
         AdHoc { name, kind: AdHocKind::Response(Box::new(f)) }
     }
+
+    /// Constructs an `AdHoc` shutdown fairing named `name`. The function `f`
+    /// will be called and the returned `Future` will be `await`ed by Rocket
+    /// once the server has shut down and its connections have drained.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// // A fairing that prints a message once the server has shut down.
+    /// let fairing = AdHoc::on_shutdown("Farewell", |rocket| {
+    ///     Box::pin(async move {
+    ///         println!("Shut down. Goodbye!");
+    /// #       let _ = rocket;
+    ///     })
+    /// });
+    /// ```
+    pub fn on_shutdown<F: Send + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'a> FnOnce(&'a Rocket) -> BoxFuture<'a, ()>
+    {
+        AdHoc { name, kind: AdHocKind::Shutdown(Mutex::new(Some(Box::new(f)))) }
+    }
 }
 
 #[crate::async_trait]
@@ -219,9 +278,10 @@ impl Fairing for AdHoc {
     fn info(&self) -> Info {
         let kind = match self.kind {
             AdHocKind::Attach(_) => Kind::Attach,
-            AdHocKind::Launch(_) => Kind::Launch,
+            AdHocKind::Launch(_) | AdHocKind::TryLaunch(_) => Kind::Launch,
             AdHocKind::Request(_) => Kind::Request,
             AdHocKind::Response(_) => Kind::Response,
+            AdHocKind::Shutdown(_) => Kind::Shutdown,
         };
 
         Info { name: self.name, kind }
@@ -239,11 +299,24 @@ impl Fairing for AdHoc {
         }
     }
 
-    fn on_launch(&self, state: &Rocket) {
-        if let AdHocKind::Launch(ref mutex) = self.kind {
-            let mut opt = mutex.lock().expect("AdHoc::Launch lock");
-            let f = opt.take().expect("internal error: `on_launch` one-call invariant broken");
-            f(state)
+    async fn on_launch(&self, state: &Rocket) -> Result<(), ()> {
+        match self.kind {
+            AdHocKind::Launch(ref mutex) => {
+                let f = mutex.lock()
+                    .expect("AdHoc::Launch lock")
+                    .take()
+                    .expect("internal error: `on_launch` one-call invariant broken");
+                f(state);
+                Ok(())
+            }
+            AdHocKind::TryLaunch(ref mutex) => {
+                let f = mutex.lock()
+                    .expect("AdHoc::TryLaunch lock")
+                    .take()
+                    .expect("internal error: `on_launch` one-call invariant broken");
+                f(state).await
+            }
+            _ => Ok(())
         }
     }
 
@@ -258,4 +331,14 @@ impl Fairing for AdHoc {
             callback(req, res).await;
         }
     }
+
+    async fn on_shutdown(&self, rocket: &Rocket) {
+        if let AdHocKind::Shutdown(ref mutex) = self.kind {
+            let f = mutex.lock()
+                .expect("AdHoc::Shutdown lock")
+                .take()
+                .expect("internal error: `on_shutdown` one-call invariant broken");
+            f(rocket).await
+        }
+    }
 }
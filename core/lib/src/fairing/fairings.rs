@@ -12,6 +12,7 @@ pub struct Fairings {
     launch: Vec<usize>,
     request: Vec<usize>,
     response: Vec<usize>,
+    shutdown: Vec<usize>,
 }
 
 impl Fairings {
@@ -42,6 +43,7 @@ impl Fairings {
             if kind.is(Kind::Launch) { self.launch.push(index); }
             if kind.is(Kind::Request) { self.request.push(index); }
             if kind.is(Kind::Response) { self.response.push(index); }
+            if kind.is(Kind::Shutdown) { self.shutdown.push(index); }
         }
     }
 
@@ -51,10 +53,20 @@ impl Fairings {
         }
     }
 
-    #[inline(always)]
-    pub fn handle_launch(&self, rocket: &Rocket) {
+    /// Runs every launch fairing, even if one fails, and returns the names of
+    /// those that failed, if any.
+    pub async fn handle_launch(&self, rocket: &Rocket) -> Result<(), Vec<&'static str>> {
+        let mut failures = vec![];
         for &i in &self.launch {
-            self.all_fairings[i].on_launch(rocket);
+            let fairing = &self.all_fairings[i];
+            if fairing.on_launch(rocket).await.is_err() {
+                failures.push(fairing.info().name);
+            }
+        }
+
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(failures),
         }
     }
 
@@ -72,6 +84,13 @@ impl Fairings {
         }
     }
 
+    #[inline(always)]
+    pub async fn handle_shutdown(&self, rocket: &Rocket) {
+        for &i in &self.shutdown {
+            self.all_fairings[i].on_shutdown(rocket).await;
+        }
+    }
+
     pub fn failures(&self) -> Option<&[&'static str]> {
         if self.attach_failures.is_empty() {
             None
@@ -98,6 +117,7 @@ impl Fairings {
             self.info_for("launch", &self.launch);
             self.info_for("request", &self.request);
             self.info_for("response", &self.response);
+            self.info_for("shutdown", &self.shutdown);
         }
     }
 }
@@ -10,7 +10,7 @@ use std::ops::BitOr;
 /// # Example
 ///
 /// A simple `Info` structure that can be used for a `Fairing` that implements
-/// all four callbacks:
+/// all five callbacks:
 ///
 /// ```
 /// use rocket::fairing::{Info, Kind};
@@ -19,6 +19,7 @@ use std::ops::BitOr;
 /// Info {
 ///     name: "Example Fairing",
 ///     kind: Kind::Attach | Kind::Launch | Kind::Request | Kind::Response
+///         | Kind::Shutdown
 /// }
 /// # ;
 /// ```
@@ -40,6 +41,7 @@ pub struct Info {
 ///   * Launch
 ///   * Request
 ///   * Response
+///   * Shutdown
 ///
 /// Two `Kind` structures can be `or`d together to represent a combination. For
 /// instance, to represent a fairing that is both a launch and request fairing,
@@ -58,6 +60,8 @@ impl Kind {
     pub const Request: Kind = Kind(0b0100);
     /// `Kind` flag representing a request for a 'response' callback.
     pub const Response: Kind = Kind(0b1000);
+    /// `Kind` flag representing a request for a 'shutdown' callback.
+    pub const Shutdown: Kind = Kind(0b10000);
 
     /// Returns `true` if `self` is a superset of `other`. In other words,
     /// returns `true` if all of the kinds in `other` are also in `self`.
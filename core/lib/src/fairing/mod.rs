@@ -1,4 +1,4 @@
-//! Fairings: callbacks at attach, launch, request, and response time.
+//! Fairings: callbacks at attach, launch, request, response, and shutdown time.
 //!
 //! Fairings allow for structured interposition at various points in the
 //! application lifetime. Fairings can be seen as a restricted form of
@@ -94,12 +94,13 @@ pub use self::info_kind::{Info, Kind};
 ///
 /// ## Fairing Callbacks
 ///
-/// There are four kinds of fairing callbacks: attach, launch, request, and
-/// response. A fairing can request any combination of these callbacks through
-/// the `kind` field of the `Info` structure returned from the `info` method.
-/// Rocket will only invoke the callbacks set in the `kind` field.
+/// There are five kinds of fairing callbacks: attach, launch, request,
+/// response, and shutdown. A fairing can request any combination of these
+/// callbacks through the `kind` field of the `Info` structure returned from the
+/// `info` method. Rocket will only invoke the callbacks set in the `kind`
+/// field.
 ///
-/// The four callback kinds are as follows:
+/// The five callback kinds are as follows:
 ///
 ///   * **Attach (`on_attach`)**
 ///
@@ -122,7 +123,15 @@ pub use self::info_kind::{Info, Kind};
 ///     is called immediately before the Rocket application has launched. At
 ///     this point, Rocket has opened a socket for listening but has not yet
 ///     begun accepting connections. A launch callback can inspect the `Rocket`
-///     instance being launched.
+///     instance being launched and perform asynchronous setup, such as warming
+///     caches or checking that dependencies are reachable.
+///
+///     A launch callback returns `Ok` if it would like launching to proceed
+///     nominally and `Err` otherwise. If a launch callback returns `Err`,
+///     launch is aborted with an [`ErrorKind::FailedFairings`] error. All
+///     launch callbacks are executed, even if one or more signal a failure.
+///
+/// [`ErrorKind::FailedFairings`]: crate::error::ErrorKind::FailedFairings
 ///
 ///   * **Request (`on_request`)**
 ///
@@ -152,11 +161,24 @@ pub use self::info_kind::{Info, Kind};
 ///     request. Additionally, Rocket will automatically strip the body for
 ///     `HEAD` requests _after_ response fairings have run.
 ///
+///   * **Shutdown (`on_shutdown`)**
+///
+///     A shutdown callback, represented by the [`Fairing::on_shutdown()`]
+///     method, is called when the server has stopped, after connections have
+///     drained or been closed as configured by [`ShutdownConfig`], no matter
+///     how the shutdown was triggered. No new requests are handled at this
+///     point. A shutdown callback can release resources held by the
+///     application, such as flushing buffers or deregistering from a service
+///     registry. [`Rocket::launch()`] returns only once all shutdown callbacks
+///     have completed.
+///
+/// [`ShutdownConfig`]: crate::config::ShutdownConfig
+///
 /// # Implementing
 ///
 /// A `Fairing` implementation has one required method: [`info`]. A `Fairing`
 /// can also implement any of the available callbacks: `on_attach`, `on_launch`,
-/// `on_request`, and `on_response`. A `Fairing` _must_ set the appropriate
+/// `on_request`, `on_response`, and `on_shutdown`. A `Fairing` _must_ set the appropriate
 /// callback kind in the `kind` field of the returned `Info` structure from
 /// [`info`] for a callback to actually be called by Rocket.
 ///
@@ -212,7 +234,7 @@ pub use self::info_kind::{Info, Kind};
 ///         # unimplemented!()
 ///     }
 ///
-///     fn on_launch(&self, rocket: &Rocket) {
+///     async fn on_launch(&self, rocket: &Rocket) -> Result<(), ()> {
 ///         /* ... */
 ///         # unimplemented!()
 ///     }
@@ -226,6 +248,11 @@ pub use self::info_kind::{Info, Kind};
 ///         /* ... */
 ///         # unimplemented!()
 ///     }
+///
+///     async fn on_shutdown(&self, rocket: &Rocket) {
+///         /* ... */
+///         # unimplemented!()
+///     }
 /// }
 /// ```
 ///
@@ -416,18 +443,20 @@ pub trait Fairing: Send + Sync + 'static {
     /// The default implementation of this method simply returns `Ok(rocket)`.
     async fn on_attach(&self, rocket: Rocket) -> Result<Rocket, Rocket> { Ok(rocket) }
 
-    /// The launch callback.
+    /// The launch callback. Returns `Ok` if launch should proceed and `Err` if
+    /// launch should be aborted.
     ///
     /// This method is called just prior to launching the application if
     /// `Kind::Launch` is in the `kind` field of the `Info` structure for this
     /// fairing. The `Rocket` parameter corresponds to the application that
-    /// will be launched.
+    /// will be launched. Connections are not accepted until all launch
+    /// callbacks have completed.
     ///
     /// ## Default Implementation
     ///
-    /// The default implementation of this method does nothing.
+    /// The default implementation of this method simply returns `Ok(())`.
     #[allow(unused_variables)]
-    fn on_launch(&self, rocket: &Rocket) {}
+    async fn on_launch(&self, rocket: &Rocket) -> Result<(), ()> { Ok(()) }
 
     /// The request callback.
     ///
@@ -454,6 +483,19 @@ pub trait Fairing: Send + Sync + 'static {
     /// The default implementation of this method does nothing.
     #[allow(unused_variables)]
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {}
+
+    /// The shutdown callback.
+    ///
+    /// This method is called once the server has shut down and its connections
+    /// have drained if `Kind::Shutdown` is in the `kind` field of the `Info`
+    /// structure for this fairing. The `Rocket` parameter corresponds to the
+    /// application that was running.
+    ///
+    /// ## Default Implementation
+    ///
+    /// The default implementation of this method does nothing.
+    #[allow(unused_variables)]
+    async fn on_shutdown(&self, rocket: &Rocket) {}
 }
 
 #[crate::async_trait]
@@ -469,8 +511,8 @@ impl<T: Fairing> Fairing for std::sync::Arc<T> {
     }
 
     #[inline]
-    fn on_launch(&self, rocket: &Rocket) {
-        (self as &T).on_launch(rocket)
+    async fn on_launch(&self, rocket: &Rocket) -> Result<(), ()> {
+        (self as &T).on_launch(rocket).await
     }
 
     #[inline]
@@ -482,4 +524,9 @@ impl<T: Fairing> Fairing for std::sync::Arc<T> {
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         (self as &T).on_response(req, res).await;
     }
+
+    #[inline]
+    async fn on_shutdown(&self, rocket: &Rocket) {
+        (self as &T).on_shutdown(rocket).await;
+    }
 }
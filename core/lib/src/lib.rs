@@ -62,10 +62,11 @@
 //!
 //! ## Features
 //!
-//! There are two optional, disabled-by-default features:
+//! There are three optional, disabled-by-default features:
 //!
 //!   * **secrets:** Enables support for [private cookies].
 //!   * **tls:** Enables support for [TLS].
+//!   * **mtls:** Enables support for [mutual TLS] and implies **tls**.
 //!
 //! The features can be enabled in `Rocket.toml`:
 //!
@@ -76,6 +77,7 @@
 //!
 //! [private cookies]: https://rocket.rs/master/guide/requests/#private-cookies
 //! [TLS]: https://rocket.rs/master/guide/configuration/#tls
+//! [mutual TLS]: https://rocket.rs/master/guide/configuration/#mutual-tls
 //!
 //! ## Configuration
 //!
@@ -120,6 +122,8 @@ pub mod handler;
 pub mod fairing;
 pub mod error;
 pub mod catcher;
+#[cfg(feature = "tls")] pub mod tls;
+#[cfg(feature = "mtls")] pub mod mtls;
 
 // Reexport of HTTP everything.
 pub mod http {
@@ -133,6 +137,7 @@ pub mod http {
 }
 
 mod shutdown;
+mod load;
 mod router;
 mod rocket;
 mod server;
@@ -149,6 +154,7 @@ pub use crate::router::Route;
 pub use crate::request::{Request, State};
 pub use crate::rocket::Rocket;
 pub use crate::shutdown::Shutdown;
+pub use crate::load::Load;
 
 /// Alias to [`Rocket::ignite()`] Creates a new instance of `Rocket`.
 pub fn ignite() -> Rocket {
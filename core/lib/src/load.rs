@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::request::{FromRequest, Outcome, Request};
+
+/// A request guard and handle reporting how busy a Rocket server is.
+///
+/// `Load` reports the number of connections currently open and the number of
+/// requests currently in flight, as limited by
+/// [`LoadLimits`](crate::config::LoadLimits). The counts are live: every clone
+/// of a `Load` observes the same values, so a handle can be retrieved once via
+/// [`Rocket::load()`](crate::Rocket::load()) and polled, e.g., by a metrics
+/// exporter.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Load;
+///
+/// #[get("/metrics")]
+/// fn metrics(load: Load) -> String {
+///     format!("connections {}\nrequests {}\n", load.connections(), load.requests())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Load {
+    pub(crate) connections: Arc<AtomicUsize>,
+    pub(crate) requests: Arc<AtomicUsize>,
+}
+
+/// Counts a request as in flight until dropped.
+pub(crate) struct InFlightRequest(Arc<AtomicUsize>);
+
+impl Load {
+    /// Returns the number of connections currently open.
+    #[inline]
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of requests currently in flight.
+    #[inline]
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Acquire)
+    }
+
+    /// Counts a new request as in flight until the returned guard is dropped.
+    /// Also returns the number of requests in flight, including the new one.
+    pub(crate) fn start_request(&self) -> (InFlightRequest, usize) {
+        let count = self.requests.fetch_add(1, Ordering::AcqRel) + 1;
+        (InFlightRequest(self.requests.clone()), count)
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Load {
+    type Error = std::convert::Infallible;
+
+    #[inline]
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(request.state.load.clone())
+    }
+}
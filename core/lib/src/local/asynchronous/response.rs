@@ -6,6 +6,7 @@ use tokio::io::AsyncRead;
 
 use crate::http::CookieJar;
 use crate::{Request, Response};
+use crate::response::Upgraded;
 
 /// An `async` response from a dispatched [`LocalRequest`](super::LocalRequest).
 ///
@@ -116,6 +117,54 @@ impl LocalResponse<'_> {
         self.response.body_bytes().await
     }
 
+    /// Performs the connection upgrade requested by this response, if any,
+    /// over an in-memory connection. The response's [`Upgrade`] is started on
+    /// one end of the connection; the other end, for the test to speak the
+    /// upgraded protocol over, is returned. Returns `None` if the response
+    /// does not upgrade the connection.
+    ///
+    /// [`Upgrade`]: crate::response::Upgrade
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::Response;
+    /// use rocket::local::asynchronous::Client;
+    /// use rocket::response::{Upgrade, Upgraded};
+    /// use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// struct Greet;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Upgrade for Greet {
+    ///     async fn start(self: Box<Self>, mut io: Upgraded) {
+    ///         let _ = io.write_all(b"hello").await;
+    ///     }
+    /// }
+    ///
+    /// #[get("/")]
+    /// fn greet() -> Response<'static> {
+    ///     Response::build().upgrade("greet", Greet).finalize()
+    /// }
+    ///
+    /// # rocket::async_test(async {
+    /// let client = Client::tracked(rocket::ignite().mount("/", routes![greet])).await.unwrap();
+    /// let mut response = client.get("/").dispatch().await;
+    /// let mut io = response.upgrade().expect("upgrade");
+    ///
+    /// let mut greeting = String::new();
+    /// io.read_to_string(&mut greeting).await.unwrap();
+    /// assert_eq!(greeting, "hello");
+    /// # });
+    /// ```
+    pub fn upgrade(&mut self) -> Option<Upgraded> {
+        let upgrade = self.response.take_upgrade()?;
+        let (client, server) = crate::ext::duplex(crate::response::DEFAULT_CHUNK_SIZE);
+        tokio::spawn(upgrade.start(Upgraded::new(server)));
+        Some(Upgraded::new(client))
+    }
+
     // Generates the public API methods, which call the private methods above.
     pub_response_impl!("# use rocket::local::asynchronous::Client;
         use rocket::local::asynchronous::LocalResponse;" async await);
@@ -0,0 +1,145 @@
+//! Support for mutual TLS client certificates.
+//!
+//! When mutual TLS is configured via [`MutualTls`](crate::config::MutualTls),
+//! clients may present an X.509 certificate during the TLS handshake, which
+//! Rocket verifies against the configured trust roots. A verified certificate
+//! is exposed to handlers via the [`ClientCertificate`] request guard.
+//!
+//! This module is only available when the `mtls` feature is enabled.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::request::{FromRequest, Outcome, Request};
+use crate::http::{Status, RawCertificate};
+
+/// A request guard for a verified client certificate.
+///
+/// The guard succeeds when the client presented a certificate that was
+/// verified against the configured mutual TLS trust roots. If the client did
+/// not present a certificate, including when mutual TLS is not configured or
+/// the connection does not use TLS, the request is forwarded. If the
+/// certificate cannot be parsed, the guard fails with a status of
+/// `401 Unauthorized`.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::mtls::ClientCertificate;
+///
+/// #[get("/whoami")]
+/// fn whoami(cert: ClientCertificate) -> String {
+///     format!("subject: {}, serial: {}", cert.subject(), cert.serial())
+/// }
+///
+/// #[get("/whoami", rank = 2)]
+/// fn anonymous() -> &'static str {
+///     "no client certificate presented"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCertificate {
+    subject: String,
+    issuer: String,
+    serial: String,
+    subject_alt_names: Vec<String>,
+    chain: Arc<Vec<RawCertificate>>,
+}
+
+/// An error parsing a client certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl ClientCertificate {
+    fn parse(chain: Arc<Vec<RawCertificate>>) -> Result<ClientCertificate, Error> {
+        use x509_parser::extensions::GeneralName;
+
+        let end_entity = chain.first()
+            .ok_or_else(|| Error("empty certificate chain".into()))?;
+
+        let (_, cert) = x509_parser::parse_x509_der(&end_entity.0)
+            .map_err(|e| Error(e.to_string()))?;
+
+        let tbs = &cert.tbs_certificate;
+        let subject_alt_names = tbs.subject_alternative_name()
+            .map(|(_, san)| {
+                san.general_names.iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(s) => Some(s.to_string()),
+                        GeneralName::RFC822Name(s) => Some(s.to_string()),
+                        GeneralName::URI(s) => Some(s.to_string()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ClientCertificate {
+            subject: tbs.subject.to_string(),
+            issuer: tbs.issuer.to_string(),
+            serial: tbs.serial.to_str_radix(16),
+            subject_alt_names,
+            chain: chain.clone(),
+        })
+    }
+
+    /// Returns the distinguished name of the certificate's subject, for
+    /// instance, `CN=alice, O=Example`.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the distinguished name of the certificate's issuer.
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Returns the certificate's serial number as a lowercase hexadecimal
+    /// string.
+    pub fn serial(&self) -> &str {
+        &self.serial
+    }
+
+    /// Returns the DNS names, email addresses, and URIs in the certificate's
+    /// subject alternative name extension, if any.
+    pub fn subject_alt_names(&self) -> &[String] {
+        &self.subject_alt_names
+    }
+
+    /// Returns the DER encoding of the end-entity certificate.
+    pub fn as_der(&self) -> &[u8] {
+        &self.chain[0].0
+    }
+
+    /// Returns the full certificate chain presented by the client, beginning
+    /// with the end-entity certificate.
+    pub fn chain(&self) -> &[RawCertificate] {
+        &self.chain
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid client certificate: {}", self.0)
+    }
+}
+
+impl std::error::Error for Error { }
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for ClientCertificate {
+    type Error = Error;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        let chain = match &request.connection.client_certificates {
+            Some(chain) => chain.clone(),
+            None => return Outcome::Forward(()),
+        };
+
+        match request.local_cache(|| ClientCertificate::parse(chain)) {
+            Ok(cert) => Outcome::Success(cert.clone()),
+            Err(e) => Outcome::Failure((Status::Unauthorized, e.clone())),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::net::{IpAddr, SocketAddr};
+
+use crate::http::HeaderMap;
+
+/// A hop in the chain of proxies a request was forwarded through, as reported
+/// by a proxy via the `Forwarded` or `X-Forwarded-*` headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Hop<'a> {
+    /// The address of the client of the reporting proxy, if it is known.
+    pub ip: Option<IpAddr>,
+    /// Whether the reporting proxy identified its client at all, if only as an
+    /// unknown or obfuscated node. When `false`, `ip` says nothing about the
+    /// client.
+    pub node: bool,
+    /// The protocol of the request the reporting proxy received.
+    pub proto: Option<&'a str>,
+    /// The `Host` of the request the reporting proxy received.
+    pub host: Option<&'a str>,
+}
+
+/// Returns the hops reported by the RFC 7239 `Forwarded` headers in `headers`
+/// or, if there are none, by the `X-Forwarded-For`, `X-Forwarded-Proto` and
+/// `X-Forwarded-Host` headers. The hops are ordered from the original client to
+/// the proxy nearest to Rocket. A proxy that sends `X-Forwarded-Proto` or
+/// `X-Forwarded-Host` without `X-Forwarded-For` is reported as a single hop
+/// without a node.
+pub(crate) fn hops<'a>(headers: &'a HeaderMap<'_>) -> Vec<Hop<'a>> {
+    let hops = headers.get("Forwarded")
+        .flat_map(|value| split_unquoted(value, ','))
+        .filter(|element| !element.trim().is_empty())
+        .map(parse_element)
+        .collect::<Vec<_>>();
+
+    if !hops.is_empty() {
+        return hops;
+    }
+
+    let list = |name| headers.get(name)
+        .flat_map(|value| value.split(','))
+        .map(|item| item.trim())
+        .collect::<Vec<_>>();
+
+    let (ips, protos, hosts) = (list("X-Forwarded-For"), list("X-Forwarded-Proto"),
+        list("X-Forwarded-Host"));
+
+    // A proxy may append to the `Proto` and `Host` lists, in which case they
+    // line up with the `For` list, or set a single value for the whole chain.
+    let aligned = |list: &[&'a str], i: usize| match list.len() {
+        1 => Some(list[0]),
+        n if n == ips.len() => Some(list[i]),
+        _ => None,
+    };
+
+    // Without a `For` list, the first values are those of the original client.
+    if ips.is_empty() && (!protos.is_empty() || !hosts.is_empty()) {
+        let (proto, host) = (protos.first().copied(), hosts.first().copied());
+        return vec![Hop { ip: None, node: false, proto, host }];
+    }
+
+    ips.iter().enumerate()
+        .map(|(i, ip)| Hop {
+            ip: parse_node(ip),
+            node: true,
+            proto: aligned(&protos, i),
+            host: aligned(&hosts, i),
+        })
+        .collect()
+}
+
+/// Parses a `Forwarded` element such as `for=192.0.2.60;proto=https`.
+fn parse_element(element: &str) -> Hop<'_> {
+    let mut hop = Hop::default();
+    for pair in split_unquoted(element, ';') {
+        let (key, value) = match pair.find('=') {
+            Some(i) => (pair[..i].trim(), unquote(pair[(i + 1)..].trim())),
+            None => continue,
+        };
+
+        if key.eq_ignore_ascii_case("for") {
+            hop.ip = parse_node(value);
+            hop.node = true;
+        } else if key.eq_ignore_ascii_case("proto") {
+            hop.proto = Some(value);
+        } else if key.eq_ignore_ascii_case("host") {
+            hop.host = Some(value);
+        }
+    }
+
+    hop
+}
+
+/// Parses a node such as `192.0.2.60`, `192.0.2.60:80`, `[2001:db8::1]`, or
+/// `[2001:db8::1]:80`. Unknown and obfuscated nodes have no address.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+
+    match node.starts_with('[') && node.ends_with(']') {
+        true => node[1..(node.len() - 1)].parse().ok(),
+        false => None,
+    }
+}
+
+/// Removes the quotes around a quoted-string `value`, if it is one.
+fn unquote(value: &str) -> &str {
+    match value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        true => &value[1..(value.len() - 1)],
+        false => value,
+    }
+}
+
+/// Splits `string` at each `separator` that isn't within a quoted-string.
+fn split_unquoted(string: &str, separator: char) -> Vec<&str> {
+    let (mut parts, mut start, mut quoted, mut escaped) = (vec![], 0, false, false);
+    for (i, c) in string.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&string[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&string[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::{Header, HeaderMap};
+    use super::{hops, Hop};
+
+    fn assert_hops(headers: &[(&'static str, &'static str)], expected: &[Hop<'_>]) {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.add(Header::new(name, value));
+        }
+
+        assert_eq!(hops(&map), expected);
+    }
+
+    fn hop<'a>(ip: &str, proto: Option<&'a str>, host: Option<&'a str>) -> Hop<'a> {
+        Hop { ip: ip.parse().ok(), node: true, proto, host }
+    }
+
+    #[test]
+    fn test_forwarded() {
+        assert_hops(&[("Forwarded", "for=192.0.2.60;proto=https;host=rocket.rs")],
+            &[hop("192.0.2.60", Some("https"), Some("rocket.rs"))]);
+
+        assert_hops(&[
+            ("Forwarded", r#"for="[2001:db8:cafe::17]:4711", For=unknown"#),
+            ("Forwarded", r#"for=198.51.100.17:80;by=203.0.113.43;host="a;b,c""#),
+        ], &[
+            hop("2001:db8:cafe::17", None, None),
+            hop("unknown", None, None),
+            hop("198.51.100.17", None, Some("a;b,c")),
+        ]);
+
+        // `Forwarded` takes precedence over `X-Forwarded-For`.
+        assert_hops(&[
+            ("X-Forwarded-For", "10.0.0.1"),
+            ("Forwarded", "for=192.0.2.60"),
+        ], &[hop("192.0.2.60", None, None)]);
+    }
+
+    #[test]
+    fn test_x_forwarded() {
+        assert_hops(&[
+            ("X-Forwarded-For", "192.0.2.60, 10.0.0.1"),
+            ("X-Forwarded-For", "10.0.0.2:8080"),
+            ("X-Forwarded-Proto", "https"),
+            ("X-Forwarded-Host", "a.rocket.rs, b.rocket.rs, c.rocket.rs"),
+        ], &[
+            hop("192.0.2.60", Some("https"), Some("a.rocket.rs")),
+            hop("10.0.0.1", Some("https"), Some("b.rocket.rs")),
+            hop("10.0.0.2", Some("https"), Some("c.rocket.rs")),
+        ]);
+
+        assert_hops(&[
+            ("X-Forwarded-For", "192.0.2.60, 10.0.0.1, 10.0.0.2"),
+            ("X-Forwarded-Proto", "https, http"),
+        ], &[
+            hop("192.0.2.60", None, None),
+            hop("10.0.0.1", None, None),
+            hop("10.0.0.2", None, None),
+        ]);
+
+        assert_hops(&[
+            ("X-Forwarded-Proto", "https"),
+            ("X-Forwarded-Host", "rocket.rs"),
+        ], &[Hop { ip: None, node: false, proto: Some("https"), host: Some("rocket.rs") }]);
+
+        assert_hops(&[("X-Real-IP", "192.0.2.60")], &[]);
+    }
+}
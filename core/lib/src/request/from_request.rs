@@ -165,6 +165,13 @@ impl<S, E> IntoOutcome<S, (Status, E), ()> for Result<S, E> {
 ///
 ///     _This implementation always returns successfully._
 ///
+///   * **&Endpoint**
+///
+///     Extracts the [`Endpoint`](crate::config::Endpoint) the request arrived
+///     on. If the endpoint is not known, the request is forwarded.
+///
+///     _This implementation always returns successfully._
+///
 ///   * **Option&lt;T>** _where_ **T: FromRequest**
 ///
 ///     The type `T` is derived from the incoming request using `T`'s
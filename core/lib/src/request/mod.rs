@@ -6,6 +6,7 @@ mod form;
 mod from_request;
 mod state;
 mod query;
+mod forwarded;
 
 #[cfg(test)]
 mod tests;
@@ -13,6 +14,7 @@ mod tests;
 #[doc(hidden)] pub use rocket_codegen::{FromForm, FromFormValue};
 
 pub use self::request::Request;
+pub(crate) use self::request::ConnectionMeta;
 pub use self::from_request::{FromRequest, Outcome};
 pub use self::param::{FromParam, FromSegments};
 pub use self::form::{FromForm, FromFormValue};
@@ -11,11 +11,15 @@ use atomic::{Atomic, Ordering};
 
 use crate::request::{FromParam, FromSegments, FromRequest, Outcome};
 use crate::request::{FromFormValue, FormItems, FormItem};
+use crate::request::forwarded::{self, Hop};
 
-use crate::{Rocket, Config, Shutdown, Route};
-use crate::http::{hyper, uri::{Origin, Segments}};
+use crate::{Rocket, Config, Shutdown, Load, Route};
+use crate::config::Endpoint;
+use crate::http::{hyper, uri::{Origin, Absolute, Segments}};
+use crate::http::ext::IntoOwned;
 use crate::http::{Method, Header, HeaderMap, uncased::UncasedStr};
 use crate::http::{RawStr, ContentType, Accept, MediaType, CookieJar, Cookie};
+use crate::http::RawCertificate;
 use crate::http::private::{Indexed, SmallVec};
 use crate::data::Limits;
 
@@ -29,14 +33,24 @@ pub struct Request<'r> {
     method: Atomic<Method>,
     uri: Origin<'r>,
     headers: HeaderMap<'r>,
-    remote: Option<SocketAddr>,
+    pub(crate) connection: ConnectionMeta,
     pub(crate) state: RequestState<'r>,
 }
 
+/// Information about the connection a request arrived on.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionMeta {
+    pub remote: Option<SocketAddr>,
+    pub client_certificates: Option<Arc<Vec<RawCertificate>>>,
+    pub server_name: Option<String>,
+    pub endpoint: Option<Arc<Endpoint>>,
+}
+
 pub(crate) struct RequestState<'r> {
     pub config: &'r Config,
     pub managed: &'r Container,
     pub shutdown: &'r Shutdown,
+    pub load: &'r Load,
     pub path_segments: SmallVec<[Indices; 12]>,
     pub query_items: Option<SmallVec<[IndexedFormItem; 6]>>,
     pub route: Atomic<Option<&'r Route>>,
@@ -52,7 +66,7 @@ impl Request<'_> {
             method: Atomic::new(self.method()),
             uri: self.uri.clone(),
             headers: self.headers.clone(),
-            remote: self.remote.clone(),
+            connection: self.connection.clone(),
             state: self.state.clone(),
         }
     }
@@ -64,6 +78,7 @@ impl RequestState<'_> {
             config: self.config,
             managed: self.managed,
             shutdown: self.shutdown,
+            load: self.load,
             path_segments: self.path_segments.clone(),
             query_items: self.query_items.clone(),
             route: Atomic::new(self.route.load(Ordering::Acquire)),
@@ -87,13 +102,14 @@ impl<'r> Request<'r> {
             uri,
             method: Atomic::new(method),
             headers: HeaderMap::new(),
-            remote: None,
+            connection: ConnectionMeta::default(),
             state: RequestState {
                 path_segments: SmallVec::new(),
                 query_items: None,
                 config: &rocket.config,
                 managed: &rocket.managed_state,
                 shutdown: &rocket.shutdown_handle,
+                load: &rocket.load,
                 route: Atomic::new(None),
                 cookies: CookieJar::new(&rocket.config.secret_key),
                 accept: Storage::new(),
@@ -183,13 +199,15 @@ impl<'r> Request<'r> {
 
     /// Returns the address of the remote connection that initiated this
     /// request if the address is known. If the address is not known, `None` is
-    /// returned.
+    /// returned. This is always the case for requests received over a Unix
+    /// domain socket, which has no network address.
     ///
     /// Because it is common for proxies to forward connections for clients, the
     /// remote address may contain information about the proxy instead of the
-    /// client. For this reason, proxies typically set the "X-Real-IP" header
-    /// with the client's true IP. To extract this IP from the request, use the
-    /// [`real_ip()`] or [`client_ip()`] methods.
+    /// client. For this reason, proxies typically set the "X-Real-IP",
+    /// "X-Forwarded-For", or "Forwarded" headers with the client's true IP. To
+    /// extract this IP from the request, use the [`real_ip()`] or
+    /// [`client_ip()`] methods.
     ///
     /// [`real_ip()`]: #method.real_ip
     /// [`client_ip()`]: #method.client_ip
@@ -205,7 +223,7 @@ impl<'r> Request<'r> {
     /// ```
     #[inline(always)]
     pub fn remote(&self) -> Option<SocketAddr> {
-        self.remote
+        self.connection.remote
     }
 
     /// Sets the remote address of `self` to `address`.
@@ -229,7 +247,29 @@ impl<'r> Request<'r> {
     /// ```
     #[inline(always)]
     pub fn set_remote(&mut self, address: SocketAddr) {
-        self.remote = Some(address);
+        self.connection.remote = Some(address);
+    }
+
+    /// Returns the endpoint the request arrived on, if it is known.
+    ///
+    /// The endpoint is one of the configured
+    /// [`endpoints`](crate::Config::endpoints) or, if none are configured, the
+    /// endpoint formed by the configured `address`, `port`, and `tls`. Its
+    /// `port` is the port that was actually bound. The endpoint is unknown for
+    /// requests received over a Unix domain socket and for local requests.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::Method;
+    /// # Request::example(Method::Get, "/uri", |request| {
+    /// assert!(request.endpoint().is_none());
+    /// # });
+    /// ```
+    #[inline(always)]
+    pub fn endpoint(&self) -> Option<&Endpoint> {
+        self.connection.endpoint.as_deref()
     }
 
     /// Returns the IP address in the "X-Real-IP" header of the request if such
@@ -257,12 +297,22 @@ impl<'r> Request<'r> {
             })
     }
 
-    /// Attempts to return the client's IP address by first inspecting the
-    /// "X-Real-IP" header and then using the remote connection's IP address.
+    /// Attempts to return the client's IP address.
+    ///
+    /// If no [`trusted_proxies`](crate::Config::trusted_proxies) are
+    /// configured, the IP address in a valid "X-Real-IP" header is returned.
+    /// Otherwise, if the address of the remote connection is known, that
+    /// address is returned. Otherwise, `None` is returned.
     ///
-    /// If the "X-Real-IP" header exists and contains a valid IP address, that
-    /// address is returned. Otherwise, if the address of the remote connection
-    /// is known, that address is returned. Otherwise, `None` is returned.
+    /// If trusted proxies are configured, the forwarding headers of requests
+    /// from any other remote are ignored and the remote's address is returned.
+    /// For requests from a trusted proxy, the chain of hops in the "Forwarded"
+    /// header or, if there is none, the "X-Forwarded-For" header is walked from
+    /// right to left, skipping hops from trusted proxies. The address of the
+    /// first untrusted hop is returned, or the leftmost hop's if all hops are
+    /// trusted. A hop with an unknown or obfuscated address yields `None`. If
+    /// there are no forwarding headers, or none that identify the client,
+    /// "X-Real-IP" and then the remote address are used as above.
     ///
     /// # Example
     ///
@@ -284,9 +334,125 @@ impl<'r> Request<'r> {
     /// assert_eq!(request.client_ip(), Some("8.8.8.8".parse().unwrap()));
     /// # });
     /// ```
-    #[inline]
     pub fn client_ip(&self) -> Option<IpAddr> {
-        self.real_ip().or_else(|| self.remote().map(|r| r.ip()))
+        let remote = self.remote().map(|r| r.ip());
+        if self.state.config.trusted_proxies.is_empty() {
+            return self.real_ip().or(remote);
+        }
+
+        if !remote.map_or(false, |ip| self.is_trusted_proxy(ip)) {
+            return remote;
+        }
+
+        match self.forwarded_client() {
+            Some(hop) if hop.node => hop.ip,
+            _ => self.real_ip().or(remote),
+        }
+    }
+
+    /// Returns the protocol, such as `https`, of the request the client made
+    /// to the outermost trusted proxy, as reported by the "Forwarded" or
+    /// "X-Forwarded-Proto" headers. Returns `None` if no
+    /// [`trusted_proxies`](crate::Config::trusted_proxies) are configured, if
+    /// the request isn't from a trusted proxy, or if no protocol was reported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::{Header, Method};
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// // the headers are ignored unless `trusted_proxies` are configured
+    /// request.add_header(Header::new("X-Forwarded-Proto", "https"));
+    /// assert!(request.forwarded_proto().is_none());
+    /// # });
+    /// ```
+    pub fn forwarded_proto(&self) -> Option<&str> {
+        self.forwarded_client().and_then(|hop| hop.proto)
+    }
+
+    /// Returns the host the client requested from the outermost trusted proxy,
+    /// as reported by the "Forwarded" or "X-Forwarded-Host" headers. Returns
+    /// `None` if no [`trusted_proxies`](crate::Config::trusted_proxies) are
+    /// configured, if the request isn't from a trusted proxy, or if no host was
+    /// reported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::{Header, Method};
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// // the headers are ignored unless `trusted_proxies` are configured
+    /// request.add_header(Header::new("X-Forwarded-Host", "rocket.rs"));
+    /// assert!(request.forwarded_host().is_none());
+    /// # });
+    /// ```
+    pub fn forwarded_host(&self) -> Option<&str> {
+        self.forwarded_client().and_then(|hop| hop.host)
+    }
+
+    /// Returns `origin` as an absolute URI with the scheme and host externally
+    /// visible to the client. The scheme is the [forwarded
+    /// protocol](Request::forwarded_proto()), if any, and otherwise `https` or
+    /// `http` depending on whether TLS is enabled on the request's
+    /// [endpoint](Request::endpoint()). The host is the [forwarded
+    /// host](Request::forwarded_host()), if any, and otherwise the request's
+    /// "Host" header. Returns `None` if the host is unknown or the resulting
+    /// URI is invalid.
+    ///
+    /// This method is intended to be used with the [`uri!`](crate::uri) macro
+    /// to generate absolute links.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::Request;
+    /// # use rocket::http::{Header, Method};
+    /// use rocket::http::uri::Origin;
+    ///
+    /// # Request::example(Method::Get, "/uri", |mut request| {
+    /// let origin = Origin::parse("/hello?name=Rocketeer").unwrap();
+    /// assert!(request.absolute_uri(&origin).is_none());
+    ///
+    /// request.add_header(Header::new("Host", "rocket.rs"));
+    /// let uri = request.absolute_uri(&origin).unwrap();
+    /// assert_eq!(uri.to_string(), "http://rocket.rs/hello?name=Rocketeer");
+    /// # });
+    /// ```
+    pub fn absolute_uri(&self, origin: &Origin<'_>) -> Option<Absolute<'static>> {
+        let hop = self.forwarded_client();
+        let tls = self.endpoint().map_or(self.state.config.tls_enabled(), |e| e.tls_enabled());
+        let scheme = hop.and_then(|hop| hop.proto).unwrap_or(if tls { "https" } else { "http" });
+
+        let host = hop.and_then(|hop| hop.host)
+            .or_else(|| self.headers().get_one("Host"))?;
+
+        let uri = format!("{}://{}{}", scheme, host, origin);
+        Absolute::parse(&uri).ok().map(|uri| uri.into_owned())
+    }
+
+    /// Returns `true` if `ip` is in one of the configured trusted proxy ranges.
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.state.config.trusted_proxies.iter().any(|range| range.contains(ip))
+    }
+
+    /// Returns the hop, as reported by trusted proxies, of the client that made
+    /// the request. Returns `None` if the request isn't from a trusted proxy or
+    /// it carries no forwarding headers.
+    fn forwarded_client(&self) -> Option<Hop<'_>> {
+        let remote = self.remote()?.ip();
+        if !self.is_trusted_proxy(remote) {
+            return None;
+        }
+
+        let hops = forwarded::hops(self.headers());
+        let leftmost = *hops.first()?;
+        let client = hops.into_iter()
+            .rev()
+            .find(|hop| !hop.ip.map_or(false, |ip| self.is_trusted_proxy(ip)));
+
+        Some(client.unwrap_or(leftmost))
     }
 
     /// Returns a wrapped borrow to the cookies in `self`.
@@ -788,6 +954,20 @@ impl<'r> Request<'r> {
             .map(|(i, j)| self.uri.path()[i..j].into())
     }
 
+    /// Get the value of the `m`th parameter, 0-indexed, of the `n`th path
+    /// segment after the mount point for the currently matched route, if the
+    /// route's segment is mixed, like `<name>.<ext>`. Used by codegen.
+    #[inline]
+    pub fn raw_segment_part(&self, n: usize, m: usize) -> Option<&RawStr> {
+        let route = self.route()?;
+        let index = route.base.segment_count() + n;
+        let (i, j) = *self.state.path_segments.get(index)?;
+        route.metadata.path_segments.get(index)?
+            .split_mixed(&self.uri.path()[i..j])?
+            .get(m)
+            .map(|&part| part.into())
+    }
+
     /// Get the segments beginning at the `n`th, 0-indexed, after the mount
     /// point for the currently matched route, if they exist. Used by codegen.
     #[inline]
@@ -849,7 +1029,7 @@ impl<'r> Request<'r> {
         h_method: hyper::Method,
         h_headers: hyper::HeaderMap<hyper::HeaderValue>,
         h_uri: &'r hyper::Uri,
-        h_addr: SocketAddr,
+        connection: ConnectionMeta,
     ) -> Result<Request<'r>, String> {
         // Get a copy of the URI (only supports path-and-query) for later use.
         let uri = match (h_uri.scheme(), h_uri.authority(), h_uri.path_and_query()) {
@@ -868,7 +1048,7 @@ impl<'r> Request<'r> {
 
         // Construct the request object.
         let mut request = Request::new(rocket, method, uri);
-        request.set_remote(h_addr);
+        request.connection = connection;
 
         // Set the request cookies, if they exist.
         for header in h_headers.get_all("Cookie") {
@@ -2,6 +2,7 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::collections::HashMap;
 
 use crate::{Rocket, Request, Config};
+use crate::request::ConnectionMeta;
 use crate::http::hyper;
 
 macro_rules! assert_headers {
@@ -10,6 +11,7 @@ macro_rules! assert_headers {
         let h_method = hyper::Method::GET;
         let h_uri = "/test".parse().unwrap();
         let h_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8000);
+        let conn = ConnectionMeta { remote: Some(h_addr), ..Default::default() };
         let mut h_headers = hyper::HeaderMap::new();
 
         // Add all of the passed in headers to the request.
@@ -21,7 +23,7 @@ macro_rules! assert_headers {
 
         // Dispatch the request and check that the headers are what we expect.
         let r = Rocket::custom(Config::default());
-        let req = Request::from_hyp(&r, h_method, h_headers, &h_uri, h_addr).unwrap();
+        let req = Request::from_hyp(&r, h_method, h_headers, &h_uri, conn).unwrap();
         let actual_headers = req.headers();
         for (key, values) in expected.iter() {
             let actual: Vec<_> = actual_headers.get(key).collect();
@@ -0,0 +1,86 @@
+use std::fmt::{self, Debug};
+use std::io;
+
+use futures::stream::Stream;
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::hyper::Bytes;
+
+/// Streams a response to a client from a `Stream` of byte chunks.
+///
+/// Each chunk is sent to the client as soon as the stream yields it, without
+/// being re-buffered, making `ByteStream` a natural fit for producers such as
+/// channels or database cursors. A chunk may be any type that converts into
+/// `Bytes`, including `Vec<u8>`, `String`, `&'static [u8]`, and `&'static str`.
+///
+/// # Example
+///
+/// Stream the messages received on a channel:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::io;
+///
+/// use rocket::futures::channel::mpsc;
+/// use rocket::futures::{SinkExt, Stream, StreamExt};
+/// use rocket::response::ByteStream;
+///
+/// #[get("/numbers")]
+/// fn numbers() -> ByteStream<impl Stream<Item = io::Result<String>>> {
+///     let (mut tx, rx) = mpsc::channel(8);
+///     rocket::tokio::spawn(async move {
+///         for i in 0..10 {
+///             if tx.send(format!("{}\n", i)).await.is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///
+///     ByteStream::from(rx.map(Ok))
+/// }
+/// ```
+pub struct ByteStream<S>(S);
+
+impl<S: Debug> Debug for ByteStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ByteStream").field(&self.0).finish()
+    }
+}
+
+/// Create a new `ByteStream` from the given `stream`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+///
+/// use rocket::futures::stream;
+/// use rocket::response::ByteStream;
+///
+/// let chunks = vec![Ok::<_, io::Error>("Hello, "), Ok("world!")];
+/// let response = ByteStream::from(stream::iter(chunks));
+/// ```
+impl<S, T> From<S> for ByteStream<S>
+    where S: Stream<Item = io::Result<T>>, T: Into<Bytes>
+{
+    fn from(stream: S) -> Self {
+        ByteStream(stream)
+    }
+}
+
+/// Sends a response to the client using the "Chunked" transfer encoding, with
+/// one chunk per item of the stream.
+///
+/// # Failure
+///
+/// If the stream yields an error at any point during the response, the
+/// response is abandoned, and the response ends abruptly. An error is printed
+/// to the console with an indication of what went wrong.
+impl<'r, 'o: 'r, S, T> Responder<'r, 'o> for ByteStream<S>
+    where S: Stream<Item = io::Result<T>> + Send + 'o, T: Into<Bytes>
+{
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        Response::build().stream_body(self.0).ok()
+    }
+}
@@ -0,0 +1,260 @@
+use std::borrow::Cow;
+use std::io::{self, Cursor};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::Future;
+use futures::stream::Stream;
+use tokio::io::AsyncRead;
+use tokio::time::{interval_at, Instant, Interval};
+
+use crate::Shutdown;
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::ContentType;
+
+/// A single event in an [`EventStream`].
+///
+/// An event carries optional `data`, an `event` name, an `id`, and a `retry`
+/// reconnection delay. Newlines in `data` are preserved by splitting the data
+/// across several `data` fields, as the Server-Sent Events format requires.
+/// Newlines in the `event` name and `id`, which cannot be represented, are
+/// removed.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::response::Event;
+///
+/// let event = Event::data("Hello,\nworld!")
+///     .event("greeting")
+///     .id("1")
+///     .retry(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Event {
+    data: Option<Cow<'static, str>>,
+    event: Option<Cow<'static, str>>,
+    id: Option<Cow<'static, str>>,
+    retry: Option<Duration>,
+}
+
+impl Event {
+    /// Creates an event with `data` as its data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Event;
+    ///
+    /// let event = Event::data("Hello!");
+    /// ```
+    pub fn data<T: Into<Cow<'static, str>>>(data: T) -> Event {
+        Event { data: Some(data.into()), ..Event::default() }
+    }
+
+    /// Creates an event without any data. Such an event can still set an
+    /// `id` or a `retry` delay.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::response::Event;
+    ///
+    /// let event = Event::empty().retry(Duration::from_secs(10));
+    /// ```
+    pub fn empty() -> Event {
+        Event::default()
+    }
+
+    /// Sets the event's name, dispatched by browsers as the event's type.
+    pub fn event<T: Into<Cow<'static, str>>>(mut self, event: T) -> Event {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the event's ID, which a reconnecting client reports in its
+    /// `Last-Event-ID` header.
+    pub fn id<T: Into<Cow<'static, str>>>(mut self, id: T) -> Event {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets how long the client should wait before reconnecting if the
+    /// connection is lost.
+    pub fn retry(mut self, retry: Duration) -> Event {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Serializes the event in the Server-Sent Events wire format.
+    fn serialize(&self) -> Vec<u8> {
+        fn field(out: &mut String, name: &str, value: &str) {
+            out.push_str(name);
+            out.push_str(": ");
+            out.extend(value.chars().filter(|&c| c != '\n' && c != '\r'));
+            out.push('\n');
+        }
+
+        let mut out = String::new();
+        if let Some(ref event) = self.event {
+            field(&mut out, "event", event);
+        }
+
+        if let Some(ref id) = self.id {
+            field(&mut out, "id", id);
+        }
+
+        if let Some(retry) = self.retry {
+            field(&mut out, "retry", &retry.as_millis().to_string());
+        }
+
+        if let Some(ref data) = self.data {
+            for line in data.split('\n') {
+                field(&mut out, "data", line);
+            }
+        }
+
+        out.push('\n');
+        out.into_bytes()
+    }
+}
+
+/// A Server-Sent Events responder streaming [`Event`]s from a [`Stream`].
+///
+/// The response is sent with a `text/event-stream` content type and with
+/// caching disabled. Each event is sent to the client as soon as the stream
+/// yields it. While the stream is idle, a keep-alive comment is sent every
+/// 30 seconds, or as configured by [`EventStream::heartbeat()`], which keeps
+/// intermediaries from timing out the connection and detects disconnected
+/// clients.
+///
+/// The response ends when the stream ends, when the client disconnects, or
+/// when the server begins a graceful [`Shutdown`]. The stream is dropped in
+/// each case.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::time::Duration;
+///
+/// use rocket::futures::stream::{self, Stream};
+/// use rocket::response::{Event, EventStream};
+/// use rocket::tokio::time::delay_for;
+///
+/// #[get("/ticks")]
+/// fn ticks() -> EventStream<impl Stream<Item = Event>> {
+///     let ticks = stream::unfold(0, |n| async move {
+///         delay_for(Duration::from_secs(1)).await;
+///         Some((Event::data(format!("tick {}", n)).id(n.to_string()), n + 1))
+///     });
+///
+///     EventStream::from(ticks)
+/// }
+/// ```
+pub struct EventStream<S> {
+    stream: S,
+    heartbeat: Option<Duration>,
+}
+
+impl<S: Stream<Item = Event>> EventStream<S> {
+    /// Sets the interval between keep-alive comments sent while `stream` is
+    /// idle. Keep-alive comments are disabled if `heartbeat` is `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use rocket::futures::stream;
+    /// use rocket::response::{Event, EventStream};
+    ///
+    /// let events = stream::iter(vec![Event::data("hi")]);
+    /// let stream = EventStream::from(events).heartbeat(Duration::from_secs(5));
+    /// let stream = stream.heartbeat(None);
+    /// ```
+    pub fn heartbeat<H: Into<Option<Duration>>>(mut self, heartbeat: H) -> Self {
+        self.heartbeat = heartbeat.into();
+        self
+    }
+}
+
+impl<S: Stream<Item = Event>> From<S> for EventStream<S> {
+    fn from(stream: S) -> Self {
+        EventStream { stream, heartbeat: Some(Duration::from_secs(30)) }
+    }
+}
+
+/// Reads the serialized events of a stream, interleaving keep-alive comments.
+struct EventReader<S> {
+    stream: Pin<Box<S>>,
+    heartbeat: Option<Interval>,
+    shutdown: Shutdown,
+    buffer: Cursor<Vec<u8>>,
+    done: bool,
+}
+
+impl<S: Stream<Item = Event>> AsyncRead for EventReader<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            if (self.buffer.position() as usize) < self.buffer.get_ref().len() {
+                return Pin::new(&mut self.buffer).poll_read(cx, buf);
+            }
+
+            if self.done {
+                return Poll::Ready(Ok(0));
+            }
+
+            if Pin::new(&mut self.shutdown).poll(cx).is_ready() {
+                self.done = true;
+                continue;
+            }
+
+            let next = self.stream.as_mut().poll_next(cx);
+            match next {
+                Poll::Ready(Some(event)) => self.buffer = Cursor::new(event.serialize()),
+                Poll::Ready(None) => self.done = true,
+                Poll::Pending => {
+                    // Send a comment, which clients ignore, if the stream has
+                    // been idle for a heartbeat period.
+                    let heartbeat = self.heartbeat.as_mut()
+                        .map_or(false, |h| h.poll_tick(cx).is_ready());
+
+                    if !heartbeat {
+                        return Poll::Pending;
+                    }
+
+                    self.buffer = Cursor::new(b":\n\n".to_vec());
+                }
+            }
+        }
+    }
+}
+
+impl<'r, 'o: 'r, S: Stream<Item = Event> + Send + 'o> Responder<'r, 'o> for EventStream<S> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let heartbeat = self.heartbeat.map(|period| interval_at(Instant::now() + period, period));
+        let reader = EventReader {
+            stream: Box::pin(self.stream),
+            heartbeat,
+            shutdown: req.state.shutdown.clone(),
+            buffer: Cursor::new(vec![]),
+            done: false,
+        };
+
+        Response::build()
+            .header(ContentType::new("text", "event-stream"))
+            .raw_header("Cache-Control", "no-cache")
+            .raw_header("X-Accel-Buffering", "no")
+            .streamed_body(reader)
+            .ok()
+    }
+}
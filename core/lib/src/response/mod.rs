@@ -27,8 +27,11 @@ mod responder;
 mod redirect;
 mod named_file;
 mod stream;
+mod byte_stream;
 mod response;
 mod debug;
+mod upgrade;
+mod event_stream;
 
 pub(crate) mod flash;
 
@@ -38,13 +41,17 @@ pub mod status;
 #[doc(hidden)] pub use rocket_codegen::Responder;
 
 pub use self::response::DEFAULT_CHUNK_SIZE;
-pub use self::response::{Response, ResponseBody, ResponseBuilder, Body};
+pub use self::response::{Response, ResponseBody, ResponseBuilder, Body, ChunkedBody};
+pub(crate) use self::response::ChunkStream;
 pub use self::responder::Responder;
 pub use self::redirect::Redirect;
 pub use self::flash::Flash;
 pub use self::named_file::NamedFile;
 pub use self::stream::Stream;
+pub use self::byte_stream::ByteStream;
 pub use self::debug::Debug;
+pub use self::upgrade::{Upgrade, Upgraded};
+pub use self::event_stream::{Event, EventStream};
 #[doc(inline)] pub use self::content::Content;
 
 /// Type alias for the `Result` of a [`Responder::respond_to()`] call.
@@ -146,12 +146,25 @@ impl Redirect {
 /// the `Location` header field. The body of the response is empty. If the URI
 /// value used to create the `Responder` is an invalid URI, an error of
 /// `Status::InternalServerError` is returned.
+///
+/// When a trusted proxy reports the protocol or host the client requested, as
+/// returned by [`Request::forwarded_proto()`] and
+/// [`Request::forwarded_host()`], a URI consisting of only a path and query is
+/// made absolute with [`Request::absolute_uri()`] so that the client is
+/// redirected to the externally visible scheme and host.
 impl<'r> Responder<'r, 'static> for Redirect {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
         if let Some(uri) = self.1 {
+            let forwarded = req.forwarded_proto().is_some() || req.forwarded_host().is_some();
+            let absolute = match uri {
+                Uri::Origin(ref origin) if forwarded => req.absolute_uri(origin),
+                _ => None,
+            };
+
+            let location = absolute.map(Uri::Absolute).unwrap_or(uri);
             Response::build()
                 .status(self.0)
-                .raw_header("Location", uri.to_string())
+                .raw_header("Location", location.to_string())
                 .ok()
         } else {
             error!("Invalid URI used for redirect.");
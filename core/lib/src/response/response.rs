@@ -1,11 +1,15 @@
 use std::borrow::Cow;
 use std::pin::Pin;
+use std::task::{Context, Poll};
 use std::{fmt, io, str};
 
+use futures::stream::{self, Stream, StreamExt};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
+use crate::ext::AsyncReadExt as _;
+use crate::http::hyper::Bytes;
 use crate::http::{ContentType, Cookie, Header, HeaderMap, Status};
-use crate::response::{self, Responder};
+use crate::response::{self, Responder, Upgrade};
 
 /// The default size, in bytes, of a chunk for streamed responses.
 pub const DEFAULT_CHUNK_SIZE: usize = 4096;
@@ -456,6 +460,30 @@ impl<'r> ResponseBuilder<'r> {
         self
     }
 
+    /// Sets the body of the `Response` to be `stream`, a `Stream` of chunks.
+    /// See [`Response::set_stream_body()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::futures::stream;
+    ///
+    /// let chunks = vec![Ok::<_, std::io::Error>("Hello, "), Ok("world!")];
+    /// let response = Response::build()
+    ///     .stream_body(stream::iter(chunks))
+    ///     .finalize();
+    /// ```
+    #[inline(always)]
+    pub fn stream_body<S, T>(&mut self, stream: S) -> &mut ResponseBuilder<'r>
+    where
+        S: Stream<Item = io::Result<T>> + Send + 'r,
+        T: Into<Bytes>,
+    {
+        self.response.set_stream_body(stream);
+        self
+    }
+
     /// Sets the body of `self` to be `body`. This method should typically not
     /// be used, opting instead for one of `sized_body`, `streamed_body`, or
     /// `chunked_body`.
@@ -482,6 +510,38 @@ impl<'r> ResponseBuilder<'r> {
         self
     }
 
+    /// Upgrades the connection to `protocol` once the response is sent, handing
+    /// it to `upgrade`. See [`Response::set_upgrade()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::Status;
+    /// use rocket::response::{Upgrade, Upgraded};
+    ///
+    /// struct Hangup;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Upgrade for Hangup {
+    ///     async fn start(self: Box<Self>, _: Upgraded) { }
+    /// }
+    ///
+    /// let response = Response::build()
+    ///     .upgrade("hangup", Hangup)
+    ///     .finalize();
+    ///
+    /// assert_eq!(response.status(), Status::SwitchingProtocols);
+    /// assert_eq!(response.headers().get_one("Upgrade"), Some("hangup"));
+    /// ```
+    #[inline(always)]
+    pub fn upgrade<P, U>(&mut self, protocol: P, upgrade: U) -> &mut ResponseBuilder<'r>
+        where P: Into<Cow<'static, str>>, U: Upgrade
+    {
+        self.response.set_upgrade(protocol, upgrade);
+        self
+    }
+
     /// Merges the `other` `Response` into `self` by setting any fields in
     /// `self` to the corresponding value in `other` if they are set in `other`.
     /// Fields in `self` are unchanged if they are not set in `other`. If a
@@ -607,8 +667,76 @@ impl<'r> ResponseBuilder<'r> {
 pub trait AsyncReadSeek: AsyncRead + AsyncSeek {}
 impl<T: AsyncRead + AsyncSeek> AsyncReadSeek for T {}
 
-pub type ResponseBody<'r> =
-    Body<Pin<Box<dyn AsyncReadSeek + Send + 'r>>, Pin<Box<dyn AsyncRead + Send + 'r>>>;
+pub type ResponseBody<'r> = Body<Pin<Box<dyn AsyncReadSeek + Send + 'r>>, ChunkedBody<'r>>;
+
+pub(crate) type ChunkStream<'r> = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + 'r>>;
+
+/// The streamed body of a [`ResponseBody`]: either an `AsyncRead`er, which is
+/// read in chunks, or a `Stream` of chunks, as set by
+/// [`Response::set_stream_body()`].
+///
+/// A `ChunkedBody` can be read as an `AsyncRead`er either way.
+pub struct ChunkedBody<'r>(ChunkedInner<'r>);
+
+enum ChunkedInner<'r> {
+    Reader(Pin<Box<dyn AsyncRead + Send + 'r>>),
+    Stream(ChunkStream<'r>, io::Cursor<Bytes>),
+}
+
+impl<'r> ChunkedBody<'r> {
+    fn reader<R: AsyncRead + Send + 'r>(reader: R) -> Self {
+        ChunkedBody(ChunkedInner::Reader(Box::pin(reader)))
+    }
+
+    fn stream<S, T>(stream: S) -> Self
+        where S: Stream<Item = io::Result<T>> + Send + 'r, T: Into<Bytes>
+    {
+        let stream = Box::pin(stream.map(|result| result.map(Into::into)));
+        ChunkedBody(ChunkedInner::Stream(stream, io::Cursor::new(Bytes::new())))
+    }
+
+    /// Returns a stream of the body's chunks. A reader is read in chunks of at
+    /// most `chunk_size` bytes while the items of a stream are passed through
+    /// as they are.
+    pub(crate) fn chunks(&mut self, chunk_size: usize) -> ChunkStream<'_> {
+        match self.0 {
+            ChunkedInner::Reader(ref mut reader) => {
+                Box::pin(reader.into_bytes_stream(chunk_size))
+            }
+            ChunkedInner::Stream(ref mut stream, ref mut partial) => {
+                // Pass along whatever remains of a partially read chunk first.
+                let start = partial.position() as usize;
+                let rest = partial.get_ref().slice(start..);
+                *partial = io::Cursor::new(Bytes::new());
+                let rest = Some(rest).filter(|rest| !rest.is_empty()).map(Ok);
+                Box::pin(stream::iter(rest).chain(stream.as_mut()))
+            }
+        }
+    }
+}
+
+impl AsyncRead for ChunkedBody<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        match self.0 {
+            ChunkedInner::Reader(ref mut reader) => reader.as_mut().poll_read(cx, buf),
+            ChunkedInner::Stream(ref mut stream, ref mut partial) => loop {
+                if (partial.position() as usize) < partial.get_ref().len() {
+                    return Pin::new(partial).poll_read(cx, buf);
+                }
+
+                match futures::ready!(stream.as_mut().poll_next(cx)) {
+                    Some(Ok(chunk)) => *partial = io::Cursor::new(chunk),
+                    Some(Err(e)) => return Poll::Ready(Err(e)),
+                    None => return Poll::Ready(Ok(0)),
+                }
+            }
+        }
+    }
+}
 
 /// A response, as returned by types implementing [`Responder`].
 #[derive(Default)]
@@ -616,6 +744,7 @@ pub struct Response<'r> {
     status: Option<Status>,
     headers: HeaderMap<'r>,
     body: Option<ResponseBody<'r>>,
+    upgrade: Option<Box<dyn Upgrade>>,
 }
 
 impl<'r> Response<'r> {
@@ -642,6 +771,7 @@ impl<'r> Response<'r> {
             status: None,
             headers: HeaderMap::new(),
             body: None,
+            upgrade: None,
         }
     }
 
@@ -1148,7 +1278,34 @@ impl<'r> Response<'r> {
     where
         B: AsyncRead + Send + 'r,
     {
-        self.body = Some(Body::Chunked(Box::pin(body), chunk_size));
+        self.body = Some(Body::Chunked(ChunkedBody::reader(body), chunk_size));
+    }
+
+    /// Sets the body of `self` to be `stream`, a `Stream` of chunks. Each
+    /// chunk is sent to the client as soon as `stream` yields it, as it is. If
+    /// `stream` yields an error, the response is abandoned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::futures::stream;
+    ///
+    /// # rocket::async_test(async {
+    /// let chunks = vec![Ok::<_, std::io::Error>("Hello, "), Ok("world!")];
+    ///
+    /// let mut response = Response::new();
+    /// response.set_stream_body(stream::iter(chunks));
+    /// assert_eq!(response.body_string().await.unwrap(), "Hello, world!");
+    /// # })
+    /// ```
+    #[inline(always)]
+    pub fn set_stream_body<S, T>(&mut self, stream: S)
+    where
+        S: Stream<Item = io::Result<T>> + Send + 'r,
+        T: Into<Bytes>,
+    {
+        self.body = Some(Body::Chunked(ChunkedBody::stream(stream), DEFAULT_CHUNK_SIZE));
     }
 
     /// Sets the body of `self` to be `body`. This method should typically not
@@ -1179,10 +1336,65 @@ impl<'r> Response<'r> {
     {
         self.body = Some(match body {
             Body::Sized(a, n) => Body::Sized(Box::pin(a), n),
-            Body::Chunked(b, n) => Body::Chunked(Box::pin(b), n),
+            Body::Chunked(b, n) => Body::Chunked(ChunkedBody::reader(b), n),
         });
     }
 
+    /// Upgrades the connection to `protocol` once this response is sent,
+    /// handing it to `upgrade`. Sets the status to `101 Switching Protocols`
+    /// and sets the `Connection: upgrade` and `Upgrade: protocol` headers.
+    ///
+    /// The upgrade is performed only if the request asked for one via its
+    /// `Upgrade` and `Connection: upgrade` headers and has no body, and only if
+    /// the response is still a `101` when it is sent. A `101` response to a
+    /// request that can't be upgraded is replaced by the `500` catcher's
+    /// response. See [`Upgrade`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::Status;
+    /// use rocket::response::{Upgrade, Upgraded};
+    ///
+    /// struct Hangup;
+    ///
+    /// #[rocket::async_trait]
+    /// impl Upgrade for Hangup {
+    ///     async fn start(self: Box<Self>, _: Upgraded) { }
+    /// }
+    ///
+    /// let mut response = Response::new();
+    /// response.set_upgrade("hangup", Hangup);
+    ///
+    /// assert_eq!(response.status(), Status::SwitchingProtocols);
+    /// assert_eq!(response.headers().get_one("Connection"), Some("upgrade"));
+    /// assert!(response.take_upgrade().is_some());
+    /// ```
+    pub fn set_upgrade<P, U>(&mut self, protocol: P, upgrade: U)
+        where P: Into<Cow<'static, str>>, U: Upgrade
+    {
+        self.set_status(Status::SwitchingProtocols);
+        self.set_raw_header("Connection", "upgrade");
+        self.set_raw_header("Upgrade", protocol.into());
+        self.upgrade = Some(Box::new(upgrade));
+    }
+
+    /// Removes and returns the upgrade handler of `self`, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    ///
+    /// let mut response = Response::new();
+    /// assert!(response.take_upgrade().is_none());
+    /// ```
+    #[inline(always)]
+    pub fn take_upgrade(&mut self) -> Option<Box<dyn Upgrade>> {
+        self.upgrade.take()
+    }
+
     /// Replaces this response's status and body with that of `other`, if they
     /// exist in `other`. Any headers that exist in `other` replace the ones in
     /// `self`. Any in `self` that aren't in `other` remain in `self`.
@@ -1223,6 +1435,10 @@ impl<'r> Response<'r> {
             self.body = Some(body);
         }
 
+        if let Some(upgrade) = other.upgrade {
+            self.upgrade = Some(upgrade);
+        }
+
         for (name, values) in other.headers.into_iter_raw() {
             self.headers.replace_all(name.into_cow(), values);
         }
@@ -1268,6 +1484,10 @@ impl<'r> Response<'r> {
             self.body = other.body;
         }
 
+        if self.upgrade.is_none() {
+            self.upgrade = other.upgrade;
+        }
+
         for (name, mut values) in other.headers.into_iter_raw() {
             self.headers.add_all(name.into_cow(), &mut values);
         }
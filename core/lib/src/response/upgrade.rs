@@ -0,0 +1,92 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// A handler that takes over a connection after a protocol upgrade.
+///
+/// An `Upgrade` is attached to a response with [`Response::set_upgrade()`] or
+/// [`ResponseBuilder::upgrade()`]. Once Rocket has sent the `101 Switching
+/// Protocols` response and the client has switched protocols, Rocket calls
+/// [`Upgrade::start()`] with the raw connection. The connection is closed when
+/// `start()` returns and the [`Upgraded`] IO is dropped.
+///
+/// Upgrades are only performed for requests that ask for one: the request must
+/// carry an `Upgrade` header, list `upgrade` in its `Connection` header, and
+/// have no body. For any other request, the upgrade is discarded, an error is
+/// logged, and, if the response is a `101`, the `500` catcher's response is
+/// sent in its place.
+///
+/// [`Response::set_upgrade()`]: crate::Response::set_upgrade()
+/// [`ResponseBuilder::upgrade()`]: crate::response::ResponseBuilder::upgrade()
+///
+/// # Example
+///
+/// An upgrade that echoes everything it receives:
+///
+/// ```rust
+/// use rocket::response::{Upgrade, Upgraded};
+/// use rocket::tokio::io::{copy, split};
+///
+/// struct Echo;
+///
+/// #[rocket::async_trait]
+/// impl Upgrade for Echo {
+///     async fn start(self: Box<Self>, io: Upgraded) {
+///         let (mut reader, mut writer) = split(io);
+///         let _ = copy(&mut reader, &mut writer).await;
+///     }
+/// }
+/// ```
+#[crate::async_trait]
+pub trait Upgrade: Send + 'static {
+    /// Takes over the upgraded connection `io`.
+    async fn start(self: Box<Self>, io: Upgraded);
+}
+
+trait UpgradedIo: AsyncRead + AsyncWrite + Send + Unpin { }
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> UpgradedIo for T { }
+
+/// The IO of a connection after a protocol upgrade, passed to
+/// [`Upgrade::start()`].
+pub struct Upgraded {
+    io: Box<dyn UpgradedIo>,
+}
+
+impl Upgraded {
+    pub(crate) fn new<T>(io: T) -> Upgraded
+        where T: AsyncRead + AsyncWrite + Send + Unpin + 'static
+    {
+        Upgraded { io: Box::new(io) }
+    }
+}
+
+impl AsyncRead for Upgraded {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.io).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Upgraded {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8]
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.io).poll_shutdown(cx)
+    }
+}
@@ -7,12 +7,15 @@ use tokio::sync::mpsc;
 use futures::future::FutureExt;
 
 use crate::logger;
-use crate::config::Config;
+use crate::config::{Config, Endpoint};
 use crate::catcher::Catcher;
 use crate::router::{Router, Route};
 use crate::fairing::{Fairing, Fairings};
 use crate::logger::PaintExt;
 use crate::shutdown::Shutdown;
+use crate::load::Load;
+use crate::http::Listener;
+use crate::http::private::MultiListener;
 use crate::http::uri::Origin;
 use crate::error::{Error, ErrorKind};
 
@@ -28,6 +31,10 @@ pub struct Rocket {
     pub(crate) fairings: Fairings,
     pub(crate) shutdown_receiver: Option<mpsc::Receiver<()>>,
     pub(crate) shutdown_handle: Shutdown,
+    pub(crate) shutdown_started: Option<futures::channel::oneshot::Sender<()>>,
+    pub(crate) load: Load,
+    #[cfg(feature = "tls")]
+    pub(crate) tls_reloader: crate::tls::Reloader,
 }
 
 impl Rocket {
@@ -87,15 +94,20 @@ impl Rocket {
 
         let managed_state = Container::new();
         let (shutdown_sender, shutdown_receiver) = mpsc::channel(1);
+        let (started_sender, started) = futures::channel::oneshot::channel();
         Rocket {
             config, figment,
             managed_state,
-            shutdown_handle: Shutdown(shutdown_sender),
+            shutdown_handle: Shutdown(shutdown_sender, started.shared()),
+            shutdown_started: Some(started_sender),
+            load: Load::default(),
             router: Router::new(),
             default_catcher: None,
             catchers: HashMap::new(),
             fairings: Fairings::new(),
             shutdown_receiver: Some(shutdown_receiver),
+            #[cfg(feature = "tls")]
+            tls_reloader: Default::default(),
         }
     }
 
@@ -469,6 +481,48 @@ impl Rocket {
         self.shutdown_handle.clone()
     }
 
+    /// Returns a handle reporting the number of open connections and in-flight
+    /// requests of this instance of Rocket. In routes, use the [`Load`]
+    /// request guard.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let rocket = rocket::ignite();
+    /// let load = rocket.load();
+    ///
+    /// // Nothing is being served yet.
+    /// assert_eq!(load.connections(), 0);
+    /// assert_eq!(load.requests(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn load(&self) -> Load {
+        self.load.clone()
+    }
+
+    /// Returns a handle that reloads the TLS certificate chain and private key
+    /// from the active [`TlsConfig`](crate::config::TlsConfig) while the server
+    /// is running. Until the server is launched with TLS enabled,
+    /// [`Reloader::reload()`](crate::tls::Reloader::reload()) returns an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # rocket::async_test(async {
+    /// let rocket = rocket::ignite();
+    /// let reloader = rocket.tls_reloader();
+    ///
+    /// // TLS isn't running yet, so there's nothing to reload.
+    /// assert!(reloader.reload().await.is_err());
+    /// # });
+    /// ```
+    #[cfg(feature = "tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+    #[inline(always)]
+    pub fn tls_reloader(&self) -> crate::tls::Reloader {
+        self.tls_reloader.clone()
+    }
+
     /// Perform "pre-launch" checks: verify that there are no routing colisions
     /// and that there were no fairing failures.
     pub(crate) async fn prelaunch_check(&mut self) -> Result<(), Error> {
@@ -486,14 +540,29 @@ impl Rocket {
     /// Returns a `Future` that drives the server, listening for and dispatching
     /// requests to mounted routes and catchers. The `Future` completes when the
     /// server is shut down via [`Shutdown`], encounters a fatal error, or if
-    /// the the `ctrlc` configuration option is set, when `Ctrl+C` is pressed.
+    /// the the `ctrlc` configuration option is set, when `Ctrl+C` is pressed,
+    /// or when one of the configured `shutdown.signals` is received.
+    ///
+    /// The server listens on the configured `unix` socket, if any, and on
+    /// every configured endpoint otherwise: each of `endpoints` if it is set,
+    /// or the endpoint formed by `address`, `port`, and `tls` if it isn't. If
+    /// `socket_activation` is configured and sockets were passed to the process
+    /// via systemd socket activation, those sockets are served instead; see
+    /// [`SocketActivation`]. To serve on a listener that has already been bound,
+    /// use [`Rocket::launch_on()`].
     ///
     /// # Error
     ///
     /// If there is a problem starting the application, an [`Error`] is
-    /// returned. Note that a value of type `Error` panics if dropped without
-    /// first being inspected. See the [`Error`] documentation for more
-    /// information.
+    /// returned. If the server is shut down but connections remain open once
+    /// the shutdown grace period elapses, an [`ErrorKind::Shutdown`] error is
+    /// returned; see [`ShutdownConfig`]. Note that a value of type `Error`
+    /// other than a shutdown error panics if dropped without first being
+    /// inspected. See the [`Error`] documentation for more information.
+    ///
+    /// [`ErrorKind::Shutdown`]: crate::error::ErrorKind::Shutdown
+    /// [`ShutdownConfig`]: crate::config::ShutdownConfig
+    /// [`SocketActivation`]: crate::config::SocketActivation
     ///
     /// # Example
     ///
@@ -507,58 +576,312 @@ impl Rocket {
     /// }
     /// ```
     pub async fn launch(mut self) -> Result<(), Error> {
-        use std::net::ToSocketAddrs;
-        use futures::future::Either;
-        use crate::http::private::bind_tcp;
+        use crate::config::SocketActivation;
 
         self.prelaunch_check().await?;
 
-        let full_addr = format!("{}:{}", self.config.address, self.config.port);
-        let addr = full_addr.to_socket_addrs()
-            .map(|mut addrs| addrs.next().expect(">= 1 socket addr"))
-            .map_err(|e| Error::new(ErrorKind::Io(e)))?;
+        if let Some(mode) = self.config.socket_activation {
+            match self.activated_listener()? {
+                Some((listener, endpoints)) => {
+                    let label = "activated Unix domain socket(s)".to_string();
+                    return self.serve(listener, endpoints, label).await;
+                }
+                None if mode == SocketActivation::Required => {
+                    let msg = "socket activation is required but no sockets were passed";
+                    let error = std::io::Error::new(std::io::ErrorKind::NotFound, msg);
+                    return Err(Error::new(ErrorKind::Bind(error)));
+                }
+                None => info!("No sockets were passed via socket activation. Binding."),
+            }
+        }
 
-        // If `ctrl-c` shutdown is enabled, we `select` on `the ctrl-c` signal
-        // and server. Otherwise, we only wait on the `server`, hence `pending`.
-        let shutdown_handle = self.shutdown_handle.clone();
-        let shutdown_signal = match self.config.ctrlc {
-            true => tokio::signal::ctrl_c().boxed(),
-            false => futures::future::pending().boxed(),
+        match self.config.unix.clone() {
+            #[cfg(unix)]
+            Some(unix) => {
+                use crate::http::private::bind_unix;
+
+                if self.config.tls_enabled() {
+                    let msg = "TLS is not supported on Unix domain sockets";
+                    let error = std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+                    return Err(Error::new(ErrorKind::Bind(error)));
+                }
+
+                match self.config.proxy_protocol {
+                    Some(crate::config::ProxyProtocol::Required) => {
+                        let msg = "the PROXY protocol is not supported on Unix domain sockets";
+                        let error = std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+                        return Err(Error::new(ErrorKind::Bind(error)));
+                    }
+                    Some(_) => warn!("The PROXY protocol is not supported on Unix domain \
+                        sockets. Ignoring `proxy_protocol`."),
+                    None => {}
+                }
+
+                if !self.config.endpoints.is_empty() {
+                    warn!("Serving on a Unix domain socket. Ignoring `endpoints`.");
+                }
+
+                let path = unix.path();
+                let l = bind_unix(&path, unix.permissions()).await.map_err(ErrorKind::Bind)?;
+                let label = format!("unix:{}", path.display());
+                self.serve(l, vec![], label).await
+            }
+            #[cfg(not(unix))]
+            Some(_) => {
+                let msg = "Unix domain sockets are not supported on this platform";
+                let error = std::io::Error::new(std::io::ErrorKind::Other, msg);
+                Err(Error::new(ErrorKind::Bind(error)))
+            }
+            None => {
+                let endpoints = self.config.bind_endpoints();
+                let mut listener = MultiListener::new();
+                let mut bound = Vec::with_capacity(endpoints.len());
+                for endpoint in &endpoints {
+                    // An IPv6 wildcard would also claim the port of an IPv4
+                    // endpoint on platforms that default to dual-stack sockets.
+                    let only_v6 = endpoint.address.is_ipv6() && endpoints.iter()
+                        .any(|e| e.address.is_ipv4() && e.port == endpoint.port);
+
+                    bound.push(self.bind(endpoint, only_v6, &mut listener).await?);
+                }
+
+                self.serve(listener, bound, String::new()).await
+            }
+        }
+    }
+
+    /// Adopts the sockets passed via socket activation, if there are any,
+    /// returning a listener for all of them and the endpoints of those that
+    /// are TCP sockets.
+    #[cfg(unix)]
+    fn activated_listener(&self) -> Result<Option<(MultiListener, Vec<Endpoint>)>, Error> {
+        use std::sync::Arc;
+        use crate::http::private::{activated_sockets, ActivatedListener};
+        use crate::config::find_endpoint;
+
+        let sockets = match activated_sockets().map_err(ErrorKind::Bind)? {
+            Some(sockets) if !sockets.is_empty() => sockets,
+            _ => return Ok(None),
         };
 
-        #[cfg(feature = "tls")]
-        let server = {
-            use crate::http::tls::bind_tls;
-
-            if let Some(tls_config) = &self.config.tls {
-                let (certs, key) = tls_config.to_readers().map_err(ErrorKind::Io)?;
-                let l = bind_tls(addr, certs, key).await.map_err(ErrorKind::Bind)?;
-                self.listen_on(l).boxed()
-            } else {
-                let l = bind_tcp(addr).await.map_err(ErrorKind::Bind)?;
-                self.listen_on(l).boxed()
+        info!("Adopting {} socket(s) passed via socket activation.", sockets.len());
+        let configured: Vec<_> = self.config.bind_endpoints().into_iter().map(Arc::new).collect();
+        let (mut listener, mut endpoints) = (MultiListener::new(), vec![]);
+        for socket in sockets {
+            let l = match socket.listener {
+                ActivatedListener::Tcp(l) => l,
+                ActivatedListener::Unix(l) => {
+                    listener.push(l);
+                    continue;
+                }
+            };
+
+            // Prefer the endpoint named like the socket to one bound to its
+            // address: the socket's address may differ from the configured one.
+            let local = l.local_addr().map_err(ErrorKind::Bind)?;
+            let name = socket.name.as_deref();
+            let matching = configured.iter()
+                .find(|e| name.is_some() && e.name.as_deref() == name)
+                .or_else(|| find_endpoint(&configured, Some(local)));
+
+            let mut endpoint = match matching {
+                Some(e) => Endpoint { address: local.ip(), port: local.port(), ..(**e).clone() },
+                None => Endpoint::new(local.ip(), local.port()),
+            };
+
+            endpoint.name = endpoint.name.or(socket.name);
+            self.add_tcp_listener(l, &endpoint, &mut listener)?;
+            endpoints.push(endpoint);
+        }
+
+        Ok(Some((listener, endpoints)))
+    }
+
+    #[cfg(not(unix))]
+    fn activated_listener(&self) -> Result<Option<(MultiListener, Vec<Endpoint>)>, Error> {
+        warn!("Socket activation is not supported on this platform.");
+        Ok(None)
+    }
+
+    /// Binds a listener for `endpoint`, adds it to `listener`, and returns the
+    /// endpoint with the port that was actually bound.
+    async fn bind(
+        &self,
+        endpoint: &Endpoint,
+        only_v6: bool,
+        listener: &mut MultiListener,
+    ) -> Result<Endpoint, Error> {
+        use crate::http::private::bind_tcp;
+
+        let l = bind_tcp(endpoint.socket_addr(), only_v6).await.map_err(ErrorKind::Bind)?;
+        let port = l.local_addr().map_err(ErrorKind::Bind)?.port();
+        let bound = Endpoint { port, ..endpoint.clone() };
+        self.add_tcp_listener(l, &bound, listener)?;
+        Ok(bound)
+    }
+
+    /// Adds `l`, a bound TCP listener for `endpoint`, to `listener`, serving
+    /// TLS and decoding PROXY protocol headers as configured.
+    fn add_tcp_listener(
+        &self,
+        l: tokio::net::TcpListener,
+        endpoint: &Endpoint,
+        listener: &mut MultiListener,
+    ) -> Result<(), Error> {
+        use crate::http::private::ProxyListener;
+        use crate::config::ProxyProtocol;
+
+        let proxy_protocol = self.config.proxy_protocol.map(|m| m == ProxyProtocol::Required);
+
+        #[cfg(feature = "tls")] {
+            use crate::http::tls::TlsListener;
+
+            if let Some(tls_config) = &endpoint.tls {
+                let config = tls_config.to_native_config().map_err(ErrorKind::Io)?;
+                let mut l = TlsListener::new(l, config).map_err(ErrorKind::Bind)?;
+                if let Some(required) = proxy_protocol {
+                    l.set_proxy_protocol(required);
+                }
+
+                self.start_tls_reloading(tls_config, l.resolver());
+                listener.push(l);
+                return Ok(());
             }
-        };
+        }
 
-        #[cfg(not(feature = "tls"))]
-        let server = {
-            let l = bind_tcp(addr).await.map_err(ErrorKind::Bind)?;
-            self.listen_on(l).boxed()
-        };
+        match proxy_protocol {
+            Some(required) => listener.push(ProxyListener::new(l, required)),
+            None => listener.push(l),
+        }
+
+        Ok(())
+    }
+
+    /// Installs a TLS reload handle for `resolver`, which serves `tls_config`,
+    /// and, if configured, spawns a task that reloads on an interval. Both hold
+    /// a weak reference to `resolver`; the task exits once the listener is
+    /// dropped.
+    #[cfg(feature = "tls")]
+    fn start_tls_reloading(
+        &self,
+        tls_config: &crate::config::TlsConfig,
+        resolver: &std::sync::Arc<crate::http::tls::CertResolver>
+    ) {
+        use std::io;
+        use std::sync::{Arc, Weak};
+        use crate::http::tls::CertResolver;
+        use crate::config::TlsConfig;
+
+        fn reload(config: &TlsConfig, resolver: &Weak<CertResolver>) -> Option<io::Result<()>> {
+            let resolver = resolver.upgrade()?;
+            let result = config.to_native_config().and_then(|c| resolver.reload(c));
+
+            Some(result)
+        }
+
+        let tls_config = Arc::new(tls_config.clone());
+        let resolver = Arc::downgrade(resolver);
+        if let Some(secs) = tls_config.reload_interval.filter(|&secs| secs > 0) {
+            let (config, resolver) = (tls_config.clone(), resolver.clone());
+            tokio::spawn(async move {
+                let period = std::time::Duration::from_secs(secs);
+                loop {
+                    tokio::time::delay_for(period).await;
+
+                    // Reading and parsing the files blocks.
+                    let (config, resolver) = (config.clone(), resolver.clone());
+                    let task = move || reload(&config, &resolver);
+                    match tokio::task::spawn_blocking(task).await {
+                        Ok(Some(Ok(()))) => debug!("Reloaded TLS certificate."),
+                        Ok(Some(Err(e))) => error!("Failed to reload TLS certificate: {}", e),
+                        Ok(None) => break,
+                        Err(e) => error!("Failed to reload TLS certificate: {}", e),
+                    }
+                }
+            });
+        }
+
+        self.tls_reloader.add(move || {
+            reload(&tls_config, &resolver).unwrap_or_else(|| {
+                Err(io::Error::new(io::ErrorKind::Other, "TLS is not running"))
+            })
+        });
+    }
+
+    /// Returns a `Future` that drives the server on `listener`, a listener that
+    /// has already been bound, instead of binding to the configured address.
+    /// The `Future` completes under the same conditions as the one returned by
+    /// [`Rocket::launch()`].
+    ///
+    /// The `address` and `port` in the active configuration, as well as the
+    /// launch message, are set from [`Listener::local_addr()`], which also
+    /// forms the single endpoint reported by [`Request::endpoint()`]. Any
+    /// `endpoints`, `tls`, `unix`, or `proxy_protocol` configuration is not
+    /// used; the listener is responsible for its own transport, and the
+    /// endpoint is reported without TLS.
+    ///
+    /// [`Request::endpoint()`]: crate::Request::endpoint()
+    ///
+    /// [`Listener::local_addr()`]: crate::http::Listener::local_addr()
+    ///
+    /// # Error
+    ///
+    /// If there is a problem starting the application, an [`Error`] is
+    /// returned. See [`Rocket::launch()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::tokio::net::TcpListener;
+    ///
+    /// #[rocket::main]
+    /// async fn main() {
+    ///     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let result = rocket::ignite().launch_on(listener).await;
+    ///     assert!(result.is_ok());
+    /// }
+    /// ```
+    pub async fn launch_on<L>(self, listener: L) -> Result<(), Error>
+        where L: Listener + Send + Unpin + 'static,
+              <L as Listener>::Connection: Send + Unpin + 'static,
+    {
+        // The listener is plain as far as Rocket knows: even if `tls` is
+        // configured, it isn't used here, so the endpoint must not claim it.
+        let endpoints = listener.local_addr()
+            .map(|addr| Endpoint::new(addr.ip(), addr.port()))
+            .into_iter()
+            .collect();
+
+        self.serve(listener, endpoints, "a custom listener".to_string()).await
+    }
 
+    /// Drives the server on `listener`, which serves `endpoints`, until it is
+    /// shut down by a shutdown handle or signal. If there are no `endpoints`,
+    /// `label` names the listener in the launch message.
+    async fn serve<L>(
+        self,
+        listener: L,
+        endpoints: Vec<Endpoint>,
+        label: String
+    ) -> Result<(), Error>
+        where L: Listener + Send + Unpin + 'static,
+              <L as Listener>::Connection: Send + Unpin + 'static,
+    {
+        use futures::future::Either;
+
+        // We `select` on the configured shutdown signals, including `ctrl-c`
+        // if enabled, and the server. If none are enabled, this is `pending`.
+        let shutdown_handle = self.shutdown_handle.clone();
+        let shutdown_signal = self.config.shutdown.signal(self.config.ctrlc);
+
+        let server = self.listen_on(listener, endpoints, label).boxed();
         match futures::future::select(shutdown_signal, server).await {
-            Either::Left((Ok(()), server)) => {
-                // Ctrl-was pressed. Signal shutdown, wait for the server.
+            Either::Left(((), server)) => {
+                // A signal was received. Signal shutdown, wait for the server.
                 shutdown_handle.shutdown();
                 server.await
             }
-            Either::Left((Err(err), server)) => {
-                // Error setting up ctrl-c signal. Let the user know.
-                warn!("Failed to enable `ctrl-c` graceful signal shutdown.");
-                info_!("Error: {}", err);
-                server.await
-            }
-            // Server shut down before Ctrl-C; return the result.
+            // Server shut down before a signal; return the result.
             Either::Right((result, _)) => result,
         }
     }
@@ -1,7 +1,8 @@
 use super::Route;
 
 use crate::http::MediaType;
-use crate::http::route::Kind;
+use crate::http::route::{Kind, Piece, RouteSegment};
+use crate::http::uri::Path;
 use crate::request::Request;
 
 impl Route {
@@ -41,8 +42,14 @@ impl Route {
     pub fn matches(&self, req: &Request<'_>) -> bool {
         self.method == req.method()
             && paths_match(self, req)
-            && queries_match(self, req)
-            && formats_match(self, req)
+            && self.query_and_format_match(req)
+    }
+
+    /// Determines if this route's query and format match against the given
+    /// request, as in [`Route::matches()`], without considering its method or
+    /// path. The router matches those by indexing its routes instead.
+    pub(crate) fn query_and_format_match(&self, req: &Request<'_>) -> bool {
+        queries_match(self, req) && formats_match(self, req)
     }
 }
 
@@ -54,16 +61,45 @@ fn paths_collide(route: &Route, other: &Route) -> bool {
             return true;
         }
 
-        if seg_a.kind == Kind::Static && seg_b.kind == Kind::Static {
-            if seg_a.string != seg_b.string {
-                return false;
-            }
+        if !segments_collide(seg_a, seg_b) {
+            return false;
         }
     }
 
     a_segments.len() == b_segments.len()
 }
 
+/// Determines if some request path segment can match both `a` and `b`, neither
+/// of which is of kind `Multi`. Two parameters collide unless their constraints
+/// are provably disjoint. Two mixed segments are considered to collide unless
+/// their leading or trailing static text conflicts.
+fn segments_collide(a: &RouteSegment<'_, Path>, b: &RouteSegment<'_, Path>) -> bool {
+    match (a.kind, b.kind) {
+        (Kind::Static, _) => b.matches(&a.string),
+        (_, Kind::Static) => a.matches(&b.string),
+        (Kind::Single, Kind::Single) => match (&a.constraint, &b.constraint) {
+            (Some(a), Some(b)) => !a.is_disjoint(b),
+            _ => true,
+        },
+        (Kind::Mixed, Kind::Mixed) => {
+            let ((a_prefix, a_suffix), (b_prefix, b_suffix)) = (affixes(a), affixes(b));
+            (a_prefix.starts_with(b_prefix) || b_prefix.starts_with(a_prefix))
+                && (a_suffix.ends_with(b_suffix) || b_suffix.ends_with(a_suffix))
+        }
+        _ => true,
+    }
+}
+
+/// Returns the static text at the start and end of the mixed `segment`.
+fn affixes<'s>(segment: &'s RouteSegment<'_, Path>) -> (&'s str, &'s str) {
+    let text = |piece: Option<&'s Piece<'_>>| match piece {
+        Some(Piece::Static(text)) => &**text,
+        _ => "",
+    };
+
+    (text(segment.pieces.first()), text(segment.pieces.last()))
+}
+
 fn paths_match(route: &Route, request: &Request<'_>) -> bool {
     let route_segments = &route.metadata.path_segments;
     if route_segments.len() > request.state.path_segments.len() {
@@ -74,7 +110,7 @@ fn paths_match(route: &Route, request: &Request<'_>) -> bool {
     for (route_seg, req_seg) in route_segments.iter().zip(request_segments) {
         match route_seg.kind {
             Kind::Multi => return true,
-            Kind::Static if &*route_seg.string != req_seg.as_str() => return false,
+            _ if !route_seg.matches(req_seg.as_str()) => return false,
             _ => continue,
         }
     }
@@ -258,6 +294,50 @@ mod tests {
         assert!(!unranked_collide("/", "/a"));
     }
 
+    #[test]
+    fn mixed_segment_collisions() {
+        assert!(unranked_collide("/<name>.<ext>", "/<name>"));
+        assert!(unranked_collide("/<name>.<ext>", "/file.txt"));
+        assert!(unranked_collide("/<name>.json", "/<id>.<ext>"));
+        assert!(unranked_collide("/<name>.tar.gz", "/<name>.gz"));
+        assert!(unranked_collide("/v<version>/a", "/<major>.<minor>/a"));
+        assert!(unranked_collide("/v<a>-<b>", "/v1-<b>"));
+        assert!(unranked_collide("/<a..>", "/v<version>"));
+    }
+
+    #[test]
+    fn mixed_segment_non_collisions() {
+        assert!(!unranked_collide("/<name>.<ext>", "/file"));
+        assert!(!unranked_collide("/<name>.<ext>", "/.txt"));
+        assert!(!unranked_collide("/<name>.json", "/<name>.xml"));
+        assert!(!unranked_collide("/<id>.json", "/file.txt"));
+        assert!(!unranked_collide("/v<version>", "/w<version>"));
+        assert!(!unranked_collide("/v<version>", "/v"));
+        assert!(!unranked_collide("/<id>.json", "/<id>.json/a"));
+    }
+
+    #[test]
+    fn constrained_param_collisions() {
+        assert!(unranked_collide("/<id:int>", "/<id>"));
+        assert!(unranked_collide("/<id:int>", "/<n:uint>"));
+        assert!(unranked_collide("/<id:int>", "/10"));
+        assert!(unranked_collide("/<id:int>/a", "/<a..>"));
+        assert!(unranked_collide("/<slug:[a-z-]+>", "/hello-world"));
+        assert!(unranked_collide("/<slug:[a-z-]+>", "/<name:alpha>"));
+        assert!(unranked_collide("/<id:uint>.json", "/10.json"));
+        assert!(unranked_collide("/<name>.<ext>", "/<id:int>"));
+    }
+
+    #[test]
+    fn constrained_param_non_collisions() {
+        assert!(!unranked_collide("/<id:int>", "/new"));
+        assert!(!unranked_collide("/<id:int>", "/<name:alpha>"));
+        assert!(!unranked_collide("/<id:uint>", "/<slug:[a-z-]+>"));
+        assert!(!unranked_collide("/a/<id:uint>", "/a/<v:v[0-9]+>"));
+        assert!(!unranked_collide("/<slug:[a-z-]+>", "/Hello"));
+        assert!(!unranked_collide("/<id:uint>.json", "/latest.json"));
+    }
+
     #[test]
     fn query_non_collisions() {
         assert!(!unranked_collide("/a?<b>", "/b"));
@@ -509,4 +589,32 @@ mod tests {
         assert!(!req_route_path_match("/a/b", "/a/b?foo&<rest..>"));
         assert!(!req_route_path_match("/a/b", "/a/b?<a>&b&<rest..>"));
     }
+
+    #[test]
+    fn test_req_route_mixed_segment_match() {
+        assert!(req_route_path_match("/file.txt", "/<name>.<ext>"));
+        assert!(req_route_path_match("/a.tar.gz", "/<name>.<ext>"));
+        assert!(req_route_path_match("/v1.2/a", "/v<major>.<minor>/a"));
+        assert!(req_route_path_match("/page-2.json", "/page-<n>.json"));
+
+        assert!(!req_route_path_match("/file", "/<name>.<ext>"));
+        assert!(!req_route_path_match("/.txt", "/<name>.<ext>"));
+        assert!(!req_route_path_match("/file.", "/<name>.<ext>"));
+        assert!(!req_route_path_match("/v1/a", "/v<major>.<minor>/a"));
+        assert!(!req_route_path_match("/page-2.xml", "/page-<n>.json"));
+        assert!(!req_route_path_match("/file.txt/a", "/<name>.<ext>"));
+    }
+
+    #[test]
+    fn test_req_route_constrained_match() {
+        assert!(req_route_path_match("/user/10", "/user/<id:int>"));
+        assert!(req_route_path_match("/user/-10", "/user/<id:int>"));
+        assert!(req_route_path_match("/post/hello-world", "/post/<slug:[a-z-]+>"));
+        assert!(req_route_path_match("/10.json", "/<id:uint>.json"));
+
+        assert!(!req_route_path_match("/user/bob", "/user/<id:int>"));
+        assert!(!req_route_path_match("/user/10a", "/user/<id:int>"));
+        assert!(!req_route_path_match("/post/Hello", "/post/<slug:[a-z-]+>"));
+        assert!(!req_route_path_match("/latest.json", "/<id:uint>.json"));
+    }
 }
@@ -1,20 +1,24 @@
 mod collider;
 mod route;
+mod tree;
 
 use std::collections::HashMap;
 
 use crate::request::Request;
 use crate::http::Method;
+use crate::http::private::SmallVec;
 use crate::handler::dummy;
 
 pub use self::route::Route;
 
+use self::tree::Tree;
+
 // type Selector = (Method, usize);
 type Selector = Method;
 
 #[derive(Default)]
 pub struct Router {
-    routes: HashMap<Selector, Vec<Route>>,
+    routes: HashMap<Selector, Tree>,
 }
 
 impl Router {
@@ -24,18 +28,15 @@ impl Router {
 
     pub fn add(&mut self, route: Route) {
         let selector = route.method;
-        let entries = self.routes.entry(selector).or_insert_with(|| vec![]);
-        let i = entries.binary_search_by_key(&route.rank, |r| r.rank)
-            .unwrap_or_else(|i| i);
-
-        entries.insert(i, route);
+        self.routes.entry(selector).or_default().add(route);
     }
 
-    pub fn route<'b>(&'b self, req: &Request<'_>) -> Vec<&'b Route> {
-        // Note that routes are presorted by rank on each `add`.
-        let matches = self.routes.get(&req.method()).map_or(vec![], |routes| {
-            routes.iter()
-                .filter(|r| r.matches(req))
+    pub fn route<'b>(&'b self, req: &Request<'_>) -> SmallVec<[&'b Route; 4]> {
+        // The tree yields the routes whose path matches, ordered by rank.
+        let matches = self.routes.get(&req.method()).map_or(SmallVec::new(), |tree| {
+            tree.matches(req.uri().path(), &req.state.path_segments)
+                .into_iter()
+                .filter(|r| r.query_and_format_match(req))
                 .collect()
         });
 
@@ -44,9 +45,21 @@ impl Router {
         matches
     }
 
+    /// Returns the methods, sorted by name, of the routes whose path matches
+    /// the path of `req`, regardless of their query or format.
+    pub fn methods_matching_path(&self, req: &Request<'_>) -> SmallVec<[Method; 4]> {
+        let mut methods: SmallVec<[Method; 4]> = self.routes.iter()
+            .filter(|(_, tree)| tree.matches_path(req.uri().path(), &req.state.path_segments))
+            .map(|(&method, _)| method)
+            .collect();
+
+        methods.sort_by_key(|method| method.as_str());
+        methods
+    }
+
     pub(crate) fn collisions(&mut self) -> Result<(), Vec<(Route, Route)>> {
         let mut collisions = vec![];
-        for routes in self.routes.values_mut() {
+        for routes in self.routes.values_mut().map(|tree| &mut tree.routes) {
             for i in 0..routes.len() {
                 let (left, right) = routes.split_at_mut(i);
                 for a_route in left.iter_mut() {
@@ -72,13 +85,13 @@ impl Router {
 
     #[inline]
     pub fn routes<'a>(&'a self) -> impl Iterator<Item=&'a Route> + 'a {
-        self.routes.values().flat_map(|v| v.iter())
+        self.routes.values().flat_map(|tree| tree.routes.iter())
     }
 
     // This is slow. Don't expose this publicly; only for tests.
     #[cfg(test)]
     fn has_collisions(&self) -> bool {
-        for routes in self.routes.values() {
+        for routes in self.routes.values().map(|tree| &tree.routes) {
             for (i, a_route) in routes.iter().enumerate() {
                 for b_route in routes.iter().skip(i + 1) {
                     if a_route.collides_with(b_route) {
@@ -256,7 +269,7 @@ mod test {
     fn matches<'a>(router: &'a Router, method: Method, uri: &str) -> Vec<&'a Route> {
         let rocket = Rocket::custom(Config::default());
         let request = Request::new(&rocket, method, Origin::parse(uri).unwrap());
-        router.route(&request)
+        router.route(&request).into_vec()
     }
 
     #[test]
@@ -444,6 +457,44 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_tree_routing() {
+        // Every static, dynamic, and `<..>` branch that matches is considered.
+        assert_ranked_routing!(
+            to: "/a/b/c",
+            with: [
+                (4, "/<a..>"), (3, "/a/<b..>"), (2, "/a/<b>/c"), (1, "/a/b/c"),
+                (5, "/a/b/<c>"), (0, "/a/b"), (0, "/a/b/c/d"), (0, "/b/<c..>")
+            ],
+            expect: (1, "/a/b/c"), (2, "/a/<b>/c"), (3, "/a/<b..>"), (4, "/<a..>"),
+                (5, "/a/b/<c>")
+        );
+
+        // A `<..>` segment matches one or more segments.
+        assert_ranked_routing!(
+            to: "/a",
+            with: [(1, "/a/<b..>"), (2, "/<a..>"), (3, "/a")],
+            expect: (2, "/<a..>"), (3, "/a")
+        );
+
+        // Routes of equal rank are ordered by when they were added.
+        assert_ranked_routing!(
+            to: "/a/b",
+            with: [(1, "/<a>/b"), (1, "/a/<b>"), (0, "/<a>/<b>")],
+            expect: (0, "/<a>/<b>"), (1, "/<a>/b"), (1, "/a/<b>")
+        );
+
+        // Checked segments only match request segments that satisfy them.
+        assert_ranked_routing!(
+            to: "/a/10.json",
+            with: [
+                (1, "/a/<b:alpha>.json"), (2, "/a/<b:int>.json"), (3, "/a/<b>"),
+                (4, "/a/<b:[0-9a-z.]+>"), (5, "/a/<b:alpha>")
+            ],
+            expect: (2, "/a/<b:int>.json"), (3, "/a/<b>"), (4, "/a/<b:[0-9a-z.]+>")
+        );
+    }
+
     macro_rules! assert_default_ranked_routing {
         (to: $to:expr, with: $routes:expr, expect: $($want:expr),+) => ({
             let router = router_with_routes(&$routes);
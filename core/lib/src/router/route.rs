@@ -1,5 +1,6 @@
 use std::fmt::{self, Display};
 use std::convert::From;
+use std::time::Duration;
 
 use yansi::Paint;
 
@@ -31,6 +32,9 @@ pub struct Route {
     pub rank: isize,
     /// The media type this route matches against, if any.
     pub format: Option<MediaType>,
+    /// The maximum time this route's handler may run, if any. Overrides the
+    /// configured [`Timeouts::handler`](crate::config::Timeouts::handler).
+    pub timeout: Option<Duration>,
     /// Cached metadata that aids in routing later.
     pub(crate) metadata: Metadata,
 }
@@ -180,6 +184,7 @@ impl Route {
             uri: route_path,
             name: None,
             format: None,
+            timeout: None,
             base: Origin::dummy(),
             handler: Box::new(handler),
             metadata: Metadata::default(),
@@ -293,6 +298,10 @@ impl fmt::Display for Route {
             write!(f, " {}", Paint::yellow(format))?;
         }
 
+        if let Some(timeout) = self.timeout {
+            write!(f, " {}", Paint::yellow(format_args!("timeout={:?}", timeout)))?;
+        }
+
         if let Some(name) = self.name {
             write!(f, " {}{}{}",
                    Paint::cyan("("), Paint::magenta(name), Paint::cyan(")"))?;
@@ -311,6 +320,7 @@ impl fmt::Debug for Route {
             .field("uri", &self.uri)
             .field("rank", &self.rank)
             .field("format", &self.format)
+            .field("timeout", &self.timeout)
             .field("metadata", &self.metadata)
             .finish()
     }
@@ -323,6 +333,7 @@ impl From<StaticRouteInfo> for Route {
         let mut route = Route::new(info.method, info.path, info.handler);
         route.format = info.format;
         route.name = Some(info.name);
+        route.timeout = info.timeout;
         if let Some(rank) = info.rank {
             route.rank = rank;
         }
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::http::route::{Kind, RouteSegment};
+use crate::http::uri::Path;
+use crate::http::private::SmallVec;
+
+use super::Route;
+
+/// The routes for a single method, indexed by a prefix tree of their path
+/// segments.
+///
+/// Each node of the tree corresponds to a path prefix. A route is stored, by
+/// index, at the node for its full path or, if its path ends in a `<..>`
+/// segment, at the node for the prefix before that segment. Matching a request
+/// thus only visits the nodes along paths that could match the request's path
+/// instead of every route.
+#[derive(Default)]
+pub(crate) struct Tree {
+    /// Every route, in the order they were added.
+    pub(crate) routes: Vec<Route>,
+    root: Node,
+}
+
+#[derive(Default)]
+struct Node {
+    /// The children for static segments, keyed by the segment.
+    statics: HashMap<String, Node>,
+    /// The child for unconstrained `<param>` segments, if any.
+    dynamic: Option<Box<Node>>,
+    /// The children for segments that must be checked against the request's
+    /// segment, one per distinct segment: constrained parameters like
+    /// `<id:int>` and mixed segments like `<name>.<ext>`.
+    checked: Vec<(RouteSegment<'static, Path>, Node)>,
+    /// The routes whose path ends at this node.
+    routes: Vec<usize>,
+    /// The routes whose path ends with a `<..>` segment after this node.
+    trailing: Vec<usize>,
+}
+
+impl Tree {
+    pub(crate) fn add(&mut self, route: Route) {
+        let index = self.routes.len();
+        let mut node = &mut self.root;
+        let mut trailing = false;
+        for segment in &route.metadata.path_segments {
+            node = match segment.kind {
+                Kind::Static => node.statics.entry(segment.string.to_string()).or_default(),
+                Kind::Single if segment.constraint.is_none() => {
+                    &mut **node.dynamic.get_or_insert_with(Default::default)
+                }
+                Kind::Single | Kind::Mixed => node.checked_child(segment),
+                Kind::Multi => {
+                    trailing = true;
+                    break;
+                }
+            };
+        }
+
+        match trailing {
+            true => node.trailing.push(index),
+            false => node.routes.push(index),
+        }
+
+        self.routes.push(route);
+    }
+
+    /// Returns the routes whose path matches `path`, whose segments are
+    /// delimited by `segments`, ordered by rank. Routes with the same rank are
+    /// ordered by when they were added.
+    pub(crate) fn matches(
+        &self,
+        path: &str,
+        segments: &[(usize, usize)]
+    ) -> SmallVec<[&Route; 4]> {
+        let mut indices = SmallVec::<[usize; 4]>::new();
+        self.root.collect(path, segments, &mut indices);
+        indices.sort_unstable_by_key(|&i| (self.routes[i].rank, i));
+        indices.into_iter().map(|i| &self.routes[i]).collect()
+    }
+
+    /// Returns `true` if the path of any route matches `path`, whose segments
+    /// are delimited by `segments`.
+    pub(crate) fn matches_path(&self, path: &str, segments: &[(usize, usize)]) -> bool {
+        let mut indices = SmallVec::<[usize; 4]>::new();
+        self.root.collect(path, segments, &mut indices);
+        !indices.is_empty()
+    }
+}
+
+impl Node {
+    /// Returns the child for the checked `segment`, adding it if necessary.
+    fn checked_child(&mut self, segment: &RouteSegment<'static, Path>) -> &mut Node {
+        let i = match self.checked.iter().position(|(s, _)| s.string == segment.string) {
+            Some(i) => i,
+            None => {
+                self.checked.push((segment.clone(), Node::default()));
+                self.checked.len() - 1
+            }
+        };
+
+        &mut self.checked[i].1
+    }
+
+    fn collect(&self, path: &str, segments: &[(usize, usize)], out: &mut SmallVec<[usize; 4]>) {
+        let ((i, j), rest) = match segments.split_first() {
+            Some((&segment, rest)) => (segment, rest),
+            None => return out.extend_from_slice(&self.routes),
+        };
+
+        // A `<..>` segment matches one or more remaining segments.
+        out.extend_from_slice(&self.trailing);
+        if let Some(child) = self.statics.get(&path[i..j]) {
+            child.collect(path, rest, out);
+        }
+
+        if let Some(ref child) = self.dynamic {
+            child.collect(path, rest, out);
+        }
+
+        for (segment, child) in &self.checked {
+            if segment.matches(&path[i..j]) {
+                child.collect(path, rest, out);
+            }
+        }
+    }
+}
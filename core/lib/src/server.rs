@@ -1,23 +1,27 @@
 use std::io;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use futures::stream::StreamExt;
-use futures::future::{Future, BoxFuture};
+use futures::future::{self, Future, FutureExt, BoxFuture, Either};
 use tokio::sync::oneshot;
 use yansi::Paint;
 
 use crate::Rocket;
 use crate::handler;
-use crate::request::{Request, FormItems};
+use crate::request::{Request, FormItems, ConnectionMeta};
 use crate::data::Data;
-use crate::response::{Body, Response};
+use crate::response::{Body, ChunkStream, Response, Upgraded};
 use crate::outcome::Outcome;
 use crate::error::{Error, ErrorKind};
+use crate::config::{Endpoint, find_endpoint};
 use crate::logger::PaintExt;
-use crate::ext::AsyncReadExt;
+use crate::ext::{AsyncReadExt, CancellableListener, CancellableIo, Trigger};
+use crate::ext::{HeaderTimeoutListener, HeaderTimeoutIo, RequestTracker, InFlight};
 
-use crate::http::{Method, Status, Header, hyper};
-use crate::http::private::{Listener, Connection, Incoming};
+use crate::http::{Method, Status, Header, Listener, Connection, hyper};
+use crate::http::private::{Incoming, SmallVec};
 use crate::http::uri::Origin;
 
 // A token returned to force the execution of one method before another.
@@ -29,7 +33,9 @@ pub(crate) struct Token;
 // `HyperResponse` type, this function does the actual response processing.
 async fn hyper_service_fn(
     rocket: Arc<Rocket>,
-    h_addr: std::net::SocketAddr,
+    conn: ConnectionMeta,
+    cancel: Trigger,
+    requests: Arc<RequestTracker>,
     hyp_req: hyper::Request<hyper::Body>,
 ) -> Result<hyper::Response<hyper::Body>, io::Error> {
     // This future must return a hyper::Response, but the response body might
@@ -37,13 +43,20 @@ async fn hyper_service_fn(
     // sends the response metadata (and a body channel) prior.
     let (tx, rx) = oneshot::channel();
 
-    tokio::spawn(async move {
+    // The request is abandoned if it's still running when `cancel` triggers,
+    // i.e, when the shutdown grace period has elapsed. The connection's header
+    // timeout is paused while the request is in flight.
+    let in_flight = requests.start();
+    let (in_flight_request, requests_in_flight) = rocket.load.start_request();
+    let request = async move {
+        let _in_flight = (in_flight, in_flight_request);
+
         // Get all of the information from Hyper.
         let (h_parts, h_body) = hyp_req.into_parts();
 
         // Convert the Hyper request into a Rocket request.
         let req_res = Request::from_hyp(
-            &rocket, h_parts.method, h_parts.headers, &h_parts.uri, h_addr
+            &rocket, h_parts.method, h_parts.headers, &h_parts.uri, conn
         );
 
         let mut req = match req_res {
@@ -56,23 +69,62 @@ async fn hyper_service_fn(
                 // handler) instead of doing this.
                 let dummy = Request::new(&rocket, Method::Get, Origin::dummy());
                 let r = rocket.handle_error(Status::BadRequest, &dummy).await;
-                return rocket.send_response(r, tx).await;
+                return rocket.send_response(r, tx, None).await;
             }
         };
 
-        // Retrieve the data from the hyper body.
-        let mut data = Data::from_hyp(h_body).await;
+        // Shed the request if too many are already in flight.
+        if let Some(max) = rocket.config.load.max_requests {
+            if requests_in_flight > max {
+                warn!("Request limit of {} reached. Shedding {}.", max, req);
+                let r = rocket.overloaded_response(&req).await;
+                return rocket.send_response(r, tx, None).await;
+            }
+        }
+
+        // Retrieve the data from the hyper body. Requests asking for a protocol
+        // upgrade have no body: keep its handle to the upgraded IO instead. An
+        // upgraded connection never awaits another request's headers.
+        let (mut data, on_upgrade) = if is_upgrade_request(&req, &h_body) {
+            (Data::local(vec![]), Some((h_body.on_upgrade(), requests.start())))
+        } else {
+            (Data::from_hyp(h_body, rocket.config.timeouts.body_idle).await, None)
+        };
 
         // Dispatch the request to get a response, then write that response out.
         let token = rocket.preprocess_request(&mut req, &mut data).await;
-        let r = rocket.dispatch(token, &mut req, data).await;
-        rocket.send_response(r, tx).await;
-    });
+        let mut r = rocket.dispatch(token, &req, data).await;
+
+        // A `101` tells the client that the connection switched protocols. If
+        // the request can't be upgraded, that would be a lie: fail instead.
+        if r.status() == Status::SwitchingProtocols && on_upgrade.is_none() {
+            error_!("Request cannot be upgraded. Refusing to send a 101 response.");
+            r = rocket.handle_error(Status::InternalServerError, &req).await;
+        }
+
+        rocket.send_response(r, tx, on_upgrade).await;
+    };
+
+    tokio::spawn(future::select(request.boxed(), cancel));
 
     // Receive the response written to `tx` by the task above.
     rx.await.map_err(|e| io::Error::new(io::ErrorKind::Other, e))
 }
 
+/// Whether `req` asks for a connection upgrade that a response could accept:
+/// it has an `Upgrade` header, `upgrade` among its `Connection` options, and no
+/// body. Clients may offer an upgrade opportunistically, for instance to
+/// `h2c`, on requests with a body; those are handled as usual.
+fn is_upgrade_request(req: &Request<'_>, body: &hyper::Body) -> bool {
+    use crate::http::hyper::HttpBody;
+
+    let connection_upgrade = req.headers().get("Connection")
+        .flat_map(|options| options.split(','))
+        .any(|option| option.trim().eq_ignore_ascii_case("upgrade"));
+
+    req.headers().contains("Upgrade") && connection_upgrade && body.is_end_stream()
+}
+
 impl Rocket {
     /// Wrapper around `make_response` to log a success or failure.
     #[inline]
@@ -80,20 +132,42 @@ impl Rocket {
         &self,
         response: Response<'_>,
         tx: oneshot::Sender<hyper::Response<hyper::Body>>,
+        on_upgrade: Option<(hyper::OnUpgrade, InFlight)>,
     ) {
-        match self.make_response(response, tx).await {
+        match self.make_response(response, tx, on_upgrade).await {
             Ok(()) => info_!("{}", Paint::green("Response succeeded.")),
             Err(e) => error_!("Failed to write response: {:?}.", e),
         }
     }
 
     /// Attempts to create a hyper response from `response` and send it to `tx`.
+    /// If `response` upgrades the connection, its upgrade is run on the IO
+    /// yielded by `on_upgrade`, with the connection marked in-flight until the
+    /// upgrade completes.
     #[inline]
     async fn make_response(
         &self,
         mut response: Response<'_>,
         tx: oneshot::Sender<hyper::Response<hyper::Body>>,
+        on_upgrade: Option<(hyper::OnUpgrade, InFlight)>,
     ) -> io::Result<()> {
+        if let Some(upgrade) = response.take_upgrade() {
+            let switching = response.status() == Status::SwitchingProtocols;
+            match on_upgrade {
+                Some((on_upgrade, in_flight)) if switching => {
+                    tokio::spawn(async move {
+                        let _in_flight = in_flight;
+                        match on_upgrade.await {
+                            Ok(io) => upgrade.start(Upgraded::new(io)).await,
+                            Err(e) => error!("Failed to upgrade connection: {}", e),
+                        }
+                    });
+                }
+                Some(_) => warn_!("Response is not a 101. Ignoring its upgrade."),
+                None => error_!("Request did not ask for an upgrade. Ignoring response's upgrade."),
+            }
+        }
+
         let mut hyp_res = hyper::Response::builder()
             .status(response.status().code);
 
@@ -115,7 +189,11 @@ impl Rocket {
 
         match response.body_mut() {
             None => {
-                hyp_res = hyp_res.header(hyper::header::CONTENT_LENGTH, 0);
+                // A `101` switches protocols; it can't specify a length.
+                if response.status() != Status::SwitchingProtocols {
+                    hyp_res = hyp_res.header(hyper::header::CONTENT_LENGTH, 0);
+                }
+
                 send_response(hyp_res, hyper::Body::empty())?;
             }
             Some(body) => {
@@ -123,15 +201,17 @@ impl Rocket {
                     hyp_res = hyp_res.header(hyper::header::CONTENT_LENGTH, s);
                 }
 
-                let chunk_size = match *body {
-                    Body::Chunked(_, chunk_size) => chunk_size as usize,
-                    Body::Sized(_, _) => crate::response::DEFAULT_CHUNK_SIZE,
-                };
-
                 let (mut sender, hyp_body) = hyper::Body::channel();
                 send_response(hyp_res, hyp_body)?;
 
-                let mut stream = body.as_reader().into_bytes_stream(chunk_size);
+                let mut stream: ChunkStream<'_> = match *body {
+                    Body::Chunked(ref mut body, chunk_size) => body.chunks(chunk_size),
+                    Body::Sized(ref mut body, _) => {
+                        let chunk_size = crate::response::DEFAULT_CHUNK_SIZE;
+                        Box::pin(body.into_bytes_stream(chunk_size))
+                    }
+                };
+
                 while let Some(next) = stream.next().await {
                     sender.send_data(next?).await
                         .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
@@ -217,11 +297,12 @@ impl Rocket {
         data: Data
     ) -> impl Future<Output = Response<'r>> + Send + 's {
         async move {
+            let body_timed_out = data.idle_timeout_watch();
             let mut response = match self.route(request, data).await {
                 Outcome::Success(response) => response,
-                Outcome::Forward(data) => {
+                Outcome::Forward(data) => match request.method() {
                     // There was no matching route. Autohandle `HEAD` requests.
-                    if request.method() == Method::Head {
+                    Method::Head => {
                         info_!("Autohandling {} request.", Paint::default("HEAD").bold());
 
                         // Dispatch the request again with Method `GET`.
@@ -231,14 +312,24 @@ impl Rocket {
                         let try_next: BoxFuture<'_, _> =
                             Box::pin(self.route_and_process(request, data));
                         return try_next.await;
-                    } else {
-                        // No match was found and it can't be autohandled. 404.
-                        self.handle_error(Status::NotFound, request).await
                     }
-                }
+                    // Autohandle `OPTIONS` requests using the route table.
+                    Method::Options => {
+                        info_!("Autohandling {} request.", Paint::default("OPTIONS").bold());
+                        self.handle_options(request).await
+                    }
+                    // No match was found and it can't be autohandled.
+                    _ => self.handle_unmatched(request).await,
+                },
                 Outcome::Failure(status) => self.handle_error(status, request).await,
             };
 
+            // Whatever the outcome, a stalled body means the request timed out.
+            if body_timed_out() {
+                warn_!("Request body idle timeout elapsed.");
+                response = self.handle_error(Status::RequestTimeout, request).await;
+            }
+
             // Set the cookies. Note that error responses will only include
             // cookies set by the error handler. See `handle_error` for more.
             let delta_jar = request.cookies().take_delta_jar();
@@ -269,8 +360,21 @@ impl Rocket {
                 info_!("Matched: {}", route);
                 request.set_route(route);
 
-                // Dispatch the request to the handler.
-                let outcome = route.handler.handle(request, data).await;
+                // Dispatch the request to the handler, cancelling it if it runs
+                // longer than the route's or the configured timeout.
+                let outcome = match route.timeout.or(self.config.timeouts.handler) {
+                    Some(limit) => {
+                        let handler = route.handler.handle(request, data);
+                        match tokio::time::timeout(limit, handler).await {
+                            Ok(outcome) => outcome,
+                            Err(_) => {
+                                warn_!("Handler timed out after {:?}.", limit);
+                                Outcome::Failure(Status::ServiceUnavailable)
+                            }
+                        }
+                    }
+                    None => route.handler.handle(request, data).await,
+                };
 
                 // Check if the request processing completed (Some) or if the
                 // request needs to be forwarded. If it does, continue the loop
@@ -330,8 +434,104 @@ impl Rocket {
         }
     }
 
-    // TODO.async: Solidify the Listener APIs and make this function public
-    pub(crate) async fn listen_on<L>(mut self, listener: L) -> Result<(), Error>
+    /// Returns the methods, sorted by name, that requests for the path of
+    /// `req` can be handled with: the methods of the routes whose path matches
+    /// as well as, if there are any, the autohandled `OPTIONS` and, if `GET` is
+    /// among them, the autohandled `HEAD`.
+    fn allowed_methods(&self, req: &Request<'_>) -> SmallVec<[Method; 4]> {
+        let mut methods = self.router.methods_matching_path(req);
+        if methods.is_empty() {
+            return methods;
+        }
+
+        if methods.contains(&Method::Get) && !methods.contains(&Method::Head) {
+            methods.push(Method::Head);
+        }
+
+        if !methods.contains(&Method::Options) {
+            methods.push(Method::Options);
+        }
+
+        methods.sort_by_key(|method| method.as_str());
+        methods
+    }
+
+    /// Returns an `Allow` header listing `methods`.
+    fn allow_header(methods: &[Method]) -> Header<'static> {
+        let methods = methods.iter().map(|m| m.as_str()).collect::<Vec<_>>();
+        Header::new("Allow", methods.join(", "))
+    }
+
+    /// Responds to `req`, an `OPTIONS` request that no route handled, with
+    /// `204 No Content` and an `Allow` header if any route's path matches its
+    /// path, or with the `404` catcher's response otherwise.
+    async fn handle_options<'s, 'r: 's>(&'s self, req: &'r Request<'s>) -> Response<'r> {
+        let allowed = self.allowed_methods(req);
+        if allowed.is_empty() {
+            return self.handle_error(Status::NotFound, req).await;
+        }
+
+        Response::build()
+            .status(Status::NoContent)
+            .header(Self::allow_header(&allowed))
+            .finalize()
+    }
+
+    /// Responds to `req`, which no route handled, with the `405` catcher's
+    /// response and an `Allow` header if routes for other methods match its
+    /// path and `method_not_allowed` is enabled, or with the `404` catcher's
+    /// response otherwise.
+    async fn handle_unmatched<'s, 'r: 's>(&'s self, req: &'r Request<'s>) -> Response<'r> {
+        let allowed = self.allowed_methods(req);
+        if !self.config.method_not_allowed
+            || allowed.is_empty()
+            || allowed.contains(&req.method())
+        {
+            return self.handle_error(Status::NotFound, req).await;
+        }
+
+        let mut response = self.handle_error(Status::MethodNotAllowed, req).await;
+        response.set_header(Self::allow_header(&allowed));
+        response
+    }
+
+    /// Responds to `req` with the `503` catcher's response and a `Retry-After`
+    /// header, shedding load.
+    async fn overloaded_response<'s, 'r: 's>(&'s self, req: &'r Request<'s>) -> Response<'r> {
+        let mut response = self.handle_error(Status::ServiceUnavailable, req).await;
+        let retry_after = self.config.load.retry_after.to_string();
+        response.set_header(Header::new("Retry-After", retry_after));
+        response
+    }
+
+    /// Renders `response` as a raw HTTP/1.1 response that closes the
+    /// connection, for writing outside of any request.
+    async fn raw_response(mut response: Response<'_>) -> Vec<u8> {
+        let body = response.body_bytes().await.unwrap_or_default();
+
+        let mut head = format!("HTTP/1.1 {}\r\n", response.status());
+        for header in response.headers().iter() {
+            if header.name() != "Content-Length" && header.name() != "Connection" {
+                head.push_str(&format!("{}\r\n", header));
+            }
+        }
+
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        head.push_str("Connection: close\r\n\r\n");
+
+        let mut raw = head.into_bytes();
+        raw.extend(body);
+        raw
+    }
+
+    /// Serves on `listener`, which serves `endpoints`. If there are none,
+    /// `label` names the listener in the launch message instead.
+    pub(crate) async fn listen_on<L>(
+        mut self,
+        listener: L,
+        endpoints: Vec<Endpoint>,
+        label: String,
+    ) -> Result<(), Error>
         where L: Listener + Send + Unpin + 'static,
               <L as Listener>::Connection: Send + Unpin + 'static,
     {
@@ -342,19 +542,34 @@ impl Rocket {
         // Freeze managed state for synchronization-free accesses later.
         self.managed_state.freeze();
 
+        // Determine the addresses and ports we actually bound to so that launch
+        // fairings observe them.
+        let full_addr = match endpoints.first() {
+            Some(first) => {
+                self.config.address = first.address;
+                self.config.port = first.port;
+                if !self.config.endpoints.is_empty() {
+                    self.config.endpoints = endpoints.clone();
+                }
+
+                let addrs = endpoints.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+                addrs.join(", ")
+            }
+            None => {
+                self.config.port = 0;
+                label
+            }
+        };
+
         // Run the launch fairings.
         self.fairings.pretty_print_counts();
-        self.fairings.handle_launch(&self);
-
-        // Determine the address and port we actually bound to.
-        self.config.port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
-        let proto = self.config.tls.as_ref().map_or("http://", |_| "https://");
-        let full_addr = format!("{}:{}", self.config.address, self.config.port);
+        if let Err(failures) = self.fairings.handle_launch(&self).await {
+            return Err(Error::new(ErrorKind::FailedFairings(failures)));
+        }
 
-        launch_info!("{}{} {}{}",
+        launch_info!("{}{} {}",
                      Paint::emoji("🚀 "),
                      Paint::default("Rocket has launched from").bold(),
-                     Paint::default(proto).bold().underline(),
                      Paint::default(&full_addr).bold().underline());
 
         // Determine keep-alives.
@@ -367,25 +582,121 @@ impl Rocket {
         // We need to get this before moving `self` into an `Arc`.
         let mut shutdown_receiver = self.shutdown_receiver.take()
             .expect("shutdown receiver has already been used");
+        let shutdown_started = self.shutdown_started.take()
+            .expect("shutdown notifier has already been used");
+
+        let grace = Duration::from_secs(self.config.shutdown.grace as u64);
+        let mercy = Duration::from_secs(self.config.shutdown.mercy as u64);
+
+        // `cancel` abandons in-flight requests once `grace` elapses; `force`
+        // closes every open connection once `mercy` elapses after that.
+        let (cancel_tx, cancel) = futures::channel::oneshot::channel();
+        let (force_tx, force) = futures::channel::oneshot::channel();
+        let (cancel, force) = (cancel.shared(), force.shared());
+        let open = self.load.connections.clone();
+
+        // HTTP/1 connections that start but don't finish a request's headers in
+        // time are sent the `408` catcher's response, and connections beyond
+        // the connection limit the `503` catcher's, both rendered once up
+        // front, and closed.
+        let header_timeout = self.config.timeouts.headers;
+        let max_connections = self.config.load.max_connections;
+        let (timeout_response, overloaded_response) = {
+            let dummy = Request::new(&self, Method::Get, Origin::dummy());
+            let timeout = match header_timeout {
+                Some(_) => Self::raw_response(
+                    self.handle_error(Status::RequestTimeout, &dummy).await
+                ).await,
+                None => vec![],
+            };
+
+            let overloaded = match max_connections {
+                Some(_) => Self::raw_response(self.overloaded_response(&dummy).await).await,
+                None => vec![],
+            };
+
+            (timeout, overloaded)
+        };
+
+        let timeout_response: Arc<[u8]> = timeout_response.into();
+        let listener = HeaderTimeoutListener::new(listener, header_timeout, timeout_response);
+        let listener = CancellableListener::new(listener, force, open.clone());
 
         let rocket = Arc::new(self);
-        let service = hyper::make_service_fn(move |conn: &<L as Listener>::Connection| {
-            let rocket = rocket.clone();
-            let remote = conn.remote_addr().unwrap_or_else(|| ([0, 0, 0, 0], 0).into());
+        let service_rocket = rocket.clone();
+        let endpoints = endpoints.into_iter().map(Arc::new).collect::<Vec<_>>();
+        type Conn<C> = CancellableIo<HeaderTimeoutIo<C>>;
+        let service = hyper::make_service_fn(move |conn: &Conn<L::Connection>| {
+            let (rocket, cancel) = (service_rocket.clone(), cancel.clone());
+            let requests = conn.inner().requests().clone();
+            let meta = ConnectionMeta {
+                remote: conn.remote_addr(),
+                client_certificates: conn.peer_certificates().map(Arc::new),
+                server_name: conn.server_name(),
+                endpoint: find_endpoint(&endpoints, conn.local_addr()).cloned(),
+            };
+
             async move {
                 Ok::<_, std::convert::Infallible>(hyper::service_fn(move |req| {
-                    hyper_service_fn(rocket.clone(), remote, req)
+                    let (rocket, meta) = (rocket.clone(), meta.clone());
+                    hyper_service_fn(rocket, meta, cancel.clone(), requests.clone(), req)
                 }))
             }
         });
 
         // NOTE: `hyper` uses `tokio::spawn()` as the default executor.
-        hyper::Server::builder(Incoming::from_listener(listener))
+        let (started_tx, started) = oneshot::channel();
+        let mut incoming = Incoming::from_listener(listener);
+        if let Some(max) = max_connections {
+            incoming.set_connection_limit(max, open.clone(), overloaded_response.into());
+        }
+
+        let mut server = Box::pin(hyper::Server::builder(incoming)
             .http1_keepalive(http1_keepalive)
             .http2_keep_alive_interval(http2_keep_alive)
             .serve(service)
-            .with_graceful_shutdown(async move { shutdown_receiver.recv().await; })
-            .await
-            .map_err(|e| Error::new(ErrorKind::Runtime(Box::new(e))))
+            .with_graceful_shutdown(async move {
+                shutdown_receiver.recv().await;
+                let _ = shutdown_started.send(());
+                let _ = started_tx.send(());
+            }));
+
+        let runtime_error = |e: hyper::Error| Error::new(ErrorKind::Runtime(Box::new(e)));
+
+        let result = async {
+            // Serve until a shutdown is triggered or the server fails.
+            if let Either::Left((result, _)) = future::select(&mut server, started).await {
+                return result.map_err(runtime_error);
+            }
+
+            // Let in-flight requests finish during the grace period.
+            let grace_timer = tokio::time::delay_for(grace);
+            if let Either::Left((result, _)) = future::select(&mut server, grace_timer).await {
+                info!("{}", Paint::green("Graceful shutdown completed."));
+                return result.map_err(runtime_error);
+            }
+
+            let remaining = open.load(Ordering::Acquire);
+            warn!("Shutdown grace period elapsed with {} connection(s) open.", remaining);
+            info_!("Cancelling in-flight requests.");
+            let _ = cancel_tx.send(());
+
+            let mercy_timer = tokio::time::delay_for(mercy);
+            let result = match future::select(&mut server, mercy_timer).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => {
+                    warn!("Shutdown mercy period elapsed. Forcibly closing connections.");
+                    let _ = force_tx.send(());
+                    server.await
+                }
+            };
+
+            result.map_err(runtime_error)?;
+            Err(Error::new(ErrorKind::Shutdown(remaining)))
+        }.await;
+
+        // Connections have drained: run the shutdown fairings.
+        rocket.fairings.handle_shutdown(&rocket).await;
+        result
     }
 }
@@ -1,13 +1,28 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use crate::request::{FromRequest, Outcome, Request};
+use crate::ext::Trigger;
 use tokio::sync::mpsc;
 
 /// A request guard to gracefully shutdown a Rocket server.
 ///
 /// A server shutdown is manually requested by calling [`Shutdown::shutdown()`]
-/// or, if enabled, by pressing `Ctrl-C`. Rocket will finish handling any
-/// pending requests and return `Ok()` to the caller of [`Rocket::launch()`].
+/// or, if enabled, by pressing `Ctrl-C` or sending one of the configured
+/// signals. Rocket will finish handling any pending requests and return `Ok()`
+/// to the caller of [`Rocket::launch()`]. Requests that don't finish within the
+/// configured grace period are cancelled; see [`ShutdownConfig`] for details.
 ///
 /// [`Rocket::launch()`]: crate::Rocket::launch()
+/// [`ShutdownConfig`]: crate::config::ShutdownConfig
+///
+/// `Shutdown` is also a future that resolves once a shutdown has been
+/// requested and the server has begun shutting down. Long-lived responses,
+/// such as streams, can await it to finish early instead of being cancelled
+/// when the grace period elapses. A `Shutdown` for a Rocket instance that never
+/// launches, such as one used by a local client, never resolves.
 ///
 /// # Example
 ///
@@ -33,13 +48,27 @@ use tokio::sync::mpsc;
 ///     result.expect("server failed unexpectedly");
 /// }
 /// ```
-#[derive(Debug, Clone)]
-pub struct Shutdown(pub(crate) mpsc::Sender<()>);
+///
+/// Awaiting a shutdown:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::Shutdown;
+///
+/// #[get("/wait")]
+/// async fn wait(shutdown: Shutdown) -> &'static str {
+///     shutdown.await;
+///     "The server is shutting down."
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Shutdown(pub(crate) mpsc::Sender<()>, pub(crate) Trigger);
 
 impl Shutdown {
     /// Notify Rocket to shut down gracefully. This function returns
-    /// immediately; pending requests will continue to run until completion
-    /// before the actual shutdown occurs.
+    /// immediately; pending requests will continue to run until completion, or
+    /// until the shutdown grace period elapses, before the actual shutdown
+    /// occurs.
     #[inline]
     pub fn shutdown(mut self) {
         // Intentionally ignore any error, as the only scenarios this can happen
@@ -49,6 +78,25 @@ impl Shutdown {
     }
 }
 
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        // The trigger is only dropped, never fired, if the server never
+        // launched. In that case, a shutdown never starts.
+        match Pin::new(&mut self.1).poll(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(()),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Shutdown").field(&self.0).finish()
+    }
+}
+
 #[crate::async_trait]
 impl<'a, 'r> FromRequest<'a, 'r> for Shutdown {
     type Error = std::convert::Infallible;
@@ -0,0 +1,138 @@
+//! TLS certificate reloading and connection information.
+//!
+//! This module is only available when the `tls` feature is enabled.
+
+use std::{io, fmt};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::request::{FromRequest, Outcome, Request};
+
+type Reload = Arc<dyn Fn() -> io::Result<()> + Send + Sync>;
+
+/// A handle to reload the server's TLS certificate and private key.
+///
+/// Obtained via [`Rocket::tls_reloader()`]. Calling [`Reloader::reload()`]
+/// re-reads every certificate chain and key in the [`TlsConfig`] of each TLS
+/// endpoint, on a thread where blocking is acceptable. Only new handshakes use
+/// the reloaded certificates; existing connections are left alone.
+///
+/// [`Rocket::tls_reloader()`]: crate::Rocket::tls_reloader()
+/// [`TlsConfig`]: crate::config::TlsConfig
+///
+/// # Example
+///
+/// Reload the certificate on request by managing the handle:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::State;
+/// use rocket::tls::Reloader;
+///
+/// #[post("/reload")]
+/// async fn reload(reloader: State<'_, Reloader>) -> Result<(), String> {
+///     reloader.reload().await.map_err(|e| e.to_string())
+/// }
+///
+/// #[launch]
+/// fn rocket() -> rocket::Rocket {
+///     let rocket = rocket::ignite().mount("/", routes![reload]);
+///     let reloader = rocket.tls_reloader();
+///     rocket.manage(reloader)
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Reloader(Arc<Mutex<Vec<Reload>>>);
+
+impl Reloader {
+    /// Re-reads the TLS certificate chains and private keys of every TLS
+    /// endpoint. Returns the first error, and keeps using the current
+    /// certificates of the endpoints that failed, if they can't be read or are
+    /// invalid. Also returns an error if the server isn't serving over TLS.
+    pub async fn reload(&self) -> io::Result<()> {
+        let reloads = self.0.lock().clone();
+        if reloads.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::Other, "TLS is not running"));
+        }
+
+        let mut result = Ok(());
+        for reload in reloads {
+            // Reading and parsing the files blocks.
+            let reloaded = tokio::task::spawn_blocking(move || reload()).await
+                .unwrap_or_else(|e| Err(io::Error::new(io::ErrorKind::Other, e)));
+
+            result = result.and(reloaded);
+        }
+
+        result
+    }
+
+    pub(crate) fn add<F>(&self, reload: F)
+        where F: Fn() -> io::Result<()> + Send + Sync + 'static
+    {
+        self.0.lock().push(Arc::new(reload));
+    }
+}
+
+impl fmt::Debug for Reloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reloader")
+            .field("endpoints", &self.0.lock().len())
+            .finish()
+    }
+}
+
+/// A request guard for the server name the client requested via TLS SNI.
+///
+/// The guard forwards if the connection isn't using TLS or if the client didn't
+/// request a server name. The name is as sent by the client; it need not match
+/// any configured [`SniCert`](crate::config::SniCert).
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::tls::ServerName;
+///
+/// #[get("/")]
+/// fn index(server_name: ServerName<'_>) -> String {
+///     format!("Hello, {}!", server_name)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServerName<'r>(&'r str);
+
+impl<'r> ServerName<'r> {
+    /// Returns the server name as a string slice.
+    pub fn as_str(&self) -> &'r str {
+        self.0
+    }
+}
+
+impl Deref for ServerName<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Display for ServerName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[crate::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for ServerName<'a> {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match &request.connection.server_name {
+            Some(name) => Outcome::Success(ServerName(name)),
+            None => Outcome::Forward(()),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+#[macro_use] extern crate rocket;
+
+use std::io;
+
+use rocket::Config;
+use rocket::futures::stream::{self, Stream};
+use rocket::local::asynchronous::Client;
+use rocket::response::ByteStream;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+
+#[get("/chunks")]
+fn chunks() -> ByteStream<impl Stream<Item = io::Result<Vec<u8>>>> {
+    let chunks = vec![Ok(b"Hello".to_vec()), Ok(vec![b','; 5000]), Ok(b"world!".to_vec())];
+    ByteStream::from(stream::iter(chunks))
+}
+
+#[rocket::async_test]
+async fn byte_stream_body() {
+    let client = Client::tracked(rocket::ignite().mount("/", routes![chunks])).await.unwrap();
+    let body = client.get("/chunks").dispatch().await.into_bytes().await.unwrap();
+    assert_eq!(body.len(), 5 + 5000 + 6);
+    assert!(body.starts_with(b"Hello,,"));
+    assert!(body.ends_with(b",,world!"));
+}
+
+#[rocket::async_test]
+async fn byte_stream_sends_one_chunk_per_item() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![chunks]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = "GET /chunks HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    let response = String::from_utf8(response).unwrap();
+
+    // Chunk sizes are in hex: 5000 bytes is `1388`.
+    let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+    let commas = ",".repeat(5000);
+    let expected = format!("5\r\nHello\r\n1388\r\n{}\r\n6\r\nworld!\r\n0\r\n\r\n", commas);
+    assert_eq!(body, expected);
+
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+}
@@ -0,0 +1,147 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Response};
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rocket::response::{Upgrade, Upgraded};
+use rocket::tokio::io::{copy, split, AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+
+struct Echo;
+
+#[rocket::async_trait]
+impl Upgrade for Echo {
+    async fn start(self: Box<Self>, io: Upgraded) {
+        let (mut reader, mut writer) = split(io);
+        let _ = copy(&mut reader, &mut writer).await;
+    }
+}
+
+#[get("/echo")]
+fn echo() -> Response<'static> {
+    Response::build().upgrade("echo", Echo).finalize()
+}
+
+#[post("/body", data = "<data>")]
+async fn body(data: Data) -> std::io::Result<String> {
+    data.open(1.kibibytes()).stream_to_string().await
+}
+
+#[rocket::async_test]
+async fn upgrade_over_local_client() {
+    let client = Client::tracked(rocket::ignite().mount("/", routes![echo])).await.unwrap();
+    let mut response = client.get("/echo").dispatch().await;
+    assert_eq!(response.status(), Status::SwitchingProtocols);
+
+    let mut io = response.upgrade().expect("response upgrades");
+    io.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    io.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+}
+
+#[rocket::async_test]
+async fn upgrade_over_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![echo]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = "GET /echo HTTP/1.1\r\nHost: localhost\r\n\
+        Connection: upgrade\r\nUpgrade: echo\r\n\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    // Read the response head, up to the blank line.
+    let mut head = vec![];
+    while !head.ends_with(b"\r\n\r\n") {
+        let mut byte = [0];
+        stream.read_exact(&mut byte).await.unwrap();
+        head.push(byte[0]);
+    }
+
+    let head = String::from_utf8(head).unwrap();
+    assert!(head.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+    assert!(head.to_lowercase().contains("upgrade: echo\r\n"));
+
+    stream.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    drop(stream);
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
+
+#[rocket::async_test]
+async fn upgrade_offer_with_body_keeps_body() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![body]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    // An opportunistic upgrade offer, as `curl` makes, and an `Upgrade` header
+    // without a matching `Connection` option: both must keep their bodies.
+    let requests = [
+        "POST /body HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade, HTTP2-Settings\r\n\
+            Upgrade: h2c\r\nHTTP2-Settings: AAMAAABkAARAAAAAAAIAAAAA\r\n\
+            Content-Length: 5\r\n\r\nhello",
+        "POST /body HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+            Upgrade: TLS/1.2\r\nContent-Length: 5\r\n\r\nhello",
+    ];
+
+    for request in &requests {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nhello"), "{}", response);
+    }
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
+
+#[rocket::async_test]
+async fn unperformed_upgrade_is_not_switching_protocols() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![echo]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    // A request that doesn't ask for an upgrade and one that has a body: the
+    // upgrade can't be performed, so the client mustn't be told it was.
+    let requests = [
+        "GET /echo HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        "GET /echo HTTP/1.1\r\nHost: localhost\r\nConnection: upgrade\r\n\
+            Upgrade: echo\r\nContent-Length: 5\r\n\r\nhello",
+    ];
+
+    for request in &requests {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"), "{}", response);
+        assert!(!response.to_lowercase().contains("upgrade: echo"), "{}", response);
+    }
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
@@ -0,0 +1,68 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::Config;
+use rocket::futures::stream::{self, Stream};
+use rocket::http::ContentType;
+use rocket::local::asynchronous::Client;
+use rocket::response::{Event, EventStream};
+use rocket::tokio::net::TcpListener;
+use rocket::tokio::time::{delay_for, timeout};
+
+#[get("/events")]
+fn events() -> EventStream<impl Stream<Item = Event>> {
+    EventStream::from(stream::iter(vec![
+        Event::data("hello"),
+        Event::data("one\ntwo").event("lines").id("2"),
+        Event::empty().retry(Duration::from_millis(1500)),
+    ]))
+}
+
+#[get("/slow")]
+fn slow() -> EventStream<impl Stream<Item = Event>> {
+    let event = stream::once(async {
+        delay_for(Duration::from_millis(250)).await;
+        Event::data("done")
+    });
+
+    EventStream::from(event).heartbeat(Duration::from_millis(50))
+}
+
+#[rocket::async_test]
+async fn events_are_framed() {
+    let client = Client::tracked(rocket::ignite().mount("/", routes![events])).await.unwrap();
+    let response = client.get("/events").dispatch().await;
+    assert_eq!(response.content_type(), Some(ContentType::new("text", "event-stream")));
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("no-cache"));
+
+    let expected = "data: hello\n\n\
+        event: lines\nid: 2\ndata: one\ndata: two\n\n\
+        retry: 1500\n\n";
+
+    assert_eq!(response.into_string().await.unwrap(), expected);
+}
+
+#[rocket::async_test]
+async fn heartbeats_are_sent_while_idle() {
+    let client = Client::tracked(rocket::ignite().mount("/", routes![slow])).await.unwrap();
+    let body = client.get("/slow").dispatch().await.into_string().await.unwrap();
+    assert!(body.starts_with(":\n\n"));
+    assert_eq!(body.trim_start_matches(":\n\n"), "data: done\n\n");
+}
+
+#[rocket::async_test]
+async fn shutdown_future_resolves_on_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config);
+    let shutdown = rocket.shutdown();
+
+    let waiter = rocket::tokio::spawn(shutdown.clone());
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+    delay_for(Duration::from_millis(100)).await;
+    shutdown.shutdown();
+
+    timeout(Duration::from_secs(5), waiter).await.expect("shutdown observed").unwrap();
+    assert!(server.await.unwrap().is_ok());
+}
@@ -0,0 +1,69 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::tokio::time::delay_for;
+
+#[get("/slow")]
+async fn slow() -> &'static str {
+    delay_for(Duration::from_secs(10)).await;
+    "Finally."
+}
+
+#[get("/slow/limited", timeout = "50ms")]
+async fn slow_limited() -> &'static str {
+    delay_for(Duration::from_secs(10)).await;
+    "Finally."
+}
+
+#[get("/quick", timeout = "5s")]
+async fn quick() -> &'static str {
+    delay_for(Duration::from_millis(100)).await;
+    "Quick."
+}
+
+mod handler_timeouts_tests {
+    use std::time::Duration;
+
+    use rocket::local::blocking::Client;
+    use rocket::http::Status;
+
+    fn rocket(handler_timeout: Option<&str>) -> rocket::Rocket {
+        let mut config = rocket::Config::figment();
+        if let Some(timeout) = handler_timeout {
+            config = config.merge(("timeouts.handler", timeout));
+        }
+
+        rocket::custom(config).mount("/", routes![super::slow, super::slow_limited, super::quick])
+    }
+
+    #[test]
+    fn route_timeout_is_visible() {
+        let routes = routes![super::slow, super::slow_limited, super::quick];
+        assert_eq!(routes[0].timeout, None);
+        assert_eq!(routes[1].timeout, Some(Duration::from_millis(50)));
+        assert_eq!(routes[2].timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn route_timeout_expires() {
+        let client = Client::tracked(rocket(None)).unwrap();
+        let response = client.get("/slow/limited").dispatch();
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+    }
+
+    #[test]
+    fn configured_timeout_expires() {
+        let client = Client::tracked(rocket(Some("50ms"))).unwrap();
+        let response = client.get("/slow").dispatch();
+        assert_eq!(response.status(), Status::ServiceUnavailable);
+    }
+
+    #[test]
+    fn route_timeout_overrides_configured_timeout() {
+        let client = Client::tracked(rocket(Some("10ms"))).unwrap();
+        let response = client.get("/quick").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string(), Some("Quick.".into()));
+    }
+}
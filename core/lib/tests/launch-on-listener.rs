@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU16, Ordering};
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::tokio::net::TcpListener;
+
+#[rocket::async_test]
+async fn launch_on_reports_listener_port() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bound_port = listener.local_addr().unwrap().port();
+
+    let launch_port = Arc::new(AtomicU16::new(0));
+    let port = launch_port.clone();
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let result = rocket::custom(config)
+        .attach(AdHoc::on_launch("Port Recorder", move |rocket| {
+            port.store(rocket.config().port, Ordering::SeqCst);
+            rocket.shutdown().shutdown();
+        }))
+        .launch_on(listener)
+        .await;
+
+    assert!(result.is_ok());
+    assert_eq!(launch_port.load(Ordering::SeqCst), bound_port);
+}
+
+#[rocket::async_test]
+async fn failing_launch_fairing_aborts_launch() {
+    use rocket::error::ErrorKind;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let result = rocket::custom(config)
+        .attach(AdHoc::try_on_launch("Slow Success", |_| Box::pin(async {
+            rocket::tokio::time::delay_for(std::time::Duration::from_millis(10)).await;
+            Ok(())
+        })))
+        .attach(AdHoc::try_on_launch("Failure", |_| Box::pin(async { Err(()) })))
+        .launch_on(listener)
+        .await;
+
+    match result.unwrap_err().kind() {
+        ErrorKind::FailedFairings(failures) => assert_eq!(failures, &["Failure"]),
+        e => panic!("expected a fairing failure, found: {}", e),
+    }
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn launch_on_ignores_configured_tls() {
+    use rocket::config::{Endpoint, TlsConfig};
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpStream;
+
+    #[rocket::get("/")]
+    fn index(endpoint: &Endpoint) -> String {
+        endpoint.to_string()
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let tls = TlsConfig::from_bytes(
+        include_bytes!("../../../examples/tls/private/cert.pem"),
+        include_bytes!("../../../examples/tls/private/key.pem"),
+    );
+
+    let config = Config { tls: Some(tls), ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", rocket::routes![index]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.ends_with(&format!("\r\n\r\nhttp://{}", addr)), "{}", response);
+
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+}
@@ -0,0 +1,117 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::{Config, Load};
+use rocket::config::LoadLimits;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+use rocket::tokio::time::delay_for;
+
+#[get("/sleep")]
+async fn sleep() -> &'static str {
+    delay_for(Duration::from_millis(500)).await;
+    "Slept."
+}
+
+#[get("/load")]
+fn load(load: Load) -> String {
+    format!("{} {}", load.connections(), load.requests())
+}
+
+#[catch(503)]
+fn overloaded() -> &'static str {
+    "Busy."
+}
+
+// Launches a server with `limits`, runs `client` against its address, then
+// shuts the server down.
+async fn with_server<F, Fut>(limits: LoadLimits, client: F)
+    where F: FnOnce(std::net::SocketAddr) -> Fut, Fut: std::future::Future<Output = ()>
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, load: limits, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![sleep, load])
+        .register(catchers![overloaded]);
+
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+    client(addr).await;
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+}
+
+// Sends a `GET` request for `path` on a new connection and returns the full
+// response.
+async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[rocket::async_test]
+async fn requests_beyond_limit_are_shed() {
+    let limits = LoadLimits { max_requests: Some(1), retry_after: 7, ..LoadLimits::default() };
+    with_server(limits, |addr| async move {
+        let first = rocket::tokio::spawn(async move { get(addr, "/sleep").await });
+        delay_for(Duration::from_millis(100)).await;
+
+        let second = get(addr, "/sleep").await;
+        assert!(second.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(second.to_lowercase().contains("retry-after: 7\r\n"));
+        assert!(second.ends_with("Busy."));
+
+        let first = first.await.unwrap();
+        assert!(first.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(first.ends_with("Slept."));
+    }).await;
+}
+
+#[rocket::async_test]
+async fn connections_beyond_limit_are_shed() {
+    let limits = LoadLimits { max_connections: Some(1), retry_after: 3, ..LoadLimits::default() };
+    with_server(limits, |addr| async move {
+        let idle = TcpStream::connect(addr).await.unwrap();
+        delay_for(Duration::from_millis(100)).await;
+
+        let response = get(addr, "/sleep").await;
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.contains("Retry-After: 3\r\n"));
+        assert!(response.ends_with("Busy."));
+
+        drop(idle);
+        delay_for(Duration::from_millis(100)).await;
+        let response = get(addr, "/load").await;
+        assert!(response.ends_with("1 1"));
+    }).await;
+}
+
+#[rocket::async_test]
+async fn http2_connections_beyond_limit_are_closed_without_response() {
+    let limits = LoadLimits { max_connections: Some(1), ..LoadLimits::default() };
+    with_server(limits, |addr| async move {
+        let idle = TcpStream::connect(addr).await.unwrap();
+        delay_for(Duration::from_millis(100)).await;
+
+        // An HTTP/2 prior-knowledge client must not be sent HTTP/1 bytes.
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").await.unwrap();
+        let mut response = vec![];
+        let _ = stream.read_to_end(&mut response).await;
+        assert!(response.is_empty(), "{}", String::from_utf8_lossy(&response));
+
+        // Nor must a client that sends nothing at all.
+        let mut silent = TcpStream::connect(addr).await.unwrap();
+        let mut response = vec![];
+        let _ = silent.read_to_end(&mut response).await;
+        assert!(response.is_empty(), "{}", String::from_utf8_lossy(&response));
+
+        drop(idle);
+    }).await;
+}
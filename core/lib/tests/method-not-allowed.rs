@@ -0,0 +1,64 @@
+#[macro_use] extern crate rocket;
+
+use rocket::Config;
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+
+#[get("/item/<id>")]
+fn get_item(id: usize) -> String {
+    id.to_string()
+}
+
+#[delete("/item/<_id>")]
+fn delete_item(_id: usize) { }
+
+#[post("/items")]
+fn create_item() -> &'static str {
+    "created"
+}
+
+fn client(method_not_allowed: bool) -> Client {
+    let config = Config { method_not_allowed, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![get_item, delete_item, create_item]);
+    Client::tracked(rocket).unwrap()
+}
+
+#[test]
+fn other_methods_respond_405() {
+    let client = client(true);
+
+    let response = client.put("/item/1").dispatch();
+    assert_eq!(response.status(), Status::MethodNotAllowed);
+    assert_eq!(response.headers().get_one("Allow"), Some("DELETE, GET, HEAD, OPTIONS"));
+
+    let response = client.get("/items").dispatch();
+    assert_eq!(response.status(), Status::MethodNotAllowed);
+    assert_eq!(response.headers().get_one("Allow"), Some("OPTIONS, POST"));
+
+    let response = client.head("/items").dispatch();
+    assert_eq!(response.status(), Status::MethodNotAllowed);
+    assert_eq!(response.headers().get_one("Allow"), Some("OPTIONS, POST"));
+}
+
+#[test]
+fn forwards_and_unknown_paths_respond_404() {
+    let client = client(true);
+
+    // A route for the method matches the path but forwards.
+    let response = client.get("/item/abc").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("Allow").is_none());
+
+    let response = client.put("/unknown").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("Allow").is_none());
+}
+
+#[test]
+fn disabled_responds_404() {
+    let client = client(false);
+
+    let response = client.put("/item/1").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("Allow").is_none());
+}
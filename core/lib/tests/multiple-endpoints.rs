@@ -0,0 +1,107 @@
+#[macro_use] extern crate rocket;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use rocket::{Config, Request};
+use rocket::config::Endpoint;
+use rocket::fairing::AdHoc;
+use rocket::futures::channel::oneshot;
+use rocket::request::{FromRequest, Outcome};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+
+struct Admin;
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = ();
+
+    async fn from_request(req: &'a Request<'r>) -> Outcome<Self, ()> {
+        match req.endpoint().and_then(|e| e.name.as_deref()) {
+            Some("admin") => Outcome::Success(Admin),
+            _ => Outcome::Forward(()),
+        }
+    }
+}
+
+#[get("/")]
+fn index(endpoint: &Endpoint) -> String {
+    endpoint.to_string()
+}
+
+#[get("/stats")]
+fn stats(_admin: Admin) -> &'static str {
+    "stats"
+}
+
+async fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[rocket::async_test]
+async fn serves_on_every_endpoint() {
+    let endpoints = vec![
+        Endpoint::new(Ipv4Addr::LOCALHOST, 0),
+        Endpoint::new(Ipv4Addr::LOCALHOST, 0).named("admin"),
+    ];
+
+    let (tx, rx) = oneshot::channel();
+    let config = Config { endpoints, ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![index, stats])
+        .attach(AdHoc::on_launch("Endpoint Recorder", move |rocket| {
+            let _ = tx.send(rocket.config().endpoints.clone());
+        }));
+
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch());
+
+    // The launch fairings observe the ports that were actually bound.
+    let bound = rx.await.unwrap();
+    assert_eq!(bound.len(), 2);
+    assert!(bound.iter().all(|e| e.port != 0));
+    assert_ne!(bound[0].port, bound[1].port);
+
+    let (public, admin) = (bound[0].socket_addr(), bound[1].socket_addr());
+    let response = get(public, "/").await;
+    assert!(response.ends_with(&format!("\r\n\r\nhttp://{}", public)), "{}", response);
+
+    let response = get(admin, "/").await;
+    assert!(response.ends_with(&format!("\r\n\r\nhttp://{} (admin)", admin)), "{}", response);
+
+    let response = get(public, "/stats").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+
+    let response = get(admin, "/stats").await;
+    assert!(response.ends_with("\r\n\r\nstats"), "{}", response);
+
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+}
+
+#[rocket::async_test]
+async fn default_endpoint_is_address_and_port() {
+    let (tx, rx) = oneshot::channel();
+    let config = Config { port: 0, ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![index])
+        .attach(AdHoc::on_launch("Port Recorder", move |rocket| {
+            let _ = tx.send(rocket.config().port);
+        }));
+
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch());
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, rx.await.unwrap()));
+    let response = get(addr, "/").await;
+    assert!(response.ends_with(&format!("\r\n\r\nhttp://{}", addr)), "{}", response);
+
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "mtls")]
+mod mutual_tls {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+
+    use rocket::{Config, Shutdown, get, routes};
+    use rocket::config::{TlsConfig, MutualTls};
+    use rocket::error::Error;
+    use rocket::fairing::AdHoc;
+    use rocket::futures::channel::oneshot;
+    use rocket::mtls::ClientCertificate;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpStream;
+    use rocket::tokio::task::JoinHandle;
+
+    use tokio_rustls::TlsConnector;
+    use tokio_rustls::rustls::{ClientConfig, internal::pemfile};
+    use tokio_rustls::webpki::DNSNameRef;
+
+    macro_rules! private {
+        ($file:literal) => (
+            &include_bytes!(concat!("../../../examples/tls/private/", $file))[..]
+        )
+    }
+
+    #[get("/")]
+    fn whoami(cert: ClientCertificate) -> String {
+        let names = cert.subject_alt_names().join(",");
+        format!("{}\n{}\n{}\n{}", cert.subject(), cert.issuer(), cert.serial(), names)
+    }
+
+    #[get("/", rank = 2)]
+    fn anonymous() -> &'static str {
+        "anonymous"
+    }
+
+    // Launches a TLS server that verifies client certificates against the
+    // example CA, returning its address.
+    async fn launch(mandatory: bool) -> (SocketAddr, Shutdown, JoinHandle<Result<(), Error>>) {
+        let mutual = MutualTls::from_bytes(private!("ca_cert.pem")).with_mandatory(mandatory);
+        let tls = TlsConfig::from_bytes(private!("cert.pem"), private!("key.pem"))
+            .with_mutual(mutual);
+
+        let (tx, rx) = oneshot::channel();
+        let config = Config { port: 0, tls: Some(tls), ctrlc: false, ..Config::debug_default() };
+        let rocket = rocket::custom(config)
+            .mount("/", routes![whoami, anonymous])
+            .attach(AdHoc::on_launch("Port Recorder", move |rocket| {
+                let _ = tx.send(rocket.config().port);
+            }));
+
+        let shutdown = rocket.shutdown();
+        let server = rocket::tokio::spawn(rocket.launch());
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, rx.await.unwrap()));
+        (addr, shutdown, server)
+    }
+
+    // Requests `/` over TLS, presenting `client` as the client certificate and
+    // key, if any. Returns everything the server sent back, which is empty if
+    // the handshake is rejected.
+    async fn get(addr: SocketAddr, client: Option<(&[u8], &[u8])>) -> String {
+        let mut config = ClientConfig::new();
+        config.root_store.add_pem_file(&mut private!("ca_cert.pem")).unwrap();
+        if let Some((mut cert, mut key)) = client {
+            let certs = pemfile::certs(&mut cert).unwrap();
+            let key = pemfile::rsa_private_keys(&mut key).unwrap().remove(0);
+            config.set_single_client_cert(certs, key).unwrap();
+        }
+
+        let connector = TlsConnector::from(Arc::new(config));
+        let domain = DNSNameRef::try_from_ascii_str("localhost").unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut stream = match connector.connect(domain, stream).await {
+            Ok(stream) => stream,
+            Err(_) => return String::new(),
+        };
+
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        let mut response = vec![];
+        if stream.write_all(request.as_bytes()).await.is_ok() {
+            let _ = stream.read_to_end(&mut response).await;
+        }
+
+        String::from_utf8(response).unwrap()
+    }
+
+    fn trusted() -> Option<(&'static [u8], &'static [u8])> {
+        Some((private!("client_cert.pem"), private!("client_key.pem")))
+    }
+
+    fn untrusted() -> Option<(&'static [u8], &'static [u8])> {
+        Some((private!("untrusted_client_cert.pem"), private!("untrusted_client_key.pem")))
+    }
+
+    #[rocket::async_test]
+    async fn valid_certificate_is_parsed() {
+        let (addr, shutdown, server) = launch(false).await;
+
+        let response = get(addr, trusted()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+
+        let body = response.splitn(2, "\r\n\r\n").nth(1).unwrap();
+        let lines: Vec<_> = body.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", body);
+        assert!(lines[0].contains("CN=client"), "{}", lines[0]);
+        assert!(lines[0].contains("O=Rocket"), "{}", lines[0]);
+        assert!(lines[1].contains("CN=Rocket Root CA"), "{}", lines[1]);
+        assert!(!lines[2].is_empty());
+        assert!(lines[2].chars().all(|c| c.is_ascii_hexdigit() && !c.is_uppercase()));
+        assert_eq!(lines[3], "client@rocket.rs");
+
+        shutdown.shutdown();
+        assert!(server.await.unwrap().is_ok());
+    }
+
+    #[rocket::async_test]
+    async fn missing_certificate_forwards() {
+        let (addr, shutdown, server) = launch(false).await;
+
+        let response = get(addr, None).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nanonymous"), "{}", response);
+
+        shutdown.shutdown();
+        assert!(server.await.unwrap().is_ok());
+    }
+
+    #[rocket::async_test]
+    async fn missing_certificate_is_rejected_when_mandatory() {
+        let (addr, shutdown, server) = launch(true).await;
+
+        let response = get(addr, None).await;
+        assert!(response.is_empty(), "{}", response);
+
+        let response = get(addr, trusted()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.contains("CN=client"), "{}", response);
+
+        shutdown.shutdown();
+        assert!(server.await.unwrap().is_ok());
+    }
+
+    #[rocket::async_test]
+    async fn untrusted_certificate_is_rejected() {
+        for &mandatory in &[false, true] {
+            let (addr, shutdown, server) = launch(mandatory).await;
+
+            let response = get(addr, untrusted()).await;
+            assert!(response.is_empty(), "{}", response);
+
+            shutdown.shutdown();
+            assert!(server.await.unwrap().is_ok());
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[post("/")]
+fn create() -> &'static str {
+    "created"
+}
+
+#[put("/item/<_id>")]
+fn put_item(_id: usize) { }
+
+#[options("/custom")]
+fn custom() -> &'static str {
+    "custom options"
+}
+
+#[get("/custom")]
+fn get_custom() -> &'static str {
+    "custom"
+}
+
+mod options_handling_tests {
+    use super::*;
+
+    use rocket::Route;
+    use rocket::local::blocking::Client;
+
+    fn routes() -> Vec<Route> {
+        routes![index, create, put_item, custom, get_custom]
+    }
+
+    #[test]
+    fn auto_options() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes())).unwrap();
+
+        let response = client.options("/").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(response.headers().get_one("Allow"), Some("GET, HEAD, OPTIONS, POST"));
+        assert!(response.into_bytes().is_none());
+
+        // The allowed methods don't depend on whether parameters parse.
+        let response = client.options("/item/abc").dispatch();
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(response.headers().get_one("Allow"), Some("OPTIONS, PUT"));
+    }
+
+    #[test]
+    fn user_options() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes())).unwrap();
+        let response = client.options("/custom").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.headers().get_one("Allow").is_none());
+        assert_eq!(response.into_string().unwrap(), "custom options");
+    }
+
+    #[test]
+    fn unknown_path_options() {
+        let client = Client::tracked(rocket::ignite().mount("/", routes())).unwrap();
+        let response = client.options("/unknown").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+        assert!(response.headers().get_one("Allow").is_none());
+    }
+}
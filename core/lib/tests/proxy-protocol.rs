@@ -0,0 +1,93 @@
+#[macro_use] extern crate rocket;
+
+use std::net::SocketAddr;
+
+use rocket::Config;
+use rocket::http::private::ProxyListener;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+
+#[get("/")]
+fn remote(remote: SocketAddr) -> String {
+    remote.to_string()
+}
+
+// Launches a server that decodes PROXY protocol headers, sends `data` on a new
+// connection, and returns everything the server sent back.
+async fn exchange(required: bool, data: &[u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![remote]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(ProxyListener::new(listener, required)));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(data).await.unwrap();
+    let mut response = vec![];
+    let _ = stream.read_to_end(&mut response).await;
+
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+    String::from_utf8(response).unwrap()
+}
+
+const REQUEST: &str = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+
+#[rocket::async_test]
+async fn v1_header_sets_remote() {
+    let data = format!("PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\n{}", REQUEST);
+    let response = exchange(true, data.as_bytes()).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.ends_with("\r\n\r\n192.0.2.1:56324"), "{}", response);
+}
+
+#[rocket::async_test]
+async fn v2_header_sets_remote() {
+    let mut data = b"\r\n\r\n\0\r\nQUIT\n\x21\x21\x00\x24".to_vec();
+    data.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&[0xdc, 0x04, 0x01, 0xbb]);
+    data.extend_from_slice(REQUEST.as_bytes());
+
+    let response = exchange(true, &data).await;
+    assert!(response.ends_with("\r\n\r\n[2001:db8::1]:56324"), "{}", response);
+}
+
+#[rocket::async_test]
+async fn missing_header() {
+    let response = exchange(false, REQUEST.as_bytes()).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("\r\n\r\n127.0.0.1:"), "{}", response);
+
+    let response = exchange(true, REQUEST.as_bytes()).await;
+    assert!(response.is_empty(), "{}", response);
+}
+
+#[rocket::async_test]
+async fn idle_client_does_not_block_others() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![remote]);
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(ProxyListener::new(listener, true)));
+
+    // The first client connects but never sends its header.
+    let idle = TcpStream::connect(addr).await.unwrap();
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let data = format!("PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\n{}", REQUEST);
+    stream.write_all(data.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    let read = stream.read_to_string(&mut response);
+    let timeout = std::time::Duration::from_secs(2);
+    rocket::tokio::time::timeout(timeout, read).await.unwrap().unwrap();
+    assert!(response.ends_with("\r\n\r\n192.0.2.1:56324"), "{}", response);
+
+    drop(idle);
+    shutdown.shutdown();
+    assert!(server.await.unwrap().is_ok());
+}
@@ -0,0 +1,93 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::Config;
+use rocket::config::Timeouts;
+use rocket::data::{Data, ToByteUnit};
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+
+#[post("/", data = "<data>")]
+async fn echo(data: Data) -> std::io::Result<String> {
+    data.open(1.kibibytes()).stream_to_string().await
+}
+
+#[catch(408)]
+fn request_timeout() -> &'static str {
+    "Too slow."
+}
+
+// Writes `request` to a freshly launched server, then returns everything the
+// server writes back before closing the connection.
+async fn respond_to<R: AsRef<[u8]>>(request: R) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let config = Config {
+        ctrlc: false,
+        timeouts: Timeouts {
+            headers: Some(Duration::from_millis(250)),
+            body_idle: Some(Duration::from_millis(250)),
+            ..Timeouts::default()
+        },
+        ..Config::debug_default()
+    };
+
+    let rocket = rocket::custom(config)
+        .mount("/", routes![echo])
+        .register(catchers![request_timeout]);
+
+    let shutdown = rocket.shutdown();
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_ref()).await.unwrap();
+    let mut response = vec![];
+    let _ = stream.read_to_end(&mut response).await;
+
+    shutdown.shutdown();
+    server.await.unwrap().unwrap();
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+#[rocket::async_test]
+async fn slow_headers_time_out() {
+    let response = respond_to("POST / HTTP/1.1\r\nHost: localhost\r\n").await;
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+    assert!(response.ends_with("Too slow."));
+}
+
+#[rocket::async_test]
+async fn idle_body_times_out() {
+    let request = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\nhi";
+    let response = respond_to(request).await;
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+    assert!(response.contains("Too slow."));
+}
+
+#[rocket::async_test]
+async fn idle_connection_closes_silently() {
+    let response = respond_to("").await;
+    assert!(response.is_empty(), "{}", response);
+}
+
+#[rocket::async_test]
+async fn idle_keep_alive_closes_silently() {
+    let request = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\nhi";
+    let response = respond_to(request).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with("\r\n\r\nhi"), "{}", response);
+    assert!(!response.contains("408"), "{}", response);
+}
+
+#[rocket::async_test]
+async fn idle_http2_closes_without_http1_response() {
+    // The HTTP/2 connection preface followed by an empty SETTINGS frame.
+    let mut request = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n".to_vec();
+    request.extend_from_slice(&[0, 0, 0, 0x4, 0, 0, 0, 0, 0]);
+
+    let response = respond_to(request).await;
+    assert!(!response.contains("HTTP/1.1"), "{}", response);
+    assert!(!response.contains("Too slow."), "{}", response);
+}
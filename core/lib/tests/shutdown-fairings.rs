@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocket::Config;
+use rocket::fairing::AdHoc;
+use rocket::tokio::net::TcpListener;
+
+#[rocket::async_test]
+async fn on_shutdown_runs_after_shutdown() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let shut_down = Arc::new(AtomicBool::new(false));
+    let flag = shut_down.clone();
+    let config = Config { ctrlc: false, ..Config::debug_default() };
+    let result = rocket::custom(config)
+        .attach(AdHoc::on_launch("Shutdown Now", |rocket| {
+            rocket.shutdown().shutdown();
+        }))
+        .attach(AdHoc::on_shutdown("Shutdown Recorder", move |rocket| {
+            Box::pin(async move {
+                assert!(!rocket.config().ctrlc);
+                flag.store(true, Ordering::SeqCst);
+            })
+        }))
+        .launch_on(listener)
+        .await;
+
+    assert!(result.is_ok());
+    assert!(shut_down.load(Ordering::SeqCst));
+}
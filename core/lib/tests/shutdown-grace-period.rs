@@ -0,0 +1,68 @@
+#[macro_use] extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::Config;
+use rocket::config::ShutdownConfig;
+use rocket::error::ErrorKind;
+use rocket::tokio::io::AsyncWriteExt;
+use rocket::tokio::net::{TcpListener, TcpStream};
+use rocket::tokio::time::delay_for;
+
+#[get("/sleep/<ms>")]
+async fn sleep(ms: u64) -> &'static str {
+    delay_for(Duration::from_millis(ms)).await;
+    "Slept."
+}
+
+fn config(grace: u32, mercy: u32) -> Config {
+    Config {
+        ctrlc: false,
+        shutdown: ShutdownConfig { signals: Default::default(), grace, mercy },
+        ..Config::debug_default()
+    }
+}
+
+// Sends a request for `/sleep/<ms>`, then requests a shutdown while the
+// request is still in-flight, and returns the launch result.
+async fn shutdown_during_sleep(config: Config, ms: u64) -> Result<(), rocket::error::Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let rocket = rocket::custom(config).mount("/", routes![sleep]);
+    let shutdown = rocket.shutdown();
+    let client = rocket::tokio::spawn(async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET /sleep/{} HTTP/1.1\r\nHost: localhost\r\n\r\n", ms);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        delay_for(Duration::from_millis(250)).await;
+        shutdown.shutdown();
+        stream
+    });
+
+    let result = rocket.launch_on(listener).await;
+    drop(client.await);
+    result
+}
+
+#[rocket::async_test]
+async fn in_flight_request_finishes_within_grace() {
+    let result = shutdown_during_sleep(config(5, 0), 500).await;
+    assert!(result.is_ok());
+}
+
+#[rocket::async_test]
+async fn in_flight_request_outlasts_grace() {
+    let result = shutdown_during_sleep(config(0, 0), 60_000).await;
+    match result.unwrap_err().kind() {
+        ErrorKind::Shutdown(open) => assert_eq!(*open, 1),
+        e => panic!("expected a shutdown error, found: {}", e),
+    }
+}
+
+#[rocket::async_test]
+async fn uninspected_shutdown_error_does_not_panic() {
+    let result = shutdown_during_sleep(config(0, 0), 60_000).await;
+    assert!(result.is_err());
+    drop(result);
+}
@@ -0,0 +1,112 @@
+#[cfg(feature = "tls")]
+mod tls_reload {
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::sync::Arc;
+
+    use rocket::{Config, get, routes};
+    use rocket::config::TlsConfig;
+    use rocket::fairing::AdHoc;
+    use rocket::futures::channel::oneshot;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpStream;
+
+    use tokio_rustls::{TlsConnector, client::TlsStream};
+    use tokio_rustls::rustls::{Certificate, ClientConfig, Session, internal::pemfile};
+    use tokio_rustls::webpki::DNSNameRef;
+
+    macro_rules! private {
+        ($file:literal) => (
+            &include_bytes!(concat!("../../../examples/tls/private/", $file))[..]
+        )
+    }
+
+    #[get("/")]
+    fn index() -> &'static str {
+        "ok"
+    }
+
+    fn certificate(mut pem: &[u8]) -> Certificate {
+        pemfile::certs(&mut pem).unwrap().remove(0)
+    }
+
+    async fn connect(addr: SocketAddr) -> TlsStream<TcpStream> {
+        let mut config = ClientConfig::new();
+        config.root_store.add_pem_file(&mut private!("ca_cert.pem")).unwrap();
+
+        let connector = TlsConnector::from(Arc::new(config));
+        let domain = DNSNameRef::try_from_ascii_str("localhost").unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        connector.connect(domain, stream).await.unwrap()
+    }
+
+    fn peer_certificate(stream: &TlsStream<TcpStream>) -> Certificate {
+        stream.get_ref().1.get_peer_certificates().unwrap().remove(0)
+    }
+
+    // Requests `/` on the kept-alive `stream`, returning the response.
+    async fn get(stream: &mut TlsStream<TcpStream>) -> String {
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = vec![];
+        while !response.ends_with(b"\r\n\r\nok") {
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(n > 0, "connection closed: {}", String::from_utf8_lossy(&response));
+            response.extend_from_slice(&buf[..n]);
+        }
+
+        String::from_utf8(response).unwrap()
+    }
+
+    #[rocket::async_test]
+    async fn reload_only_affects_new_handshakes() {
+        let dir = std::env::temp_dir();
+        let id = std::process::id();
+        let cert_path = dir.join(format!("rocket-reload-cert-{}.pem", id));
+        let key_path = dir.join(format!("rocket-reload-key-{}.pem", id));
+        std::fs::write(&cert_path, private!("cert.pem")).unwrap();
+        std::fs::write(&key_path, private!("key.pem")).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        let tls = TlsConfig::from_paths(&cert_path, &key_path);
+        let config = Config { port: 0, tls: Some(tls), ctrlc: false, ..Config::debug_default() };
+        let rocket = rocket::custom(config)
+            .mount("/", routes![index])
+            .attach(AdHoc::on_launch("Port Recorder", move |rocket| {
+                let _ = tx.send(rocket.config().port);
+            }));
+
+        let (reloader, shutdown) = (rocket.tls_reloader(), rocket.shutdown());
+        let server = rocket::tokio::spawn(rocket.launch());
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, rx.await.unwrap()));
+
+        let mut old = connect(addr).await;
+        assert_eq!(peer_certificate(&old), certificate(private!("cert.pem")));
+        assert!(get(&mut old).await.starts_with("HTTP/1.1 200 OK"));
+
+        std::fs::write(&cert_path, private!("ecdsa_cert.pem")).unwrap();
+        std::fs::write(&key_path, private!("ecdsa_key.pem")).unwrap();
+        reloader.reload().await.unwrap();
+
+        let mut new = connect(addr).await;
+        assert_eq!(peer_certificate(&new), certificate(private!("ecdsa_cert.pem")));
+        assert!(get(&mut new).await.starts_with("HTTP/1.1 200 OK"));
+
+        // The connection established before the reload is still usable.
+        assert!(get(&mut old).await.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(peer_certificate(&old), certificate(private!("cert.pem")));
+
+        // A failed reload keeps the current certificate.
+        std::fs::write(&key_path, b"not a key").unwrap();
+        assert!(reloader.reload().await.is_err());
+        new = connect(addr).await;
+        assert_eq!(peer_certificate(&new), certificate(private!("ecdsa_cert.pem")));
+
+        drop((old, new));
+        shutdown.shutdown();
+        assert!(server.await.unwrap().is_ok());
+        let _ = std::fs::remove_file(cert_path);
+        let _ = std::fs::remove_file(key_path);
+    }
+}
@@ -0,0 +1,110 @@
+#[macro_use] extern crate rocket;
+
+use std::net::{IpAddr, SocketAddr};
+
+use rocket::{Config, Request};
+use rocket::http::Header;
+use rocket::response::Redirect;
+use rocket::local::blocking::Client;
+
+#[get("/ip")]
+fn ip(ip: Option<IpAddr>) -> String {
+    ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".into())
+}
+
+#[get("/link")]
+fn link(req: &Request<'_>) -> String {
+    req.absolute_uri(&uri!(ip)).map(|uri| uri.to_string()).unwrap_or_default()
+}
+
+#[get("/redirect")]
+fn redirect() -> Redirect {
+    Redirect::to(uri!(ip))
+}
+
+fn client(trusted_proxies: &[&str]) -> Client {
+    let trusted_proxies = trusted_proxies.iter().map(|r| r.parse().unwrap()).collect();
+    let config = Config { trusted_proxies, ..Config::debug_default() };
+    let rocket = rocket::custom(config).mount("/", routes![ip, link, redirect]);
+    Client::tracked(rocket).unwrap()
+}
+
+type Headers<'a> = &'a [(&'static str, &'static str)];
+
+fn get(client: &Client, uri: &str, remote: &str, headers: Headers<'_>) -> String {
+    let mut request = client.get(uri).remote(remote.parse::<SocketAddr>().unwrap());
+    for &(name, value) in headers {
+        request = request.header(Header::new(name, value));
+    }
+
+    let response = request.dispatch();
+    match response.headers().get_one("Location") {
+        Some(location) => location.to_string(),
+        None => response.into_string().unwrap(),
+    }
+}
+
+#[test]
+fn no_trusted_proxies_uses_real_ip() {
+    let client = client(&[]);
+    let headers = [("X-Real-IP", "192.0.2.1"), ("X-Forwarded-For", "192.0.2.2")];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "192.0.2.1");
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers[1..]), "10.0.0.1");
+}
+
+#[test]
+fn untrusted_remote_is_client() {
+    let client = client(&["10.0.0.0/8"]);
+    let headers = [("X-Real-IP", "192.0.2.1"), ("X-Forwarded-For", "192.0.2.2")];
+    assert_eq!(get(&client, "/ip", "203.0.113.9:80", &headers), "203.0.113.9");
+}
+
+#[test]
+fn forwarded_chain_skips_trusted_hops() {
+    let client = client(&["10.0.0.0/8", "2001:db8::/32"]);
+
+    // A forged hop to the left of an untrusted one is ignored.
+    let headers = [("X-Forwarded-For", "198.51.100.1, 192.0.2.7, 10.0.0.2")];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "192.0.2.7");
+
+    let headers = [("Forwarded", r#"for=192.0.2.7, for="[2001:db8::5]:443""#)];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "192.0.2.7");
+
+    // When every hop is trusted, the leftmost one is the client.
+    let headers = [("X-Forwarded-For", "10.1.1.1, 10.0.0.2")];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "10.1.1.1");
+
+    let headers = [("Forwarded", "for=unknown")];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "unknown");
+
+    // Without forwarding headers, `X-Real-IP` and the remote are used.
+    let headers = [("X-Real-IP", "192.0.2.1")];
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "192.0.2.1");
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &[]), "10.0.0.1");
+}
+
+#[test]
+fn forwarded_scheme_and_host_in_links() {
+    let client = client(&["10.0.0.0/8"]);
+    let host = ("Host", "internal:8000");
+
+    assert_eq!(get(&client, "/link", "10.0.0.1:80", &[host]), "http://internal:8000/ip");
+    assert_eq!(get(&client, "/redirect", "10.0.0.1:80", &[host]), "/ip");
+
+    let headers = [host, ("Forwarded", "for=192.0.2.7;proto=https;host=rocket.rs")];
+    assert_eq!(get(&client, "/link", "10.0.0.1:80", &headers), "https://rocket.rs/ip");
+    assert_eq!(get(&client, "/redirect", "10.0.0.1:80", &headers), "https://rocket.rs/ip");
+
+    let headers = [host, ("X-Forwarded-For", "192.0.2.7"), ("X-Forwarded-Proto", "https")];
+    assert_eq!(get(&client, "/redirect", "10.0.0.1:80", &headers), "https://internal:8000/ip");
+
+    // Forwarding headers from an untrusted remote are ignored.
+    assert_eq!(get(&client, "/redirect", "203.0.113.9:80", &headers), "/ip");
+
+    // A proxy may report the scheme and host without `X-Forwarded-For`. The
+    // client's address is then the proxy's.
+    let headers = [host, ("X-Forwarded-Proto", "https"), ("X-Forwarded-Host", "rocket.rs")];
+    assert_eq!(get(&client, "/link", "10.0.0.1:80", &headers), "https://rocket.rs/ip");
+    assert_eq!(get(&client, "/redirect", "10.0.0.1:80", &headers), "https://rocket.rs/ip");
+    assert_eq!(get(&client, "/ip", "10.0.0.1:80", &headers), "10.0.0.1");
+}
@@ -0,0 +1,120 @@
+#[cfg(unix)]
+mod unix_socket {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+
+    use rocket::{Config, Request, get, routes};
+    use rocket::config::UnixConfig;
+    use rocket::fairing::AdHoc;
+    use rocket::futures::channel::oneshot;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::UnixStream;
+
+    #[get("/")]
+    fn remote(request: &Request<'_>) -> String {
+        format!("{:?} {:?}", request.remote(), request.client_ip())
+    }
+
+    fn socket_path(name: &str) -> PathBuf {
+        let file = format!("rocket-{}-{}.sock", name, std::process::id());
+        std::env::temp_dir().join(file)
+    }
+
+    fn config(path: &Path) -> Config {
+        let unix = UnixConfig::from_path(path).with_permissions(0o600);
+        Config { unix: Some(unix), ctrlc: false, ..Config::debug_default() }
+    }
+
+    #[rocket::async_test]
+    async fn serves_on_unix_socket() {
+        let path = socket_path("serve");
+        let _ = std::fs::remove_file(&path);
+
+        // Leave a stale socket file behind: it exists but isn't accepting.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(std::fs::metadata(&path).unwrap().file_type().is_socket());
+
+        let (tx, rx) = oneshot::channel();
+        let rocket = rocket::custom(config(&path))
+            .mount("/", routes![remote])
+            .attach(AdHoc::on_launch("Launch Signal", move |_| {
+                let _ = tx.send(());
+            }));
+
+        let shutdown = rocket.shutdown();
+        let server = rocket::tokio::spawn(rocket.launch());
+        rx.await.unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("\r\n\r\nNone None"), "{}", response);
+
+        shutdown.shutdown();
+        assert!(server.await.unwrap().is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[rocket::async_test]
+    async fn live_socket_is_not_replaced() {
+        use rocket::error::ErrorKind;
+
+        let path = socket_path("live");
+        let _ = std::fs::remove_file(&path);
+        let _live = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let result = rocket::custom(config(&path)).launch().await;
+        match result.unwrap_err().kind() {
+            ErrorKind::Bind(e) => assert_eq!(e.kind(), std::io::ErrorKind::AddrInUse),
+            e => panic!("expected a bind error, found: {}", e),
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "tls")]
+    #[rocket::async_test]
+    async fn tls_on_unix_socket_fails_launch() {
+        use rocket::config::TlsConfig;
+        use rocket::error::ErrorKind;
+
+        let path = socket_path("tls");
+        let _ = std::fs::remove_file(&path);
+
+        let tls = TlsConfig::from_bytes(
+            include_bytes!("../../../examples/tls/private/cert.pem"),
+            include_bytes!("../../../examples/tls/private/key.pem"),
+        );
+
+        let config = Config { tls: Some(tls), ..config(&path) };
+        match rocket::custom(config).launch().await.unwrap_err().kind() {
+            ErrorKind::Bind(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            e => panic!("expected a bind error, found: {}", e),
+        }
+
+        assert!(!path.exists());
+    }
+
+    #[rocket::async_test]
+    async fn required_proxy_protocol_on_unix_socket_fails_launch() {
+        use rocket::config::ProxyProtocol;
+        use rocket::error::ErrorKind;
+
+        let path = socket_path("proxy");
+        let _ = std::fs::remove_file(&path);
+
+        let config = Config { proxy_protocol: Some(ProxyProtocol::Required), ..config(&path) };
+        match rocket::custom(config).launch().await.unwrap_err().kind() {
+            ErrorKind::Bind(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            e => panic!("expected a bind error, found: {}", e),
+        }
+
+        assert!(!path.exists());
+    }
+}
@@ -26,17 +26,19 @@ fn test_root() {
         });
     }
 
-    // Check that other request methods are not accepted (and instead caught).
-    for method in &[Post, Put, Delete, Options, Trace, Connect, Patch] {
-        dispatch!(*method, "/", |client, response| {
-            let mut map = std::collections::HashMap::new();
-            map.insert("path", "/");
-            let expected = Template::show(client.rocket(), "error/404", &map).unwrap();
-
-            assert_eq!(response.status(), Status::NotFound);
-            assert_eq!(response.into_string(), Some(expected));
+    // Check that other request methods are not allowed.
+    for method in &[Post, Put, Delete, Trace, Connect, Patch] {
+        dispatch!(*method, "/", |_client, response| {
+            assert_eq!(response.status(), Status::MethodNotAllowed);
+            assert_eq!(response.headers().get_one("Allow"), Some("GET, HEAD, OPTIONS"));
         });
     }
+
+    // Check that `OPTIONS` requests are answered with the allowed methods.
+    dispatch!(Options, "/", |_client, response| {
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(response.headers().get_one("Allow"), Some("GET, HEAD, OPTIONS"));
+    });
 }
 
 #[test]
@@ -5,17 +5,75 @@
 # clear if this is intended, since certificates _should_ have a `subjectAltName`
 # with a DNS name, or if it simply hasn't been implemented yet. See
 # https://bugzilla.mozilla.org/show_bug.cgi?id=552346 for a bit more info.
+#
+# Usage: ./gen_cert.sh [ca|rsa|ecdsa|ed25519|client]...
+#
+# With no arguments, generates the CA and every certificate/key pair.
 
 CA_SUBJECT="/C=US/ST=CA/O=Rocket CA/CN=Rocket Root CA"
 SUBJECT="/C=US/ST=CA/O=Rocket/CN=localhost"
 ALT="DNS:localhost"
+CLIENT_SUBJECT="/C=US/ST=CA/O=Rocket/CN=client"
+CLIENT_ALT="email:client@rocket.rs"
+UNTRUSTED_CA_SUBJECT="/C=US/ST=CA/O=Untrusted CA/CN=Untrusted Root CA"
 
-openssl genrsa -out ca_key.pem 4096
-openssl req -new -x509 -days 3650 -key ca_key.pem -subj "${CA_SUBJECT}" -out ca_cert.pem
+function gen_ca() {
+  openssl genrsa -out ca_key.pem 4096
+  openssl req -new -x509 -days 3650 -key ca_key.pem -subj "${CA_SUBJECT}" -out ca_cert.pem
+}
 
-openssl req -newkey rsa:4096 -nodes -sha256 -keyout key.pem -subj "${SUBJECT}" -out server.csr
-openssl x509 -req -sha256 -extfile <(printf "subjectAltName=${ALT}") -days 3650 \
-    -CA ca_cert.pem -CAkey ca_key.pem -CAcreateserial \
-    -in server.csr -out cert.pem
+# Signs the CSR for `$1` (a key file) with the CA, writing the cert to `$2`.
+function sign_cert() {
+  openssl req -new -sha256 -key "$1" -subj "${SUBJECT}" -out server.csr
+  openssl x509 -req -sha256 -extfile <(printf "subjectAltName=${ALT}") -days 3650 \
+      -CA ca_cert.pem -CAkey ca_key.pem -CAcreateserial \
+      -in server.csr -out "$2"
 
-rm ca_cert.srl server.csr
+  rm ca_cert.srl server.csr
+}
+
+# RSA key in PKCS#1 format.
+function gen_rsa() {
+  openssl genrsa -traditional -out key.pem 4096
+  sign_cert key.pem cert.pem
+}
+
+# ECDSA P-256 key in SEC1 (`EC PRIVATE KEY`) format.
+function gen_ecdsa() {
+  openssl ecparam -name prime256v1 -genkey -noout -out ecdsa_key.pem
+  sign_cert ecdsa_key.pem ecdsa_cert.pem
+}
+
+# Ed25519 key in PKCS#8 format.
+function gen_ed25519() {
+  openssl genpkey -algorithm ED25519 -out ed25519_key.pem
+  sign_cert ed25519_key.pem ed25519_cert.pem
+}
+
+# Client certificates for mutual TLS: one signed by the CA and one signed by a
+# throwaway CA that the server doesn't trust. Both use RSA keys in PKCS#1 format.
+function gen_client() {
+  local ext="subjectAltName=${CLIENT_ALT}\nextendedKeyUsage=clientAuth"
+
+  openssl genrsa -traditional -out client_key.pem 2048
+  openssl req -new -sha256 -key client_key.pem -subj "${CLIENT_SUBJECT}" -out client.csr
+  openssl x509 -req -sha256 -extfile <(printf "${ext}") -days 3650 \
+      -CA ca_cert.pem -CAkey ca_key.pem -CAcreateserial \
+      -in client.csr -out client_cert.pem
+
+  openssl genrsa -out untrusted_ca_key.pem 2048
+  openssl req -new -x509 -days 3650 -key untrusted_ca_key.pem \
+      -subj "${UNTRUSTED_CA_SUBJECT}" -out untrusted_ca_cert.pem
+
+  openssl genrsa -traditional -out untrusted_client_key.pem 2048
+  openssl req -new -sha256 -key untrusted_client_key.pem -subj "${CLIENT_SUBJECT}" -out client.csr
+  openssl x509 -req -sha256 -extfile <(printf "${ext}") -days 3650 \
+      -CA untrusted_ca_cert.pem -CAkey untrusted_ca_key.pem -CAcreateserial \
+      -in client.csr -out untrusted_client_cert.pem
+
+  rm ca_cert.srl untrusted_ca_cert.srl untrusted_ca_key.pem untrusted_ca_cert.pem client.csr
+}
+
+for kind in ${@:-ca rsa ecdsa ed25519 client}; do
+  "gen_${kind}"
+done
@@ -86,6 +86,7 @@ if [ "$1" = "--contrib" ]; then
     handlebars_templates
     serve
     helmet
+    websocket
     diesel_postgres_pool
     diesel_sqlite_pool
     diesel_mysql_pool
@@ -112,6 +113,7 @@ elif [ "$1" = "--core" ]; then
   FEATURES=(
     secrets
     tls
+    mtls
   )
 
   pushd "${CORE_LIB_ROOT}" > /dev/null 2>&1